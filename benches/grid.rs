@@ -0,0 +1,104 @@
+//! Grid functions against the scalar loop over the same points, at a fine step (many samples
+//! per lattice cell) and a coarse one (about one sample per cell).
+//! Run with `cargo bench --bench grid`.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod bench {
+    use criterion::{black_box, criterion_group, BenchmarkId, Criterion, Throughput};
+    use terrainopensimplex2::{fast, smooth};
+
+    const SEED: i64 = 40000345266;
+    const STEPS: [f64; 2] = [0.05, 1.0];
+
+    type Noise2 = fn(i64, f64, f64) -> f32;
+    type Noise3 = fn(i64, f64, f64, f64) -> f32;
+    type Grid2 = fn(i64, [f64; 2], [f64; 2], [usize; 2], &mut [f32]);
+    type Grid3 = fn(i64, [f64; 3], [f64; 3], [usize; 3], &mut [f32]);
+
+    fn bench2(c: &mut Criterion, name: &str, scalar: Noise2, grid: Grid2) {
+        let origin = [-13.37, 4.2];
+        let size = [64, 64];
+        let mut out = vec![0.0; size[0] * size[1]];
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Elements(out.len() as u64));
+        for step in STEPS {
+            group.bench_function(BenchmarkId::new("scalar", step), |b| {
+                b.iter(|| {
+                    for j in 0..size[1] {
+                        for i in 0..size[0] {
+                            let x = origin[0] + i as f64 * step;
+                            let y = origin[1] + j as f64 * step;
+                            out[i + size[0] * j] = scalar(SEED, black_box(x), black_box(y));
+                        }
+                    }
+                    black_box(&out);
+                })
+            });
+            group.bench_function(BenchmarkId::new("grid", step), |b| {
+                b.iter(|| {
+                    grid(SEED, black_box(origin), [step; 2], size, &mut out);
+                    black_box(&out);
+                })
+            });
+        }
+        group.finish();
+    }
+
+    fn bench3(c: &mut Criterion, name: &str, scalar: Noise3, grid: Grid3) {
+        let origin = [-2.5, 31.75, 7.0];
+        let size = [16, 16, 16];
+        let mut out = vec![0.0; size[0] * size[1] * size[2]];
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Elements(out.len() as u64));
+        for step in STEPS {
+            group.bench_function(BenchmarkId::new("scalar", step), |b| {
+                b.iter(|| {
+                    for k in 0..size[2] {
+                        for j in 0..size[1] {
+                            for i in 0..size[0] {
+                                let x = origin[0] + i as f64 * step;
+                                let y = origin[1] + j as f64 * step;
+                                let z = origin[2] + k as f64 * step;
+                                out[i + size[0] * (j + size[1] * k)] =
+                                    scalar(SEED, black_box(x), black_box(y), black_box(z));
+                            }
+                        }
+                    }
+                    black_box(&out);
+                })
+            });
+            group.bench_function(BenchmarkId::new("grid", step), |b| {
+                b.iter(|| {
+                    grid(SEED, black_box(origin), [step; 3], size, &mut out);
+                    black_box(&out);
+                })
+            });
+        }
+        group.finish();
+    }
+
+    fn grid(c: &mut Criterion) {
+        bench2(c, "fast::noise2", fast::noise2, fast::noise2_Grid);
+        bench3(
+            c,
+            "fast::noise3_ImproveXZ",
+            fast::noise3_ImproveXZ,
+            fast::noise3_ImproveXZ_Grid,
+        );
+        bench2(c, "smooth::noise2", smooth::noise2, smooth::noise2_Grid);
+        bench3(
+            c,
+            "smooth::noise3_ImproveXZ",
+            smooth::noise3_ImproveXZ,
+            smooth::noise3_ImproveXZ_Grid,
+        );
+    }
+
+    criterion_group!(benches, grid);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+criterion::criterion_main!(bench::benches);
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,111 @@
+//! Lane functions against the scalar loop they replace, on the same batch of points.
+//! Run with `cargo bench --bench lanes`.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod bench {
+    use criterion::{black_box, criterion_group, BenchmarkId, Criterion, Throughput};
+    use terrainopensimplex2::{fast, smooth};
+
+    const SEED: i64 = 40000345266;
+    const POINTS: usize = 4096;
+
+    type Noise2 = fn(i64, f64, f64) -> f32;
+    type Noise3 = fn(i64, f64, f64, f64) -> f32;
+    type Lanes2 = fn(i64, [f64; 8], [f64; 8]) -> [f32; 8];
+    type Lanes3 = fn(i64, [f64; 8], [f64; 8], [f64; 8]) -> [f32; 8];
+
+    /// Terrain-sized coordinates from a fixed xorshift stream.
+    fn points() -> Vec<[f64; 3]> {
+        let mut state = 0x9E3779B97F4A7C15u64;
+        let mut coordinate = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * 200.0
+        };
+        (0..POINTS)
+            .map(|_| [coordinate(), coordinate(), coordinate()])
+            .collect()
+    }
+
+    fn bench2(c: &mut Criterion, name: &str, scalar: Noise2, lanes: Lanes2) {
+        let points = points();
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Elements(POINTS as u64));
+        group.bench_function(BenchmarkId::new("scalar", POINTS), |b| {
+            b.iter(|| {
+                points
+                    .iter()
+                    .map(|&[x, y, _]| scalar(SEED, black_box(x), black_box(y)))
+                    .sum::<f32>()
+            })
+        });
+        group.bench_function(BenchmarkId::new("lanes8", POINTS), |b| {
+            b.iter(|| {
+                points
+                    .chunks_exact(8)
+                    .map(|chunk| {
+                        let x = std::array::from_fn(|l| chunk[l][0]);
+                        let y = std::array::from_fn(|l| chunk[l][1]);
+                        lanes(SEED, black_box(x), black_box(y)).iter().sum::<f32>()
+                    })
+                    .sum::<f32>()
+            })
+        });
+        group.finish();
+    }
+
+    fn bench3(c: &mut Criterion, name: &str, scalar: Noise3, lanes: Lanes3) {
+        let points = points();
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Elements(POINTS as u64));
+        group.bench_function(BenchmarkId::new("scalar", POINTS), |b| {
+            b.iter(|| {
+                points
+                    .iter()
+                    .map(|&[x, y, z]| scalar(SEED, black_box(x), black_box(y), black_box(z)))
+                    .sum::<f32>()
+            })
+        });
+        group.bench_function(BenchmarkId::new("lanes8", POINTS), |b| {
+            b.iter(|| {
+                points
+                    .chunks_exact(8)
+                    .map(|chunk| {
+                        let x = std::array::from_fn(|l| chunk[l][0]);
+                        let y = std::array::from_fn(|l| chunk[l][1]);
+                        let z = std::array::from_fn(|l| chunk[l][2]);
+                        lanes(SEED, black_box(x), black_box(y), black_box(z))
+                            .iter()
+                            .sum::<f32>()
+                    })
+                    .sum::<f32>()
+            })
+        });
+        group.finish();
+    }
+
+    fn lanes(c: &mut Criterion) {
+        bench2(c, "fast::noise2", fast::noise2, fast::noise2_Lanes::<8>);
+        bench3(
+            c,
+            "fast::noise3_ImproveXZ",
+            fast::noise3_ImproveXZ,
+            fast::noise3_ImproveXZ_Lanes::<8>,
+        );
+        bench2(
+            c,
+            "smooth::noise2",
+            smooth::noise2,
+            smooth::noise2_Lanes::<8>,
+        );
+    }
+
+    criterion_group!(benches, lanes);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+criterion::criterion_main!(bench::benches);
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
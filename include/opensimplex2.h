@@ -0,0 +1,264 @@
+/* OpenSimplex2 C API. Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#ifndef OPENSIMPLEX2_H
+#define OPENSIMPLEX2_H
+
+#include <stddef.h>
+
+typedef struct OpenSimplex2 OpenSimplex2;
+
+#define OPENSIMPLEX2_OK 0
+
+#define OPENSIMPLEX2_ERROR_NULL_POINTER -1
+
+#define OPENSIMPLEX2_ERROR_BAD_LENGTH -2
+
+#define OPENSIMPLEX2_ERROR_BAD_ARGUMENT -3
+
+// The library broke one of its own invariants; a bug rather than a bad call.
+#define OPENSIMPLEX2_ERROR_INTERNAL -4
+
+// The voxel mesh of one generated terrain. Sizes and buffers are read from the same mesh, so
+// the terrain is generated once however many calls read it.
+typedef struct OpenSimplex2TerrainMesh OpenSimplex2TerrainMesh;
+
+// Settings of the viewer's terrain. The viewer's mesh mode, skirts, volume, color ramp, water
+// and thread count are not exposed: meshes are always stacked cubes in the shaded base color,
+// without water, built on one thread per core.
+//
+// Every float must be finite, and cube_size and failoff positive. The terrain may be at most
+// 65536 columns along each axis and 4096 x 4096 in all, max_height at most 4096 cubes, and
+// fractal_octaves at most 64. Other settings give OPENSIMPLEX2_ERROR_BAD_ARGUMENT.
+typedef struct OpenSimplex2TerrainConfig {
+  float tot_width;
+  float tot_depth;
+  long long seed;
+  float cube_size;
+  unsigned char color[3];
+  float max_height;
+  float failoff;
+  double z;
+  int fractal_octaves;
+  float fractal_amplitude;
+  double fractal_frequency;
+  // 0 fbm, 1 ridged, 2 billow, 3 hybrid multifractal, 4 swiss, 5 jordan.
+  int fractal_type;
+  // Nested domain warps, from 0 (no warping) to 2.
+  unsigned int warp_levels;
+  long long warp_seed;
+  double warp_strength;
+  double warp_frequency;
+  // 0 fast, 1 smooth.
+  int noise_algorithm;
+  // 0 ImproveX, 1 ImproveXY, 2 ImproveXZ, 3 ImproveXYZ, 4 ImproveXY_ImproveZW, 5 Fallback.
+  int noise_orientation;
+  // Noise module graph replacing the fractal and height curve, as NUL-terminated JSON in
+  // the format of the REST API's /api/terrain/graph. NULL for none.
+  const char *graph;
+} OpenSimplex2TerrainConfig;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Builds the fast and smooth lookup tables ahead of the first noise call.
+void opensimplex2_warm_up(void);
+
+float opensimplex2_fast_noise2(long long seed, double x, double y);
+
+float opensimplex2_fast_noise2_ImproveX(long long seed, double x, double y);
+
+float opensimplex2_fast_noise3_ImproveXY(long long seed, double x, double y, double z);
+
+float opensimplex2_fast_noise3_ImproveXZ(long long seed, double x, double y, double z);
+
+float opensimplex2_fast_noise3_Fallback(long long seed, double x, double y, double z);
+
+float opensimplex2_fast_noise4_ImproveXYZ_ImproveXY(long long seed,
+                                                    double x,
+                                                    double y,
+                                                    double z,
+                                                    double w);
+
+float opensimplex2_fast_noise4_ImproveXYZ_ImproveXZ(long long seed,
+                                                    double x,
+                                                    double y,
+                                                    double z,
+                                                    double w);
+
+float opensimplex2_fast_noise4_ImproveXYZ(long long seed, double x, double y, double z, double w);
+
+float opensimplex2_fast_noise4_ImproveXY_ImproveZW(long long seed,
+                                                   double x,
+                                                   double y,
+                                                   double z,
+                                                   double w);
+
+float opensimplex2_fast_noise4_Fallback(long long seed, double x, double y, double z, double w);
+
+float opensimplex2_smooth_noise2(long long seed, double x, double y);
+
+float opensimplex2_smooth_noise2_ImproveX(long long seed, double x, double y);
+
+float opensimplex2_smooth_noise3_ImproveXY(long long seed, double x, double y, double z);
+
+float opensimplex2_smooth_noise3_ImproveXZ(long long seed, double x, double y, double z);
+
+float opensimplex2_smooth_noise3_Fallback(long long seed, double x, double y, double z);
+
+float opensimplex2_smooth_noise4_ImproveXYZ_ImproveXY(long long seed,
+                                                      double x,
+                                                      double y,
+                                                      double z,
+                                                      double w);
+
+float opensimplex2_smooth_noise4_ImproveXYZ_ImproveXZ(long long seed,
+                                                      double x,
+                                                      double y,
+                                                      double z,
+                                                      double w);
+
+float opensimplex2_smooth_noise4_ImproveXYZ(long long seed, double x, double y, double z, double w);
+
+float opensimplex2_smooth_noise4_ImproveXY_ImproveZW(long long seed,
+                                                     double x,
+                                                     double y,
+                                                     double z,
+                                                     double w);
+
+float opensimplex2_smooth_noise4_Fallback(long long seed, double x, double y, double z, double w);
+
+// `distance`: 0 Euclidean, 1 Manhattan, 2 Chebyshev.
+// `output`: 0 F1, 1 F2, 2 F2 - F1, 3 cell id.
+// Returns NaN for any other code, and for coordinates that are not finite.
+float opensimplex2_cellular_noise2(long long seed, double x, double y, int distance, int output);
+
+// Same codes as `opensimplex2_cellular_noise2`.
+float opensimplex2_cellular_noise3(long long seed,
+                                   double x,
+                                   double y,
+                                   double z,
+                                   int distance,
+                                   int output);
+
+// `variant`: 0 fast, 1 smooth.
+// Returns NULL for any other code. Release the handle with `opensimplex2_destroy`.
+OpenSimplex2 *opensimplex2_create(long long seed, int variant);
+
+// Frees a handle from `opensimplex2_create`. NULL is ignored.
+//
+// # Safety
+// `handle` must be NULL or a live handle, and must not be used afterwards.
+void opensimplex2_destroy(OpenSimplex2 *handle);
+
+// Scales every input coordinate before sampling. Defaults to 1.
+//
+// # Safety
+// `handle` must be NULL or a live handle.
+int opensimplex2_set_frequency(OpenSimplex2 *handle, double frequency);
+
+// Added to every scaled input coordinate before sampling. Defaults to 0.
+//
+// # Safety
+// `handle` must be NULL or a live handle.
+int opensimplex2_set_offset(OpenSimplex2 *handle, double x, double y, double z, double w);
+
+// Fills `out` with `size_x * size_y` samples starting at (`x`, `y`).
+// `orientation`: 0 standard, 1 ImproveX.
+//
+// # Safety
+// `handle` must be NULL or a live handle, and `out` NULL or valid for `len` writes.
+int opensimplex2_fill_grid2(const OpenSimplex2 *handle,
+                            float *out,
+                            size_t len,
+                            int orientation,
+                            double x,
+                            double y,
+                            double step_x,
+                            double step_y,
+                            size_t size_x,
+                            size_t size_y);
+
+// Fills `out` with `size_x * size_y * size_z` samples starting at (`x`, `y`, `z`).
+// `orientation`: 0 ImproveXY, 1 ImproveXZ, 2 Fallback.
+//
+// # Safety
+// `handle` must be NULL or a live handle, and `out` NULL or valid for `len` writes.
+int opensimplex2_fill_grid3(const OpenSimplex2 *handle,
+                            float *out,
+                            size_t len,
+                            int orientation,
+                            double x,
+                            double y,
+                            double z,
+                            double step_x,
+                            double step_y,
+                            double step_z,
+                            size_t size_x,
+                            size_t size_y,
+                            size_t size_z);
+
+// The viewer's starting terrain.
+struct OpenSimplex2TerrainConfig opensimplex2_terrain_default_config(void);
+
+// Writes the number of columns along the width and depth.
+//
+// # Safety
+// Every pointer must be NULL or valid, and `config->graph` NULL or a valid string.
+int opensimplex2_terrain_size(const struct OpenSimplex2TerrainConfig *config,
+                              size_t *size_x,
+                              size_t *size_y);
+
+// Fills `out` with `size_x * size_y` column heights.
+//
+// # Safety
+// `config` must be NULL or valid, `config->graph` NULL or a valid string, and `out` NULL or
+// valid for `len` writes.
+int opensimplex2_terrain_heights(const struct OpenSimplex2TerrainConfig *config,
+                                 float *out,
+                                 size_t len);
+
+// Generates the configured terrain and meshes it into a new handle written to `*mesh`.
+// Release it with `opensimplex2_terrain_mesh_destroy`. A noise graph can raise columns past
+// the max_height limit; any column more than 4096 cubes high, or not finite, gives
+// OPENSIMPLEX2_ERROR_BAD_ARGUMENT.
+//
+// # Safety
+// `config` must be NULL or valid, `config->graph` NULL or a valid string, and `mesh` NULL or
+// valid for one write.
+int opensimplex2_terrain_mesh_create(const struct OpenSimplex2TerrainConfig *config,
+                                     struct OpenSimplex2TerrainMesh **mesh);
+
+// Frees a handle from `opensimplex2_terrain_mesh_create`. NULL is ignored.
+//
+// # Safety
+// `mesh` must be NULL or a live handle, and must not be used afterwards.
+void opensimplex2_terrain_mesh_destroy(struct OpenSimplex2TerrainMesh *mesh);
+
+// Writes the vertex and index counts of the mesh.
+//
+// # Safety
+// Every pointer must be NULL or valid, and `mesh` a live handle if not NULL.
+int opensimplex2_terrain_mesh_size(const struct OpenSimplex2TerrainMesh *mesh,
+                                   size_t *vertex_count,
+                                   size_t *index_count);
+
+// Fills the mesh buffers: `3 * vertex_count` positions, `index_count` indices and
+// `4 * vertex_count` colors. NULL buffers are skipped.
+//
+// # Safety
+// `mesh` must be NULL or a live handle, and each buffer NULL or valid for its length in
+// writes.
+int opensimplex2_terrain_mesh(const struct OpenSimplex2TerrainMesh *mesh,
+                              float *positions,
+                              size_t positions_len,
+                              unsigned int *indices,
+                              size_t indices_len,
+                              unsigned char *colors,
+                              size_t colors_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* OPENSIMPLEX2_H */
@@ -0,0 +1,165 @@
+/*!
+    Turns control panel updates into configuration messages without HTTP, for the in-page
+    WebAssembly viewer. Endpoints, values and validation match the REST routes, so the same
+    control panel drives both builds.
+*/
+
+use crate::color_ramp::ColorRamp;
+use crate::configuration::ConfigurationMessage;
+use crate::domain_warp::DomainWarp;
+use crate::fractal::FractalType;
+use crate::mesh_mode::MeshMode;
+use crate::noise_graph::NoiseModule;
+use crate::noise_source::{Algorithm, Orientation};
+use crate::terrain_configuration::TerrainConfiguration;
+use serde::de::DeserializeOwned;
+
+/**
+    `endpoint` is a REST path such as "/api/terrain/width"; anything before "/api/" (the
+    panel's server URL) is ignored. `value` is the JSON encoding of the request's `value` field.
+*/
+pub fn message(endpoint: &str, value: &str) -> Result<ConfigurationMessage, String> {
+    let path = endpoint
+        .rsplit_once("/api/")
+        .map_or(endpoint, |(_, path)| path)
+        .trim_matches('/');
+
+    let message = match path {
+        "terrain/width" => ConfigurationMessage::TerrainWidth(parse(value)?),
+        "terrain/depth" => ConfigurationMessage::TerrainDepth(parse(value)?),
+        "terrain/seed" => ConfigurationMessage::TerrainSeed(parse(value)?),
+        "terrain/cubesize" => {
+            let cube_size: f32 = parse(value)?;
+            if cube_size <= 0.0 {
+                return Err("cube size must be positive".to_string());
+            }
+            ConfigurationMessage::TerrainCubeSize(cube_size)
+        }
+        "terrain/color" => ConfigurationMessage::TerrainColor(parse_color(value)?),
+        "terrain/height" => ConfigurationMessage::TerrainMaxHeight(parse(value)?),
+        "terrain/failoff" => ConfigurationMessage::TerrainFailoff(parse(value)?),
+        "terrain/z" => ConfigurationMessage::TerrainZ(parse(value)?),
+        "terrain/fractal/octaves" => ConfigurationMessage::TerrainFractalOctaves(parse(value)?),
+        "terrain/fractal/amplitude" => ConfigurationMessage::TerrainFractalAmplitude(parse(value)?),
+        "terrain/fractal/frequency" => ConfigurationMessage::TerrainFractalFrequency(parse(value)?),
+        "terrain/fractal/type" => ConfigurationMessage::TerrainFractalType(
+            parse::<String>(value)?.parse::<FractalType>()?,
+        ),
+        "terrain/warp/levels" => {
+            let levels: u32 = parse(value)?;
+            if levels > DomainWarp::MAX_LEVELS {
+                return Err(format!(
+                    "warp levels must be at most {}",
+                    DomainWarp::MAX_LEVELS
+                ));
+            }
+            ConfigurationMessage::TerrainWarpLevels(levels)
+        }
+        "terrain/warp/seed" => ConfigurationMessage::TerrainWarpSeed(parse(value)?),
+        "terrain/warp/strength" => ConfigurationMessage::TerrainWarpStrength(parse(value)?),
+        "terrain/warp/frequency" => ConfigurationMessage::TerrainWarpFrequency(parse(value)?),
+        "terrain/noise/algorithm" => ConfigurationMessage::TerrainNoiseAlgorithm(
+            parse::<String>(value)?.parse::<Algorithm>()?,
+        ),
+        "terrain/noise/orientation" => ConfigurationMessage::TerrainNoiseOrientation(
+            parse::<String>(value)?.parse::<Orientation>()?,
+        ),
+        "terrain/graph" => {
+            let graph: Option<NoiseModule> = parse(value)?;
+            if let Some(graph) = &graph {
+                graph.validate()?;
+            }
+            ConfigurationMessage::TerrainGraph(graph)
+        }
+        "terrain/threads" => {
+            let threads: usize = parse(value)?;
+            if threads > TerrainConfiguration::MAX_THREADS {
+                return Err(format!(
+                    "threads must be at most {}",
+                    TerrainConfiguration::MAX_THREADS
+                ));
+            }
+            ConfigurationMessage::TerrainThreads(threads)
+        }
+        "terrain/mode" => {
+            ConfigurationMessage::TerrainMode(parse::<String>(value)?.parse::<MeshMode>()?)
+        }
+        "terrain/skirts" => ConfigurationMessage::TerrainSkirts(parse(value)?),
+        "terrain/colorramp" => {
+            let color_ramp: Option<ColorRamp> = parse(value)?;
+            if let Some(color_ramp) = &color_ramp {
+                color_ramp.validate()?;
+            }
+            ConfigurationMessage::TerrainColorRamp(color_ramp)
+        }
+        "terrain/volume/threshold" => ConfigurationMessage::TerrainVolumeThreshold(parse(value)?),
+        "terrain/volume/cavefrequency" => {
+            ConfigurationMessage::TerrainVolumeCaveFrequency(parse(value)?)
+        }
+        "terrain/volume/verticalbias" => {
+            ConfigurationMessage::TerrainVolumeVerticalBias(parse(value)?)
+        }
+        "terrain/water/level" => ConfigurationMessage::TerrainWaterLevel(parse(value)?),
+        "terrain/water/color" => ConfigurationMessage::TerrainWaterColor(parse_color(value)?),
+        "terrain/water/opacity" => {
+            let opacity: f32 = parse(value)?;
+            if !(0.0..=1.0).contains(&opacity) {
+                return Err("water opacity must be in [0, 1]".to_string());
+            }
+            ConfigurationMessage::TerrainWaterOpacity(opacity)
+        }
+        "terrain/water/seabedcolor" => {
+            ConfigurationMessage::TerrainWaterSeabedColor(parse_color(value)?)
+        }
+        "camera/position/x" => ConfigurationMessage::CameraPositionX(parse(value)?),
+        "camera/position/y" => ConfigurationMessage::CameraPositionY(parse(value)?),
+        "camera/position/z" => ConfigurationMessage::CameraPositionZ(parse(value)?),
+        "camera/fieldview/y" => ConfigurationMessage::CameraFieldViewY(parse(value)?),
+        "camera/far/z" => ConfigurationMessage::CameraZFar(parse(value)?),
+        "camera/target/x" => ConfigurationMessage::CameraTargetX(parse(value)?),
+        "camera/target/y" => ConfigurationMessage::CameraTargetY(parse(value)?),
+        "camera/target/z" => ConfigurationMessage::CameraTargetZ(parse(value)?),
+        "camera/up/x" => ConfigurationMessage::CameraUpX(parse(value)?),
+        "camera/up/y" => ConfigurationMessage::CameraUpY(parse(value)?),
+        "camera/up/z" => ConfigurationMessage::CameraUpZ(parse(value)?),
+        "light/direction/x" => ConfigurationMessage::LightDirectionX(parse(value)?),
+        "light/direction/y" => ConfigurationMessage::LightDirectionY(parse(value)?),
+        "light/direction/z" => ConfigurationMessage::LightDirectionZ(parse(value)?),
+        "light/color" => ConfigurationMessage::LightColor(parse_color(value)?),
+        "light/intensity" => ConfigurationMessage::LightIntensity(parse_intensity(value)?),
+        "light/ambient/intensity" => {
+            ConfigurationMessage::LightAmbientIntensity(parse_intensity(value)?)
+        }
+        _ => return Err(format!("unknown endpoint: {}", endpoint)),
+    };
+    Ok(message)
+}
+
+fn parse<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_str(value).map_err(|e| e.to_string())
+}
+
+/**
+    A color as 6 hex digits, "rrggbb".
+*/
+fn parse_color(value: &str) -> Result<String, String> {
+    let color: String = parse(value)?;
+    let valid = color.len() == 6
+        && (0..6).step_by(2).all(|i| {
+            color
+                .get(i..i + 2)
+                .is_some_and(|c| u8::from_str_radix(c, 16).is_ok())
+        });
+    if !valid {
+        return Err(format!("color must be 6 hex digits: {}", color));
+    }
+    Ok(color)
+}
+
+fn parse_intensity(value: &str) -> Result<f32, String> {
+    let intensity: f32 = parse(value)?;
+    if intensity < 0.0 {
+        return Err("light intensity must not be negative".to_string());
+    }
+    Ok(intensity)
+}
@@ -17,6 +17,7 @@ pub struct CameraConfiguration {
 }
 
 impl CameraConfiguration {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         position_x: f32,
         position_y: f32,
@@ -119,7 +120,7 @@ pub fn update_configuration(
             up_z: value,
             ..camera_configuration
         },
-        None => camera_configuration.clone(),
-        _ => camera_configuration.clone(),
+        None => camera_configuration,
+        _ => camera_configuration,
     }
 }
@@ -0,0 +1,254 @@
+/*!
+    Cellular (Worley/Voronoi) noise, hashed with the same primes and multiplier as `fast`.
+
+    Every lattice cell holds one feature point, jittered around the cell centre within a square
+    (or cube). The search starts with the surrounding 3x3 (or 3x3x3) cells and widens ring by
+    ring while a farther ring could still hold a point closer than the second nearest, so F1
+    and F2 are exact for every distance function. Distances are taken from the position within
+    the sample's cell, so they stay exact and the search stays a few rings wide however far the
+    sample is from the origin.
+*/
+
+use crate::fast::{HASH_MULTIPLIER, PRIME_X, PRIME_Y, PRIME_Z};
+use std::num::Wrapping;
+
+// Largest offset of a feature point from its cell centre, along each axis.
+const JITTER_2D: f64 = 0.43701595;
+const JITTER_3D: f64 = 0.39614353;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceFunction {
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellularOutput {
+    /**
+        Distance to the nearest feature point.
+    */
+    F1,
+    /**
+        Distance to the second nearest feature point.
+    */
+    F2,
+    /**
+        `F2 - F1`, zero along cell borders.
+    */
+    F2MinusF1,
+    /**
+        Value in [-1, 1) shared by every point of the nearest cell.
+    */
+    CellId,
+}
+
+/*
+ * Noise Evaluators
+ */
+
+/**
+    2D cellular noise. Distances are in lattice units. Returns NaN if `x` or `y` is not finite.
+*/
+pub fn cellular2(
+    seed: i64,
+    x: f64,
+    y: f64,
+    distance: DistanceFunction,
+    output: CellularOutput,
+) -> f32 {
+    if !(x.is_finite() && y.is_finite()) {
+        return f32::NAN;
+    }
+    let seed = Wrapping(seed);
+    let (xb, xi) = (x.floor() as i64, x - x.floor());
+    let (yb, yi) = (y.floor() as i64, y - y.floor());
+    let mut nearest = Nearest::new();
+
+    for radius in 0.. {
+        if radius > 1 && !nearest.may_improve(distance, ring_distance(radius, JITTER_2D)) {
+            break;
+        }
+        for i in -radius..=radius {
+            for j in -radius..=radius {
+                if i.abs().max(j.abs()) != radius {
+                    continue;
+                }
+                let cell = [xb.wrapping_add(i), yb.wrapping_add(j)];
+                let ([px, py], hash) = cell2(seed, cell);
+                let d = [i as f64 + px - xi, j as f64 + py - yi];
+                nearest.insert(measure(distance, &d), hash);
+            }
+        }
+    }
+
+    nearest.output(distance, output)
+}
+
+/**
+    3D cellular noise. Distances are in lattice units. Returns NaN if `x`, `y` or `z` is not
+    finite.
+*/
+pub fn cellular3(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    distance: DistanceFunction,
+    output: CellularOutput,
+) -> f32 {
+    if !(x.is_finite() && y.is_finite() && z.is_finite()) {
+        return f32::NAN;
+    }
+    let seed = Wrapping(seed);
+    let (xb, xi) = (x.floor() as i64, x - x.floor());
+    let (yb, yi) = (y.floor() as i64, y - y.floor());
+    let (zb, zi) = (z.floor() as i64, z - z.floor());
+    let mut nearest = Nearest::new();
+
+    for radius in 0.. {
+        if radius > 1 && !nearest.may_improve(distance, ring_distance(radius, JITTER_3D)) {
+            break;
+        }
+        for i in -radius..=radius {
+            for j in -radius..=radius {
+                for k in -radius..=radius {
+                    if i.abs().max(j.abs()).max(k.abs()) != radius {
+                        continue;
+                    }
+                    let cell = [xb.wrapping_add(i), yb.wrapping_add(j), zb.wrapping_add(k)];
+                    let ([px, py, pz], hash) = cell3(seed, cell);
+                    let d = [i as f64 + px - xi, j as f64 + py - yi, k as f64 + pz - zi];
+                    nearest.insert(measure(distance, &d), hash);
+                }
+            }
+        }
+    }
+
+    nearest.output(distance, output)
+}
+
+/**
+    Feature point of the 2D cell whose lowest corner is `cell`.
+*/
+pub fn feature_point2(seed: i64, cell: [i64; 2]) -> [f64; 2] {
+    let offset = cell2(Wrapping(seed), cell).0;
+    [0, 1].map(|axis| cell[axis] as f64 + offset[axis])
+}
+
+/**
+    Feature point of the 3D cell whose lowest corner is `cell`.
+*/
+pub fn feature_point3(seed: i64, cell: [i64; 3]) -> [f64; 3] {
+    let offset = cell3(Wrapping(seed), cell).0;
+    [0, 1, 2].map(|axis| cell[axis] as f64 + offset[axis])
+}
+
+/*
+ * Utility
+ */
+
+/**
+    Feature point of a 2D cell, relative to its lowest corner, and the cell's hash.
+*/
+fn cell2(seed: Wrapping<i64>, [x, y]: [i64; 2]) -> ([f64; 2], i64) {
+    let hash = hash(seed ^ (Wrapping(x) * Wrapping(PRIME_X)) ^ (Wrapping(y) * Wrapping(PRIME_Y)));
+    let point = [16, 32].map(|shift| 0.5 + unit(hash, shift) * JITTER_2D);
+    (point, hash)
+}
+
+/**
+    Feature point of a 3D cell, relative to its lowest corner, and the cell's hash.
+*/
+fn cell3(seed: Wrapping<i64>, [x, y, z]: [i64; 3]) -> ([f64; 3], i64) {
+    let hash = hash(
+        seed ^ (Wrapping(x) * Wrapping(PRIME_X))
+            ^ (Wrapping(y) * Wrapping(PRIME_Y))
+            ^ (Wrapping(z) * Wrapping(PRIME_Z)),
+    );
+    let point = [8, 24, 40].map(|shift| 0.5 + unit(hash, shift) * JITTER_3D);
+    (point, hash)
+}
+
+struct Nearest {
+    f1: f64,
+    f2: f64,
+    hash: i64,
+}
+
+impl Nearest {
+    fn new() -> Self {
+        Self {
+            f1: f64::INFINITY,
+            f2: f64::INFINITY,
+            hash: 0,
+        }
+    }
+
+    fn insert(&mut self, d: f64, hash: i64) {
+        if d < self.f1 {
+            self.f2 = self.f1;
+            self.f1 = d;
+            self.hash = hash;
+        } else if d < self.f2 {
+            self.f2 = d;
+        }
+    }
+
+    /**
+        Whether a point at `bound` or farther, in the units of `measure`, could still be one of
+        the nearest two.
+    */
+    fn may_improve(&self, distance: DistanceFunction, bound: f64) -> bool {
+        let bound = match distance {
+            DistanceFunction::Euclidean => bound * bound,
+            DistanceFunction::Manhattan | DistanceFunction::Chebyshev => bound,
+        };
+        bound < self.f2
+    }
+
+    fn output(&self, distance: DistanceFunction, output: CellularOutput) -> f32 {
+        let finish = |d: f64| match distance {
+            DistanceFunction::Euclidean => d.sqrt(),
+            DistanceFunction::Manhattan | DistanceFunction::Chebyshev => d,
+        };
+        let value = match output {
+            CellularOutput::F1 => finish(self.f1),
+            CellularOutput::F2 => finish(self.f2),
+            CellularOutput::F2MinusF1 => finish(self.f2) - finish(self.f1),
+            CellularOutput::CellId => (self.hash >> 32) as f64 / 2147483648.0,
+        };
+        value as f32
+    }
+}
+
+/**
+    Least distance along one axis from any point of a cell to the feature points of the cells
+    `radius` cells away, which holds for every distance function.
+*/
+fn ring_distance(radius: i64, jitter: f64) -> f64 {
+    radius as f64 - 0.5 - jitter
+}
+
+/**
+    Squared for Euclidean, so the square root is only taken once per sample.
+*/
+fn measure(distance: DistanceFunction, d: &[f64]) -> f64 {
+    match distance {
+        DistanceFunction::Euclidean => d.iter().map(|v| v * v).sum(),
+        DistanceFunction::Manhattan => d.iter().map(|v| v.abs()).sum(),
+        DistanceFunction::Chebyshev => d.iter().fold(0.0, |m, v| v.abs().max(m)),
+    }
+}
+
+fn hash(hash: Wrapping<i64>) -> i64 {
+    let hash = hash * Wrapping(HASH_MULTIPLIER);
+    (hash ^ (hash >> 28)).0
+}
+
+/**
+    16 bits of the hash starting at `shift`, mapped to [-1, 1].
+*/
+fn unit(hash: i64, shift: u32) -> f64 {
+    ((hash >> shift) & 0xFFFF) as f64 * (2.0 / 65535.0) - 1.0
+}
@@ -0,0 +1,239 @@
+/*!
+    Terrain colors from ordered stops keyed by normalised height, such as sand, grass, rock and
+    snow, with an optional color that takes over on steep slopes.
+
+    A ramp is plain serde data, so it can be sent as JSON over the REST API:
+
+    ```json
+    {
+        "stops": [
+            { "height": 0.0, "color": "c2b280" },
+            { "height": 0.1, "color": "4f7a3a" },
+            { "height": 0.55, "color": "7a746b" },
+            { "height": 0.8, "color": "f4f4f4" }
+        ],
+        "slope": { "start": 0.4, "end": 0.6, "color": "6e6a64" }
+    }
+    ```
+*/
+
+use serde::{Deserialize, Serialize};
+use std::f32::consts::FRAC_PI_2;
+use three_d::{Srgba, Vec3};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorRamp {
+    /**
+        Stops in ascending height order.
+    */
+    pub stops: Vec<ColorStop>,
+    #[serde(default)]
+    pub slope: Option<SlopeColor>,
+    /**
+        Blend between neighbouring stops, or switch to a stop's color at its height.
+    */
+    #[serde(default = "blend")]
+    pub blend: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorStop {
+    /**
+        0 for the ground, 1 for the highest column the configuration can produce.
+    */
+    pub height: f32,
+    /**
+        6 hex digits, "rrggbb".
+    */
+    pub color: String,
+}
+
+/**
+    Color blended in as the slope rises from `start` to `end`, both between 0 for flat ground
+    and 1 for a vertical wall.
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlopeColor {
+    pub start: f32,
+    pub end: f32,
+    pub color: String,
+}
+
+fn blend() -> bool {
+    true
+}
+
+impl ColorRamp {
+    /**
+        Checks what serde cannot: at least one stop, heights ascending within [0, 1], a slope
+        range within [0, 1] and every color 6 hex digits.
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        if self.stops.is_empty() {
+            return Err("color ramp needs at least one stop".to_string());
+        }
+        for stop in &self.stops {
+            if !(0.0..=1.0).contains(&stop.height) {
+                return Err(format!(
+                    "stop height must be in [0, 1], got {}",
+                    stop.height
+                ));
+            }
+            parse_color(&stop.color)?;
+        }
+        if self.stops.windows(2).any(|w| w[0].height > w[1].height) {
+            return Err("stops must be in ascending height order".to_string());
+        }
+        if let Some(slope) = &self.slope {
+            if !(0.0 <= slope.start && slope.start < slope.end && slope.end <= 1.0) {
+                return Err(format!(
+                    "slope range must satisfy 0 <= start < end <= 1, got {} to {}",
+                    slope.start, slope.end
+                ));
+            }
+            parse_color(&slope.color)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_color(color: &str) -> Result<[f32; 3], String> {
+    let channel = |i: usize| {
+        color
+            .get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .map(f32::from)
+    };
+    match (color.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(red), Some(green), Some(blue)) => Ok([red, green, blue]),
+        _ => Err(format!("color must be 6 hex digits: {}", color)),
+    }
+}
+
+/**
+    How the meshes color the terrain: the base color lightened with height, as the viewer has
+    always drawn it, or a color ramp.
+*/
+#[derive(Debug, Clone)]
+pub struct Palette {
+    base: [u8; 3],
+    ramp: Option<Ramp>,
+    seabed: Option<(f32, [u8; 3])>,
+}
+
+#[derive(Debug, Clone)]
+struct Ramp {
+    stops: Vec<(f32, [f32; 3])>,
+    slope: Option<(f32, f32, [f32; 3])>,
+    blend: bool,
+    top: f32,
+}
+
+impl Palette {
+    pub fn shaded(base: [u8; 3]) -> Self {
+        Self {
+            base,
+            ramp: None,
+            seabed: None,
+        }
+    }
+
+    /**
+        Colors from `ramp`, with heights normalised by `top`, the height of the highest column.
+    */
+    pub fn ramp(ramp: &ColorRamp, top: f32) -> Result<Self, String> {
+        ramp.validate()?;
+        let stops = ramp
+            .stops
+            .iter()
+            .map(|stop| Ok((stop.height, parse_color(&stop.color)?)))
+            .collect::<Result<_, String>>()?;
+        let slope = match &ramp.slope {
+            Some(slope) => Some((slope.start, slope.end, parse_color(&slope.color)?)),
+            None => None,
+        };
+        Ok(Self {
+            base: [0; 3],
+            ramp: Some(Ramp {
+                stops,
+                slope,
+                blend: ramp.blend,
+                top,
+            }),
+            seabed: None,
+        })
+    }
+
+    /**
+        The same palette with everything lower than `level` in `color`.
+    */
+    pub fn with_seabed(self, level: f32, color: [u8; 3]) -> Self {
+        Self {
+            seabed: Some((level, color)),
+            ..self
+        }
+    }
+
+    /**
+        Color of the surface at `height` with `slope` (see `slope`). `shading` drives the
+        classic shading and is the height for smooth surfaces.
+    */
+    pub fn color(&self, shading: f32, height: f32, slope: f32) -> Srgba {
+        if let Some((level, [red, green, blue])) = self.seabed {
+            if height < level {
+                return Srgba::new(red, green, blue, 255);
+            }
+        }
+        let Some(ramp) = &self.ramp else {
+            return shade(self.base, shading);
+        };
+        let t = if ramp.top > 0.0 {
+            (height / ramp.top).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let after = ramp.stops.partition_point(|&(height, _)| height <= t);
+        let mut color = match (after, ramp.stops.get(after)) {
+            (0, _) => ramp.stops[0].1,
+            (_, None) => ramp.stops[after - 1].1,
+            (_, Some(&(h1, c1))) => {
+                let (h0, c0) = ramp.stops[after - 1];
+                if ramp.blend {
+                    mix(c0, c1, (t - h0) / (h1 - h0))
+                } else {
+                    c0
+                }
+            }
+        };
+        if let Some((start, end, steep)) = ramp.slope {
+            color = mix(
+                color,
+                steep,
+                ((slope - start) / (end - start)).clamp(0.0, 1.0),
+            );
+        }
+        let [red, green, blue] = color.map(|c| c.round() as u8);
+        Srgba::new(red, green, blue, 255)
+    }
+}
+
+fn mix(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+/**
+    Steepness of a surface with unit `normal`: 0 for flat ground, 1 for a vertical wall or
+    anything facing further down, linear in the angle.
+*/
+pub fn slope(normal: Vec3) -> f32 {
+    (normal.y.clamp(-1.0, 1.0).acos() / FRAC_PI_2).min(1.0)
+}
+
+/**
+    The viewer's shading: `color` lightened in proportion to `t`, roughly a height.
+*/
+fn shade(color: [u8; 3], t: f32) -> Srgba {
+    let [red, green, blue] = color.map(|c| (c as f32 + 0.25 + (0.45 * t) * 50.0) as u8);
+    Srgba::new(red, green, blue, 255)
+}
@@ -1,3 +1,9 @@
+use crate::color_ramp::ColorRamp;
+use crate::fractal::FractalType;
+use crate::mesh_mode::MeshMode;
+use crate::noise_graph::NoiseModule;
+use crate::noise_source::{Algorithm, Orientation};
+
 #[derive(Debug, Clone)]
 pub enum ConfigurationMessage {
     TerrainWidth(f32),
@@ -11,6 +17,25 @@ pub enum ConfigurationMessage {
     TerrainFractalOctaves(i32),
     TerrainFractalAmplitude(f32),
     TerrainFractalFrequency(f64),
+    TerrainFractalType(FractalType),
+    TerrainWarpLevels(u32),
+    TerrainWarpSeed(i64),
+    TerrainWarpStrength(f64),
+    TerrainWarpFrequency(f64),
+    TerrainNoiseAlgorithm(Algorithm),
+    TerrainNoiseOrientation(Orientation),
+    TerrainGraph(Option<NoiseModule>),
+    TerrainThreads(usize),
+    TerrainMode(MeshMode),
+    TerrainSkirts(bool),
+    TerrainColorRamp(Option<ColorRamp>),
+    TerrainVolumeThreshold(f32),
+    TerrainVolumeCaveFrequency(f64),
+    TerrainVolumeVerticalBias(f32),
+    TerrainWaterLevel(f32),
+    TerrainWaterColor(String),
+    TerrainWaterOpacity(f32),
+    TerrainWaterSeabedColor(String),
     CameraPositionX(f32),
     CameraPositionY(f32),
     CameraPositionZ(f32),
@@ -22,4 +47,41 @@ pub enum ConfigurationMessage {
     CameraUpX(f32),
     CameraUpY(f32),
     CameraUpZ(f32),
+    LightDirectionX(f32),
+    LightDirectionY(f32),
+    LightDirectionZ(f32),
+    LightColor(String),
+    LightIntensity(f32),
+    LightAmbientIntensity(f32),
+}
+
+impl ConfigurationMessage {
+    /**
+        Whether the terrain has to be rebuilt; camera, light and water surface messages only
+        change the view.
+    */
+    pub fn changes_terrain(&self) -> bool {
+        !matches!(
+            self,
+            ConfigurationMessage::TerrainWaterColor(_)
+                | ConfigurationMessage::TerrainWaterOpacity(_)
+                | ConfigurationMessage::CameraPositionX(_)
+                | ConfigurationMessage::CameraPositionY(_)
+                | ConfigurationMessage::CameraPositionZ(_)
+                | ConfigurationMessage::CameraFieldViewY(_)
+                | ConfigurationMessage::CameraZFar(_)
+                | ConfigurationMessage::CameraTargetX(_)
+                | ConfigurationMessage::CameraTargetY(_)
+                | ConfigurationMessage::CameraTargetZ(_)
+                | ConfigurationMessage::CameraUpX(_)
+                | ConfigurationMessage::CameraUpY(_)
+                | ConfigurationMessage::CameraUpZ(_)
+                | ConfigurationMessage::LightDirectionX(_)
+                | ConfigurationMessage::LightDirectionY(_)
+                | ConfigurationMessage::LightDirectionZ(_)
+                | ConfigurationMessage::LightColor(_)
+                | ConfigurationMessage::LightIntensity(_)
+                | ConfigurationMessage::LightAmbientIntensity(_)
+        )
+    }
 }
@@ -0,0 +1,65 @@
+/*!
+    Domain warping: offsets the horizontal sample coordinates by secondary noise before the
+    main fractal is evaluated, following Inigo Quilez's `fbm(p + fbm(p + fbm(p)))` construction.
+*/
+
+use crate::noise_source::NoiseSource;
+
+// Decorrelates the x and y offsets, and the second level from the first.
+const OFFSETS: [[f64; 2]; 4] = [[0.0, 0.0], [5.2, 1.3], [1.7, 9.2], [8.3, 2.8]];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DomainWarp {
+    /**
+        Number of nested warps, 0 to disable warping.
+    */
+    pub levels: u32,
+    pub seed: i64,
+    /**
+        Largest offset one level can add along each axis, in terrain units.
+    */
+    pub strength: f64,
+    pub frequency: f64,
+}
+
+impl DomainWarp {
+    pub const MAX_LEVELS: u32 = 2;
+
+    pub fn new(levels: u32, seed: i64, strength: f64, frequency: f64) -> Self {
+        Self {
+            levels,
+            seed,
+            strength,
+            frequency,
+        }
+    }
+
+    /**
+        Returns the warped `(x, y)`, sampling a copy of `noise` under the warp's own seed. `z`
+        selects the noise slice and is not displaced.
+    */
+    pub fn apply<N: NoiseSource>(&self, noise: &N, x: f64, y: f64, z: f64) -> (f64, f64) {
+        let noise = noise.with_seed(self.seed);
+        let mut offset = [0.0, 0.0];
+        for level in 0..self.levels.min(Self::MAX_LEVELS) as usize {
+            let px = (x + offset[0]) * self.frequency;
+            let py = (y + offset[1]) * self.frequency;
+            let [ox, oy] = OFFSETS[2 * level];
+            let [qx, qy] = OFFSETS[2 * level + 1];
+            offset = [
+                f64::from(noise.sample3(px + ox, py + oy, z)) * self.strength,
+                f64::from(noise.sample3(px + qx, py + qy, z)) * self.strength,
+            ];
+        }
+        (x + offset[0], y + offset[1])
+    }
+}
+
+impl Default for DomainWarp {
+    /**
+        Disabled, with settings that give a visible warp once levels are raised.
+    */
+    fn default() -> Self {
+        Self::new(0, 0, 1.0, 0.25)
+    }
+}
@@ -2,17 +2,20 @@
     K.jpg's OpenSimplex 2, faster variant
 */
 
-use std::{num::Wrapping, sync::Once};
+// Literals, index arithmetic and the base function signatures follow the Java original.
+#![allow(clippy::excessive_precision, clippy::identity_op, clippy::too_many_arguments)]
 
-const PRIME_X: i64 = 0x5205402B9270C86F;
-const PRIME_Y: i64 = 0x598CD327003817B5;
-const PRIME_Z: i64 = 0x5BCC226E9FA0BACB;
+use std::{num::Wrapping, sync::OnceLock};
+
+pub(crate) const PRIME_X: i64 = 0x5205402B9270C86F;
+pub(crate) const PRIME_Y: i64 = 0x598CD327003817B5;
+pub(crate) const PRIME_Z: i64 = 0x5BCC226E9FA0BACB;
 const PRIME_W: i64 = 0x56CC5227E58F554B;
-const HASH_MULTIPLIER: i64 = 0x53A3F72DEEC546F5;
+pub(crate) const HASH_MULTIPLIER: i64 = 0x53A3F72DEEC546F5;
 const SEED_FLIP_3D: i64 = -0x52D547B2E96ED629;
 const SEED_OFFSET_4D: i64 = 0xE83DC3E0DA7164D;
 
-const ROOT2OVER2: f64 = 0.7071067811865476;
+const ROOT2OVER2: f64 = std::f64::consts::FRAC_1_SQRT_2;
 const SKEW_2D: f64 = 0.366025403784439;
 const UNSKEW_2D: f64 = -0.21132486540518713;
 
@@ -52,7 +55,7 @@ pub fn noise2(seed: i64, x: f64, y: f64) -> f32 {
     let xs = x + s;
     let ys = y + s;
 
-    noise2_UnskewedBase(seed, xs, ys)
+    noise2_UnskewedBase(getGradients(), seed, xs, ys, &mut NoCache)
 }
 
 /**
@@ -67,13 +70,19 @@ pub fn noise2_ImproveX(seed: i64, x: f64, y: f64) -> f32 {
     let xx = x * ROOT2OVER2;
     let yy = y * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D));
 
-    noise2_UnskewedBase(seed, yy + xx, yy - xx)
+    noise2_UnskewedBase(getGradients(), seed, yy + xx, yy - xx, &mut NoCache)
 }
 
 /**
     2D Simplex noise base.
 */
-fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
+fn noise2_UnskewedBase(
+    gradients: &Gradients,
+    seed: i64,
+    xs: f64,
+    ys: f64,
+    cache: &mut impl LatticeCache<2>,
+) -> f32 {
     let seed = Wrapping(seed);
 
     // Get base points and offsets.
@@ -81,6 +90,7 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
     let ysb = fastFloor(ys);
     let xi = (xs - xsb as f64) as f32;
     let yi = (ys - ysb as f64) as f32;
+    cache.enter([xsb, ysb, 0, 0]);
 
     // Prime pre-multiplication for hash.
     let xsbp = Wrapping(xsb as i64) * Wrapping(PRIME_X);
@@ -95,7 +105,8 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
     let mut value = 0.0;
     let a0 = RSQUARED_2D - dx0 * dx0 - dy0 * dy0;
     if a0 > 0.0 {
-        value = (a0 * a0) * (a0 * a0) * grad2(seed, xsbp, ysbp, dx0, dy0);
+        value =
+            (a0 * a0) * (a0 * a0) * grad2Cached(cache, 0, gradients, seed, xsbp, ysbp, dx0, dy0);
     }
 
     // Second vertex.
@@ -106,7 +117,10 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
         let dy1 = dy0 - (1.0 + 2.0 * UNSKEW_2D) as f32;
         value += (a1 * a1)
             * (a1 * a1)
-            * grad2(
+            * grad2Cached(
+                cache,
+                1,
+                gradients,
                 seed,
                 xsbp + Wrapping(PRIME_X),
                 ysbp + Wrapping(PRIME_Y),
@@ -121,14 +135,36 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
         let dy2 = dy0 - (UNSKEW_2D + 1.0) as f32;
         let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
         if a2 > 0.0 {
-            value += (a2 * a2) * (a2 * a2) * grad2(seed, xsbp, ysbp + Wrapping(PRIME_Y), dx2, dy2);
+            value += (a2 * a2)
+                * (a2 * a2)
+                * grad2Cached(
+                    cache,
+                    2,
+                    gradients,
+                    seed,
+                    xsbp,
+                    ysbp + Wrapping(PRIME_Y),
+                    dx2,
+                    dy2,
+                );
         }
     } else {
         let dx2 = dx0 - (UNSKEW_2D + 1.0) as f32;
         let dy2 = dy0 - UNSKEW_2D as f32;
         let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
         if a2 > 0.0 {
-            value += (a2 * a2) * (a2 * a2) * grad2(seed, xsbp + Wrapping(PRIME_X), ysbp, dx2, dy2);
+            value += (a2 * a2)
+                * (a2 * a2)
+                * grad2Cached(
+                    cache,
+                    3,
+                    gradients,
+                    seed,
+                    xsbp + Wrapping(PRIME_X),
+                    ysbp,
+                    dx2,
+                    dy2,
+                );
         }
     }
 
@@ -155,7 +191,7 @@ pub fn noise3_ImproveXY(seed: i64, x: f64, y: f64, z: f64) -> f32 {
     let zr = xy * -ROOT3OVER3 + zz;
 
     // Evaluate both lattices to form a BCC lattice.
-    noise3_UnrotatedBase(seed, xr, yr, zr)
+    noise3_UnrotatedBase(getGradients(), seed, xr, yr, zr, &mut NoCache)
 }
 
 /**
@@ -178,7 +214,7 @@ pub fn noise3_ImproveXZ(seed: i64, x: f64, y: f64, z: f64) -> f32 {
     let yr = xz * -ROOT3OVER3 + yy;
 
     // Evaluate both lattices to form a BCC lattice.
-    noise3_UnrotatedBase(seed, xr, yr, zr)
+    noise3_UnrotatedBase(getGradients(), seed, xr, yr, zr, &mut NoCache)
 }
 
 /**
@@ -195,13 +231,20 @@ pub fn noise3_Fallback(seed: i64, x: f64, y: f64, z: f64) -> f32 {
     let zr = r - z;
 
     // Evaluate both lattices to form a BCC lattice.
-    noise3_UnrotatedBase(seed, xr, yr, zr)
+    noise3_UnrotatedBase(getGradients(), seed, xr, yr, zr, &mut NoCache)
 }
 
 /**
     Generate overlapping cubic lattices for 3D OpenSimplex2 noise.
 */
-fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
+fn noise3_UnrotatedBase(
+    gradients: &Gradients,
+    seed: i64,
+    xr: f64,
+    yr: f64,
+    zr: f64,
+    cache: &mut impl LatticeCache<3>,
+) -> f32 {
     let mut seed = Wrapping(seed);
 
     // Get base points and offsets.
@@ -217,6 +260,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
     let mut yNSign = (-1.0 - yri) as i32 | 1;
     let mut zNSign = (-1.0 - zri) as i32 | 1;
 
+    // The vertices only depend on the rounded point and the octant around it.
+    let octant = (xNSign >> 1 & 1) | (yNSign >> 1 & 2) | (zNSign >> 1 & 4);
+    cache.enter([xrb, yrb, zrb, octant]);
+
     // Compute absolute values, using the above as a shortcut. This was faster in my tests for some reason.
     let mut ax0 = xNSign as f32 * -xri;
     let mut ay0 = yNSign as f32 * -yri;
@@ -233,7 +280,20 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
     for l in 0.. {
         // Closest point on cube.
         if a > 0.0 {
-            value += (a * a) * (a * a) * grad3(seed, xrbp, yrbp, zrbp, xri, yri, zri);
+            value += (a * a)
+                * (a * a)
+                * grad3Cached(
+                    cache,
+                    4 * l + 0,
+                    gradients,
+                    seed,
+                    xrbp,
+                    yrbp,
+                    zrbp,
+                    xri,
+                    yri,
+                    zri,
+                );
         }
 
         // Second-closest point.
@@ -243,7 +303,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
                 b -= 1.0;
                 value += (b * b)
                     * (b * b)
-                    * grad3(
+                    * grad3Cached(
+                        cache,
+                        4 * l + 1,
+                        gradients,
                         seed,
                         xrbp - Wrapping(xNSign as i64) * Wrapping(PRIME_X),
                         yrbp,
@@ -259,7 +322,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
                 b -= 1.0;
                 value += (b * b)
                     * (b * b)
-                    * grad3(
+                    * grad3Cached(
+                        cache,
+                        4 * l + 2,
+                        gradients,
                         seed,
                         xrbp,
                         yrbp - Wrapping(yNSign as i64) * Wrapping(PRIME_Y),
@@ -275,7 +341,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
                 b -= 1.0;
                 value += (b * b)
                     * (b * b)
-                    * grad3(
+                    * grad3Cached(
+                        cache,
+                        4 * l + 3,
+                        gradients,
                         seed,
                         xrbp,
                         yrbp,
@@ -338,7 +407,7 @@ pub fn noise4_ImproveXYZ_ImproveXY(seed: i64, x: f64, y: f64, z: f64, w: f64) ->
     let zr = xy * -0.57735026918962599998 + (zz + ww);
     let wr = z * -0.866025403784439 + ww;
 
-    noise4_UnskewedBase(seed, xr, yr, zr, wr)
+    noise4_UnskewedBase(getGradients(), seed, xr, yr, zr, wr)
 }
 
 /**
@@ -357,7 +426,7 @@ pub fn noise4_ImproveXYZ_ImproveXZ(seed: i64, x: f64, y: f64, z: f64, w: f64) ->
     let yr = xz * -0.57735026918962599998 + (yy + ww);
     let wr = y * -0.866025403784439 + ww;
 
-    noise4_UnskewedBase(seed, xr, yr, zr, wr)
+    noise4_UnskewedBase(getGradients(), seed, xr, yr, zr, wr)
 }
 
 /**
@@ -375,7 +444,7 @@ pub fn noise4_ImproveXYZ(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
     let zs = z + s2;
     let ws = -0.5 * xyz + ww;
 
-    noise4_UnskewedBase(seed, xs, ys, zs, ws)
+    noise4_UnskewedBase(getGradients(), seed, xs, ys, zs, ws)
 }
 
 /**
@@ -391,7 +460,7 @@ pub fn noise4_ImproveXY_ImproveZW(seed: i64, x: f64, y: f64, z: f64, w: f64) ->
     let zs = z + t2;
     let ws = w + t2;
 
-    noise4_UnskewedBase(seed, xs, ys, zs, ws)
+    noise4_UnskewedBase(getGradients(), seed, xs, ys, zs, ws)
 }
 
 /**
@@ -405,13 +474,20 @@ pub fn noise4_Fallback(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
     let zs = z + s;
     let ws = w + s;
 
-    noise4_UnskewedBase(seed, xs, ys, zs, ws)
+    noise4_UnskewedBase(getGradients(), seed, xs, ys, zs, ws)
 }
 
 /**
     4D OpenSimplex2 noise base.
 */
-fn noise4_UnskewedBase(seed: i64, xs: f64, ys: f64, zs: f64, ws: f64) -> f32 {
+fn noise4_UnskewedBase(
+    gradients: &Gradients,
+    seed: i64,
+    xs: f64,
+    ys: f64,
+    zs: f64,
+    ws: f64,
+) -> f32 {
     let mut seed = Wrapping(seed);
 
     // Get base points and offsets
@@ -480,7 +556,7 @@ fn noise4_UnskewedBase(seed: i64, xs: f64, ys: f64, zs: f64, ws: f64) -> f32 {
         if a < RSQUARED_4D {
             a -= RSQUARED_4D;
             a *= a;
-            value += a * a * grad4(seed, xsvp, ysvp, zsvp, wsvp, dx, dy, dz, dw);
+            value += a * a * grad4(gradients, seed, xsvp, ysvp, zsvp, wsvp, dx, dy, dz, dw);
         }
 
         // Break from loop if we're done, skipping updates below.
@@ -510,113 +586,1809 @@ fn noise4_UnskewedBase(seed: i64, xs: f64, ys: f64, zs: f64, ws: f64) -> f32 {
 }
 
 /*
-    Utility
+    Grid Evaluators
+
+    Each function samples its scalar counterpart on a regular grid and writes the
+    results into `out`, X varying fastest: sample (i, j, ...) is taken at
+    (origin[0] + i * step[0], origin[1] + j * step[1], ...) and stored at
+    out[i + size[0] * (j + size[1] * (...))].
+    Results are bit-identical to the scalar functions. The orientation terms that
+    only depend on some of the axes are computed once per row/plane, and the 2D and 3D
+    functions keep the gradients of the lattice cell the previous sample fell in, so
+    neighbouring samples in the same cell skip the hashing for the vertices it already
+    looked up. The 4D functions only share the orientation terms.
+
+    `cargo bench --bench grid` times 64x64 and 16x16x16 grids against the scalar loop.
+    On one x86-64 core, with a step of 0.05 (many samples per cell), noise2_Grid and
+    noise3_ImproveXZ_Grid ran 20-50% faster than the loop; with a step of 1.0 the cache
+    rarely hits and they ran about as fast as it.
 */
 
-fn grad2(seed: Wrapping<i64>, xsvp: Wrapping<i64>, ysvp: Wrapping<i64>, dx: f32, dy: f32) -> f32 {
-    let mut hash = seed ^ xsvp ^ ysvp;
-    hash *= HASH_MULTIPLIER;
-    hash ^= hash.0 >> (64 - N_GRADS_2D_EXPONENT + 1);
-    let gi = (hash.0 as i32 & ((N_GRADS_2D - 1) << 1)) as usize;
-    let grads = &getGradients().gradients2D;
-    grads[gi | 0] * dx + grads[gi | 1] * dy
+/**
+    2D Simplex noise, standard lattice orientation, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise2_Grid(seed: i64, origin: [f64; 2], step: [f64; 2], size: [usize; 2], out: &mut [f32]) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let mut cache = CellCache::new();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+
+    for (row, &y) in out.chunks_exact_mut(size[0].max(1)).zip(&ys) {
+        for (value, &x) in row.iter_mut().zip(&xs) {
+            let s = SKEW_2D * (x + y);
+            *value = noise2_UnskewedBase(gradients, seed, x + s, y + s, &mut cache);
+        }
+    }
 }
 
-fn grad3(
-    seed: Wrapping<i64>,
-    xrvp: Wrapping<i64>,
-    yrvp: Wrapping<i64>,
-    zrvp: Wrapping<i64>,
-    dx: f32,
-    dy: f32,
-    dz: f32,
-) -> f32 {
-    let mut hash = (seed ^ xrvp) ^ (yrvp ^ zrvp);
-    hash *= HASH_MULTIPLIER;
-    hash ^= hash.0 >> (64 - N_GRADS_3D_EXPONENT + 2);
-    let gi = (hash.0 as i32 & ((N_GRADS_3D - 1) << 2)) as usize;
-    let grads = &getGradients().gradients3D;
-    grads[gi | 0] * dx + grads[gi | 1] * dy + grads[gi | 2] * dz
+/**
+    2D Simplex noise, with Y pointing down the main diagonal, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise2_ImproveX_Grid(
+    seed: i64,
+    origin: [f64; 2],
+    step: [f64; 2],
+    size: [usize; 2],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let mut cache = CellCache::new();
+    let xxs: Vec<f64> = gridAxis(origin[0], step[0], size[0])
+        .into_iter()
+        .map(|x| x * ROOT2OVER2)
+        .collect();
+    let yys: Vec<f64> = gridAxis(origin[1], step[1], size[1])
+        .into_iter()
+        .map(|y| y * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D)))
+        .collect();
+
+    for (row, &yy) in out.chunks_exact_mut(size[0].max(1)).zip(&yys) {
+        for (value, &xx) in row.iter_mut().zip(&xxs) {
+            *value = noise2_UnskewedBase(gradients, seed, yy + xx, yy - xx, &mut cache);
+        }
+    }
 }
 
-fn grad4(
-    seed: Wrapping<i64>,
-    xsvp: Wrapping<i64>,
-    ysvp: Wrapping<i64>,
-    zsvp: Wrapping<i64>,
-    wsvp: Wrapping<i64>,
-    dx: f32,
-    dy: f32,
-    dz: f32,
-    dw: f32,
-) -> f32 {
-    let mut hash = seed ^ (xsvp ^ ysvp) ^ (zsvp ^ wsvp);
-    hash *= HASH_MULTIPLIER;
-    hash ^= hash.0 >> (64 - N_GRADS_4D_EXPONENT + 2);
-    let gi = (hash.0 as i32 & ((N_GRADS_4D - 1) << 2)) as usize;
-    let grads = &getGradients().gradients4D;
-    (grads[gi | 0] * dx + grads[gi | 1] * dy) + (grads[gi | 2] * dz + grads[gi | 3] * dw)
+/**
+    3D OpenSimplex2 noise, with better visual isotropy in (X, Y), on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise3_ImproveXY_Grid(
+    seed: i64,
+    origin: [f64; 3],
+    step: [f64; 3],
+    size: [usize; 3],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let mut cache = CellCache::new();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+
+    // The XY part of the rotation is shared by every Z plane.
+    let mut rotatedXY = Vec::with_capacity(xs.len() * ys.len());
+    for &y in &ys {
+        for &x in &xs {
+            let xy = x + y;
+            let s2 = xy * ROTATE_3D_ORTHOGONALIZER;
+            rotatedXY.push((x + s2, y + s2, xy * -ROOT3OVER3));
+        }
+    }
+
+    for (plane, &z) in out.chunks_exact_mut(rotatedXY.len().max(1)).zip(&zs) {
+        let zz = z * ROOT3OVER3;
+        for (value, &(xs2, ys2, xyr)) in plane.iter_mut().zip(&rotatedXY) {
+            *value =
+                noise3_UnrotatedBase(gradients, seed, xs2 + zz, ys2 + zz, xyr + zz, &mut cache);
+        }
+    }
 }
 
-fn fastFloor(x: f64) -> i32 {
-    let xi = x as i32;
-    if x < xi as f64 {
-        xi - 1
-    } else {
-        xi
+/**
+    3D OpenSimplex2 noise, with better visual isotropy in (X, Z), on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise3_ImproveXZ_Grid(
+    seed: i64,
+    origin: [f64; 3],
+    step: [f64; 3],
+    size: [usize; 3],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let mut cache = CellCache::new();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let yys: Vec<f64> = ys.iter().map(|y| y * ROOT3OVER3).collect();
+
+    for (plane, &z) in out.chunks_exact_mut((xs.len() * ys.len()).max(1)).zip(&zs) {
+        // The XZ part of the rotation is shared by every row of the plane.
+        let rotatedXZ: Vec<_> = xs
+            .iter()
+            .map(|&x| {
+                let xz = x + z;
+                let s2 = xz * ROTATE_3D_ORTHOGONALIZER;
+                (x + s2, z + s2, xz * -ROOT3OVER3)
+            })
+            .collect();
+
+        for (row, &yy) in plane.chunks_exact_mut(xs.len().max(1)).zip(&yys) {
+            for (value, &(xs2, zs2, xzr)) in row.iter_mut().zip(&rotatedXZ) {
+                *value =
+                    noise3_UnrotatedBase(gradients, seed, xs2 + yy, xzr + yy, zs2 + yy, &mut cache);
+            }
+        }
     }
 }
 
-fn fastRound(x: f64) -> i32 {
-    if x < 0.0 {
-        (x - 0.5) as i32
-    } else {
-        (x + 0.5) as i32
+/**
+    3D OpenSimplex2 noise, fallback rotation option, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise3_Fallback_Grid(
+    seed: i64,
+    origin: [f64; 3],
+    step: [f64; 3],
+    size: [usize; 3],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let mut cache = CellCache::new();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+
+    let mut samples = out.iter_mut();
+    for &z in &zs {
+        for &y in &ys {
+            for (&x, value) in xs.iter().zip(samples.by_ref()) {
+                let r = FALLBACK_ROTATE_3D * (x + y + z);
+                *value = noise3_UnrotatedBase(gradients, seed, r - x, r - y, r - z, &mut cache);
+            }
+        }
+    }
+}
+
+/**
+    4D OpenSimplex2 noise, with XYZ oriented like noise3_ImproveXY, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_ImproveXYZ_ImproveXY_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut partsXY = Vec::with_capacity(xs.len() * ys.len());
+    for &y in &ys {
+        for &x in &xs {
+            let xy = x + y;
+            partsXY.push((
+                x,
+                y,
+                xy * -0.21132486540518699998,
+                xy * -0.57735026918962599998,
+            ));
+        }
+    }
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        let ww = w * 0.2236067977499788;
+        for &z in &zs {
+            let zz = z * 0.28867513459481294226;
+            let zzww = zz + ww;
+            let wr = z * -0.866025403784439 + ww;
+            for (&(x, y, s2, xyr), value) in partsXY.iter().zip(samples.by_ref()) {
+                let xr = x + (zzww + s2);
+                let yr = y + (zzww + s2);
+                let zr = xyr + zzww;
+                *value = noise4_UnskewedBase(gradients, seed, xr, yr, zr, wr);
+            }
+        }
+    }
+}
+
+/**
+    4D OpenSimplex2 noise, with XYZ oriented like noise3_ImproveXZ, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_ImproveXYZ_ImproveXZ_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        let ww = w * 0.2236067977499788;
+        for &z in &zs {
+            let partsXZ: Vec<_> = xs
+                .iter()
+                .map(|&x| {
+                    let xz = x + z;
+                    (
+                        x,
+                        xz * -0.21132486540518699998,
+                        xz * -0.57735026918962599998,
+                    )
+                })
+                .collect();
+            for &y in &ys {
+                let yy = y * 0.28867513459481294226;
+                let yyww = yy + ww;
+                let wr = y * -0.866025403784439 + ww;
+                for (&(x, s2, xzr), value) in partsXZ.iter().zip(samples.by_ref()) {
+                    let xr = x + (yyww + s2);
+                    let zr = z + (yyww + s2);
+                    let yr = xzr + yyww;
+                    *value = noise4_UnskewedBase(gradients, seed, xr, yr, zr, wr);
+                }
+            }
+        }
+    }
+}
+
+/**
+    4D OpenSimplex2 noise, with XYZ oriented like noise3_Fallback, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_ImproveXYZ_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        let ww = w * 0.2236067977499788;
+        for &z in &zs {
+            for &y in &ys {
+                for (&x, value) in xs.iter().zip(samples.by_ref()) {
+                    let xyz = x + y + z;
+                    let s2 = xyz * -0.16666666666666666 + ww;
+                    let ws = -0.5 * xyz + ww;
+                    *value = noise4_UnskewedBase(gradients, seed, x + s2, y + s2, z + s2, ws);
+                }
+            }
+        }
+    }
+}
+
+/**
+    4D OpenSimplex2 noise, with XY and ZW forming orthogonal triangular-based planes,
+    on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_ImproveXY_ImproveZW_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut partsXY = Vec::with_capacity(xs.len() * ys.len());
+    for &y in &ys {
+        for &x in &xs {
+            let xy = x + y;
+            partsXY.push((x, y, xy * -0.178275657951399372, xy * -0.375199083010075342));
+        }
+    }
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        for &z in &zs {
+            let zw = z + w;
+            let s2ZW = zw * 0.215623393288842828;
+            let t2ZW = zw * -0.403949762580207112;
+            for (&(x, y, s2XY, t2XY), value) in partsXY.iter().zip(samples.by_ref()) {
+                let s2 = s2XY + s2ZW;
+                let t2 = t2ZW + t2XY;
+                *value = noise4_UnskewedBase(gradients, seed, x + s2, y + s2, z + t2, w + t2);
+            }
+        }
+    }
+}
+
+/**
+    4D OpenSimplex2 noise, fallback lattice orientation, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_Fallback_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let gradients = getGradients();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        for &z in &zs {
+            for &y in &ys {
+                for (&x, value) in xs.iter().zip(samples.by_ref()) {
+                    let s = SKEW_4D as f64 * (x + y + z + w);
+                    *value = noise4_UnskewedBase(gradients, seed, x + s, y + s, z + s, w + s);
+                }
+            }
+        }
     }
 }
 
 /*
-    gradients
+    Lane Evaluators
+
+    Each function evaluates N independent points at once, in stages that each loop
+    over the lanes: lattice offsets and falloffs for every vertex, then the vertex
+    hashes, then the gradient reads and the falloff sums. The scalar branches are
+    selects, so the offset and falloff stages compile to packed SSE2 arithmetic on
+    baseline x86-64; the 64-bit hash multiply and the gradient reads stay one lane
+    at a time.
+
+    `cargo bench --bench lanes` times 8 lanes against the scalar loop over the same
+    points. On one x86-64 core, noise2_Lanes ran about 45% faster, and
+    noise3_ImproveXZ_Lanes about as fast as the scalar loop: in 3D, most of the time
+    goes to the per-lane hashes and reads.
+    The scalar functions stay the reference implementation and the fallback for
+    batches that don't fill a whole set of lanes. Lane results match them to within
+    float rounding.
 */
 
-struct Gradients {
-    gradients2D: Vec<f32>,
-    gradients3D: Vec<f32>,
-    gradients4D: Vec<f32>,
+/**
+    2D Simplex noise, standard lattice orientation, for N points at once.
+*/
+pub fn noise2_Lanes<const N: usize>(seed: i64, x: [f64; N], y: [f64; N]) -> [f32; N] {
+    let mut xs = [0.0; N];
+    let mut ys = [0.0; N];
+    for l in 0..N {
+        let s = SKEW_2D * (x[l] + y[l]);
+        xs[l] = x[l] + s;
+        ys[l] = y[l] + s;
+    }
+
+    noise2_UnskewedBase_Lanes(getGradients(), seed, xs, ys)
 }
 
-static mut GRADIENTS: (Once, Option<Gradients>) = (Once::new(), None);
+/**
+    2D Simplex noise, with Y pointing down the main diagonal, for N points at once.
+*/
+pub fn noise2_ImproveX_Lanes<const N: usize>(seed: i64, x: [f64; N], y: [f64; N]) -> [f32; N] {
+    let mut xs = [0.0; N];
+    let mut ys = [0.0; N];
+    for l in 0..N {
+        let xx = x[l] * ROOT2OVER2;
+        let yy = y[l] * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D));
+        xs[l] = yy + xx;
+        ys[l] = yy - xx;
+    }
+
+    noise2_UnskewedBase_Lanes(getGradients(), seed, xs, ys)
+}
 
-fn getGradients() -> &'static Gradients {
-    unsafe {
-        GRADIENTS.0.call_once(|| {
-            GRADIENTS.1 = Some(initGradients());
-        });
-        GRADIENTS.1.as_ref().unwrap()
+/**
+    3D OpenSimplex2 noise, with better visual isotropy in (X, Y), for N points at once.
+*/
+pub fn noise3_ImproveXY_Lanes<const N: usize>(
+    seed: i64,
+    x: [f64; N],
+    y: [f64; N],
+    z: [f64; N],
+) -> [f32; N] {
+    let mut xr = [0.0; N];
+    let mut yr = [0.0; N];
+    let mut zr = [0.0; N];
+    for l in 0..N {
+        let xy = x[l] + y[l];
+        let s2 = xy * ROTATE_3D_ORTHOGONALIZER;
+        let zz = z[l] * ROOT3OVER3;
+        xr[l] = x[l] + s2 + zz;
+        yr[l] = y[l] + s2 + zz;
+        zr[l] = xy * -ROOT3OVER3 + zz;
     }
+
+    noise3_UnrotatedBase_Lanes(getGradients(), seed, xr, yr, zr)
 }
 
-fn initGradients() -> Gradients {
-    let gradients2D: Vec<_> = GRAD2_SRC
-        .into_iter()
-        .map(|v| (v / NORMALIZER_2D) as f32)
-        .collect::<Vec<_>>() // cache divisions
-        .into_iter()
-        .cycle()
-        .take((N_GRADS_2D * 2) as usize)
-        .collect();
+/**
+    3D OpenSimplex2 noise, with better visual isotropy in (X, Z), for N points at once.
+*/
+pub fn noise3_ImproveXZ_Lanes<const N: usize>(
+    seed: i64,
+    x: [f64; N],
+    y: [f64; N],
+    z: [f64; N],
+) -> [f32; N] {
+    let mut xr = [0.0; N];
+    let mut yr = [0.0; N];
+    let mut zr = [0.0; N];
+    for l in 0..N {
+        let xz = x[l] + z[l];
+        let s2 = xz * ROTATE_3D_ORTHOGONALIZER;
+        let yy = y[l] * ROOT3OVER3;
+        xr[l] = x[l] + s2 + yy;
+        zr[l] = z[l] + s2 + yy;
+        yr[l] = xz * -ROOT3OVER3 + yy;
+    }
 
-    let gradients3D: Vec<_> = GRAD3_SRC
-        .into_iter()
-        .map(|v| (v / NORMALIZER_3D) as f32)
-        .collect::<Vec<_>>() // cache divisions
-        .into_iter()
-        .cycle()
-        .take((N_GRADS_3D * 4) as usize)
-        .collect();
+    noise3_UnrotatedBase_Lanes(getGradients(), seed, xr, yr, zr)
+}
 
-    let gradients4D: Vec<_> = GRAD4_SRC
-        .into_iter()
+/**
+    3D OpenSimplex2 noise, fallback rotation option, for N points at once.
+*/
+pub fn noise3_Fallback_Lanes<const N: usize>(
+    seed: i64,
+    x: [f64; N],
+    y: [f64; N],
+    z: [f64; N],
+) -> [f32; N] {
+    let mut xr = [0.0; N];
+    let mut yr = [0.0; N];
+    let mut zr = [0.0; N];
+    for l in 0..N {
+        let r = FALLBACK_ROTATE_3D * (x[l] + y[l] + z[l]);
+        xr[l] = r - x[l];
+        yr[l] = r - y[l];
+        zr[l] = r - z[l];
+    }
+
+    noise3_UnrotatedBase_Lanes(getGradients(), seed, xr, yr, zr)
+}
+
+/**
+    2D Simplex noise base, N lanes.
+*/
+fn noise2_UnskewedBase_Lanes<const N: usize>(
+    gradients: &Gradients,
+    seed: i64,
+    xs: [f64; N],
+    ys: [f64; N],
+) -> [f32; N] {
+    let seed = Wrapping(seed);
+
+    // Get base points and offsets.
+    let mut xsbp = [Wrapping(0); N];
+    let mut ysbp = [Wrapping(0); N];
+    let mut t = [0.0; N];
+    let mut dx0 = [0.0; N];
+    let mut dy0 = [0.0; N];
+    for l in 0..N {
+        let xsb = floorLane(xs[l]);
+        let ysb = floorLane(ys[l]);
+        let xi = (xs[l] - xsb) as f32;
+        let yi = (ys[l] - ysb) as f32;
+
+        // Prime pre-multiplication for hash.
+        xsbp[l] = Wrapping(xsb as i32 as i64) * Wrapping(PRIME_X);
+        ysbp[l] = Wrapping(ysb as i32 as i64) * Wrapping(PRIME_Y);
+
+        // Unskew.
+        t[l] = (xi + yi) * UNSKEW_2D as f32;
+        dx0[l] = xi + t[l];
+        dy0[l] = yi + t[l];
+    }
+
+    // First vertex.
+    let mut value = [0.0; N];
+    let mut a0 = [0.0; N];
+    for l in 0..N {
+        a0[l] = RSQUARED_2D - dx0[l] * dx0[l] - dy0[l] * dy0[l];
+    }
+    grad2_Lanes(gradients, seed, xsbp, ysbp, dx0, dy0, a0, &mut value);
+
+    // Second vertex.
+    let mut a1 = [0.0; N];
+    let mut dx1 = [0.0; N];
+    let mut dy1 = [0.0; N];
+    let mut xp1 = [Wrapping(0); N];
+    let mut yp1 = [Wrapping(0); N];
+    for l in 0..N {
+        a1[l] = (2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 / UNSKEW_2D + 2.0)) as f32 * t[l]
+            + ((-2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 + 2.0 * UNSKEW_2D)) as f32 + a0[l]);
+        dx1[l] = dx0[l] - (1.0 + 2.0 * UNSKEW_2D) as f32;
+        dy1[l] = dy0[l] - (1.0 + 2.0 * UNSKEW_2D) as f32;
+        xp1[l] = xsbp[l] + Wrapping(PRIME_X);
+        yp1[l] = ysbp[l] + Wrapping(PRIME_Y);
+    }
+    grad2_Lanes(gradients, seed, xp1, yp1, dx1, dy1, a1, &mut value);
+
+    // Third vertex, picked by which side of the diagonal we're on.
+    let mut a2 = [0.0; N];
+    let mut dx2 = [0.0; N];
+    let mut dy2 = [0.0; N];
+    let mut xp2 = [Wrapping(0); N];
+    let mut yp2 = [Wrapping(0); N];
+    for l in 0..N {
+        let upper = dy0[l] > dx0[l];
+        dx2[l] = dx0[l] - select(upper, UNSKEW_2D as f32, (UNSKEW_2D + 1.0) as f32);
+        dy2[l] = dy0[l] - select(upper, (UNSKEW_2D + 1.0) as f32, UNSKEW_2D as f32);
+        xp2[l] = xsbp[l] + select(upper, Wrapping(0), Wrapping(PRIME_X));
+        yp2[l] = ysbp[l] + select(upper, Wrapping(PRIME_Y), Wrapping(0));
+        a2[l] = RSQUARED_2D - dx2[l] * dx2[l] - dy2[l] * dy2[l];
+    }
+    grad2_Lanes(gradients, seed, xp2, yp2, dx2, dy2, a2, &mut value);
+
+    value
+}
+
+/**
+    Adds one vertex's contribution to every lane, one stage at a time: hashes, gradient reads,
+    then falloffs.
+*/
+fn grad2_Lanes<const N: usize>(
+    gradients: &Gradients,
+    seed: Wrapping<i64>,
+    xsvp: [Wrapping<i64>; N],
+    ysvp: [Wrapping<i64>; N],
+    dx: [f32; N],
+    dy: [f32; N],
+    a: [f32; N],
+    value: &mut [f32; N],
+) {
+    let mut gi = [0; N];
+    for l in 0..N {
+        let mut hash = seed ^ xsvp[l] ^ ysvp[l];
+        hash *= HASH_MULTIPLIER;
+        let shifted = ((hash.0 >> 32) as i32) >> (32 - N_GRADS_2D_EXPONENT + 1);
+        gi[l] = ((hash.0 as i32 ^ shifted) & ((N_GRADS_2D - 1) << 1)) as usize;
+    }
+
+    let grads = &gradients.gradients2D[..N_GRADS_2D as usize * 2];
+    let mut g = [[0.0; 2]; N];
+    for l in 0..N {
+        g[l] = [grads[gi[l] | 0], grads[gi[l] | 1]];
+    }
+
+    for l in 0..N {
+        let a = a[l].max(0.0);
+        value[l] += (a * a) * (a * a) * (g[l][0] * dx[l] + g[l][1] * dy[l]);
+    }
+}
+
+/**
+    `fastFloor` as a float, with the branch as a select.
+*/
+fn floorLane(x: f64) -> f64 {
+    let xi = (x as i32) as f64;
+    select(x < xi, xi - 1.0, xi)
+}
+
+/**
+    Generate overlapping cubic lattices for 3D OpenSimplex2 noise, N lanes.
+*/
+fn noise3_UnrotatedBase_Lanes<const N: usize>(
+    gradients: &Gradients,
+    seed: i64,
+    xr: [f64; N],
+    yr: [f64; N],
+    zr: [f64; N],
+) -> [f32; N] {
+    let seed = Wrapping(seed);
+
+    // One closest and one second-closest point on each lattice copy.
+    let mut p = [[[Wrapping(0); 3]; N]; 4];
+    let mut d = [[[0.0; 3]; N]; 4];
+    let mut a = [[0.0; N]; 4];
+    for l in 0..N {
+        // Get base points and offsets.
+        let rb = [roundLane(xr[l]), roundLane(yr[l]), roundLane(zr[l])];
+        let mut ri = [
+            (xr[l] - rb[0]) as f32,
+            (yr[l] - rb[1]) as f32,
+            (zr[l] - rb[2]) as f32,
+        ];
+
+        // -1 if positive, 1 if negative.
+        let mut nSign = ri.map(|ri| select(ri >= 0.0, -1.0, 1.0));
+
+        let mut a0 = [0, 1, 2].map(|axis| nSign[axis] * -ri[axis]);
+
+        // Prime pre-multiplication for hash.
+        let primes = [PRIME_X, PRIME_Y, PRIME_Z];
+        let mut rbp =
+            [0, 1, 2].map(|axis| Wrapping(rb[axis] as i32 as i64) * Wrapping(primes[axis]));
+
+        let mut aCopy = (RSQUARED_3D - ri[0] * ri[0]) - (ri[1] * ri[1] + ri[2] * ri[2]);
+        for copy in 0..2 {
+            p[copy * 2][l] = rbp;
+            d[copy * 2][l] = ri;
+            a[copy * 2][l] = aCopy;
+
+            // Second-closest point, along the axis with the largest offset.
+            let alongX = a0[0] >= a0[1] && a0[0] >= a0[2];
+            let alongY = !alongX && a0[1] > a0[0] && a0[1] >= a0[2];
+            let along = [alongX, alongY, !alongX && !alongY];
+            let axis0 = select(alongX, a0[0], select(alongY, a0[1], a0[2]));
+            a[copy * 2 + 1][l] = aCopy + axis0 + axis0 - 1.0;
+            for axis in 0..3 {
+                let step = select(nSign[axis] < 0.0, -primes[axis], primes[axis]);
+                p[copy * 2 + 1][l][axis] =
+                    rbp[axis] - select(along[axis], Wrapping(step), Wrapping(0));
+                d[copy * 2 + 1][l][axis] = select(along[axis], ri[axis] + nSign[axis], ri[axis]);
+            }
+
+            // Move to the other lattice copy.
+            for axis in 0..3 {
+                a0[axis] = 0.5 - a0[axis];
+                ri[axis] = nSign[axis] * a0[axis];
+            }
+            aCopy += (0.75 - a0[0]) - (a0[1] + a0[2]);
+            for axis in 0..3 {
+                rbp[axis] += select(nSign[axis] < 0.0, Wrapping(primes[axis]), Wrapping(0));
+                nSign[axis] = -nSign[axis];
+            }
+        }
+    }
+
+    let mut value = [0.0; N];
+    for vertex in 0..4 {
+        let seed = select(vertex < 2, seed, seed ^ Wrapping(SEED_FLIP_3D));
+        grad3_Lanes(gradients, seed, p[vertex], d[vertex], a[vertex], &mut value);
+    }
+
+    value
+}
+
+/**
+    `grad2_Lanes` for the 3D lattice.
+*/
+fn grad3_Lanes<const N: usize>(
+    gradients: &Gradients,
+    seed: Wrapping<i64>,
+    p: [[Wrapping<i64>; 3]; N],
+    d: [[f32; 3]; N],
+    a: [f32; N],
+    value: &mut [f32; N],
+) {
+    let mut gi = [0; N];
+    for l in 0..N {
+        let mut hash = (seed ^ p[l][0]) ^ (p[l][1] ^ p[l][2]);
+        hash *= HASH_MULTIPLIER;
+        let shifted = ((hash.0 >> 32) as i32) >> (32 - N_GRADS_3D_EXPONENT + 2);
+        gi[l] = ((hash.0 as i32 ^ shifted) & ((N_GRADS_3D - 1) << 2)) as usize;
+    }
+
+    let grads = &gradients.gradients3D[..N_GRADS_3D as usize * 4];
+    let mut g = [[0.0; 3]; N];
+    for l in 0..N {
+        g[l] = [grads[gi[l] | 0], grads[gi[l] | 1], grads[gi[l] | 2]];
+    }
+
+    for l in 0..N {
+        let a = a[l].max(0.0);
+        let [dx, dy, dz] = d[l];
+        value[l] += (a * a) * (a * a) * (g[l][0] * dx + g[l][1] * dy + g[l][2] * dz);
+    }
+}
+
+/**
+    `fastRound` as a float, with the branch as a select.
+*/
+fn roundLane(x: f64) -> f64 {
+    (select(x < 0.0, x - 0.5, x + 0.5) as i32) as f64
+}
+
+/*
+    Derivative Evaluators
+
+    Each function returns the same value as its scalar counterpart, together with
+    the analytic gradient of the noise with respect to the input coordinates.
+    The per-vertex terms are differentiated in lattice space, then mapped back
+    through the transpose of the function's skew or rotation.
+*/
+
+/**
+    2D Simplex noise, standard lattice orientation, with derivatives.
+*/
+pub fn noise2_with_derivatives(seed: i64, x: f64, y: f64) -> (f32, [f32; 2]) {
+    let s = SKEW_2D * (x + y);
+    let xs = x + s;
+    let ys = y + s;
+
+    let (value, [dxs, dys]) = noise2_UnskewedBase_Derivatives(getGradients(), seed, xs, ys);
+    let s = (dxs + dys) as f64 * SKEW_2D;
+    (value, [(dxs as f64 + s) as f32, (dys as f64 + s) as f32])
+}
+
+/**
+    2D Simplex noise, with Y pointing down the main diagonal, with derivatives.
+*/
+pub fn noise2_ImproveX_with_derivatives(seed: i64, x: f64, y: f64) -> (f32, [f32; 2]) {
+    let xx = x * ROOT2OVER2;
+    let yy = y * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D));
+
+    let (value, [dxs, dys]) =
+        noise2_UnskewedBase_Derivatives(getGradients(), seed, yy + xx, yy - xx);
+    let dx = (dxs - dys) as f64 * ROOT2OVER2;
+    let dy = (dxs + dys) as f64 * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D));
+    (value, [dx as f32, dy as f32])
+}
+
+/**
+    3D OpenSimplex2 noise, with better visual isotropy in (X, Y), with derivatives.
+*/
+pub fn noise3_ImproveXY_with_derivatives(seed: i64, x: f64, y: f64, z: f64) -> (f32, [f32; 3]) {
+    let xy = x + y;
+    let s2 = xy * ROTATE_3D_ORTHOGONALIZER;
+    let zz = z * ROOT3OVER3;
+    let xr = x + s2 + zz;
+    let yr = y + s2 + zz;
+    let zr = xy * -ROOT3OVER3 + zz;
+
+    let (value, [dxr, dyr, dzr]) =
+        noise3_UnrotatedBase_Derivatives(getGradients(), seed, xr, yr, zr);
+    let (dxr, dyr, dzr) = (dxr as f64, dyr as f64, dzr as f64);
+    let s2 = (dxr + dyr) * ROTATE_3D_ORTHOGONALIZER - dzr * ROOT3OVER3;
+    let dz = (dxr + dyr + dzr) * ROOT3OVER3;
+    (value, [(dxr + s2) as f32, (dyr + s2) as f32, dz as f32])
+}
+
+/**
+    3D OpenSimplex2 noise, with better visual isotropy in (X, Z), with derivatives.
+*/
+pub fn noise3_ImproveXZ_with_derivatives(seed: i64, x: f64, y: f64, z: f64) -> (f32, [f32; 3]) {
+    let xz = x + z;
+    let s2 = xz * ROTATE_3D_ORTHOGONALIZER;
+    let yy = y * ROOT3OVER3;
+    let xr = x + s2 + yy;
+    let zr = z + s2 + yy;
+    let yr = xz * -ROOT3OVER3 + yy;
+
+    let (value, [dxr, dyr, dzr]) =
+        noise3_UnrotatedBase_Derivatives(getGradients(), seed, xr, yr, zr);
+    let (dxr, dyr, dzr) = (dxr as f64, dyr as f64, dzr as f64);
+    let s2 = (dxr + dzr) * ROTATE_3D_ORTHOGONALIZER - dyr * ROOT3OVER3;
+    let dy = (dxr + dyr + dzr) * ROOT3OVER3;
+    (value, [(dxr + s2) as f32, dy as f32, (dzr + s2) as f32])
+}
+
+/**
+    3D OpenSimplex2 noise, fallback rotation option, with derivatives.
+*/
+pub fn noise3_Fallback_with_derivatives(seed: i64, x: f64, y: f64, z: f64) -> (f32, [f32; 3]) {
+    let r = FALLBACK_ROTATE_3D * (x + y + z);
+    let xr = r - x;
+    let yr = r - y;
+    let zr = r - z;
+
+    let (value, [dxr, dyr, dzr]) =
+        noise3_UnrotatedBase_Derivatives(getGradients(), seed, xr, yr, zr);
+    let (dxr, dyr, dzr) = (dxr as f64, dyr as f64, dzr as f64);
+    let r = FALLBACK_ROTATE_3D * (dxr + dyr + dzr);
+    (
+        value,
+        [(r - dxr) as f32, (r - dyr) as f32, (r - dzr) as f32],
+    )
+}
+
+/**
+    4D OpenSimplex2 noise, with XYZ oriented like noise3_ImproveXY, with derivatives.
+*/
+pub fn noise4_ImproveXYZ_ImproveXY_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let xy = x + y;
+    let s2 = xy * -0.21132486540518699998;
+    let zz = z * 0.28867513459481294226;
+    let ww = w * 0.2236067977499788;
+    let xr = x + (zz + ww + s2);
+    let yr = y + (zz + ww + s2);
+    let zr = xy * -0.57735026918962599998 + (zz + ww);
+    let wr = z * -0.866025403784439 + ww;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getGradients(), seed, xr, yr, zr, wr);
+    let [dxr, dyr, dzr, dwr] = derivatives.map(|d| d as f64);
+    let s2 = (dxr + dyr) * -0.21132486540518699998 + dzr * -0.57735026918962599998;
+    let dz = (dxr + dyr + dzr) * 0.28867513459481294226 + dwr * -0.866025403784439;
+    let dw = (dxr + dyr + dzr + dwr) * 0.2236067977499788;
+    (
+        value,
+        [(dxr + s2) as f32, (dyr + s2) as f32, dz as f32, dw as f32],
+    )
+}
+
+/**
+    4D OpenSimplex2 noise, with XYZ oriented like noise3_ImproveXZ, with derivatives.
+*/
+pub fn noise4_ImproveXYZ_ImproveXZ_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let xz = x + z;
+    let s2 = xz * -0.21132486540518699998;
+    let yy = y * 0.28867513459481294226;
+    let ww = w * 0.2236067977499788;
+    let xr = x + (yy + ww + s2);
+    let zr = z + (yy + ww + s2);
+    let yr = xz * -0.57735026918962599998 + (yy + ww);
+    let wr = y * -0.866025403784439 + ww;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getGradients(), seed, xr, yr, zr, wr);
+    let [dxr, dyr, dzr, dwr] = derivatives.map(|d| d as f64);
+    let s2 = (dxr + dzr) * -0.21132486540518699998 + dyr * -0.57735026918962599998;
+    let dy = (dxr + dyr + dzr) * 0.28867513459481294226 + dwr * -0.866025403784439;
+    let dw = (dxr + dyr + dzr + dwr) * 0.2236067977499788;
+    (
+        value,
+        [(dxr + s2) as f32, dy as f32, (dzr + s2) as f32, dw as f32],
+    )
+}
+
+/**
+    4D OpenSimplex2 noise, with XYZ oriented like noise3_Fallback, with derivatives.
+*/
+pub fn noise4_ImproveXYZ_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let xyz = x + y + z;
+    let ww = w * 0.2236067977499788;
+    let s2 = xyz * -0.16666666666666666 + ww;
+    let xs = x + s2;
+    let ys = y + s2;
+    let zs = z + s2;
+    let ws = -0.5 * xyz + ww;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getGradients(), seed, xs, ys, zs, ws);
+    let [dxs, dys, dzs, dws] = derivatives.map(|d| d as f64);
+    let s2 = (dxs + dys + dzs) * -0.16666666666666666 - 0.5 * dws;
+    let dw = (dxs + dys + dzs + dws) * 0.2236067977499788;
+    (
+        value,
+        [
+            (dxs + s2) as f32,
+            (dys + s2) as f32,
+            (dzs + s2) as f32,
+            dw as f32,
+        ],
+    )
+}
+
+/**
+    4D OpenSimplex2 noise, with XY and ZW forming orthogonal triangular-based planes, with derivatives.
+*/
+pub fn noise4_ImproveXY_ImproveZW_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let s2 = (x + y) * -0.178275657951399372 + (z + w) * 0.215623393288842828;
+    let t2 = (z + w) * -0.403949762580207112 + (x + y) * -0.375199083010075342;
+    let xs = x + s2;
+    let ys = y + s2;
+    let zs = z + t2;
+    let ws = w + t2;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getGradients(), seed, xs, ys, zs, ws);
+    let [dxs, dys, dzs, dws] = derivatives.map(|d| d as f64);
+    let s2 = (dxs + dys) * -0.178275657951399372 + (dzs + dws) * -0.375199083010075342;
+    let t2 = (dzs + dws) * -0.403949762580207112 + (dxs + dys) * 0.215623393288842828;
+    (
+        value,
+        [
+            (dxs + s2) as f32,
+            (dys + s2) as f32,
+            (dzs + t2) as f32,
+            (dws + t2) as f32,
+        ],
+    )
+}
+
+/**
+    4D OpenSimplex2 noise, fallback lattice orientation, with derivatives.
+*/
+pub fn noise4_Fallback_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let s = SKEW_4D as f64 * (x + y + z + w);
+    let xs = x + s;
+    let ys = y + s;
+    let zs = z + s;
+    let ws = w + s;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getGradients(), seed, xs, ys, zs, ws);
+    let [dxs, dys, dzs, dws] = derivatives.map(|d| d as f64);
+    let s = SKEW_4D as f64 * (dxs + dys + dzs + dws);
+    (
+        value,
+        [
+            (dxs + s) as f32,
+            (dys + s) as f32,
+            (dzs + s) as f32,
+            (dws + s) as f32,
+        ],
+    )
+}
+
+/**
+    2D Simplex noise base, with derivatives with respect to (xs, ys).
+*/
+fn noise2_UnskewedBase_Derivatives(
+    gradients: &Gradients,
+    seed: i64,
+    xs: f64,
+    ys: f64,
+) -> (f32, [f32; 2]) {
+    let seed = Wrapping(seed);
+
+    // Get base points and offsets.
+    let xsb = fastFloor(xs);
+    let ysb = fastFloor(ys);
+    let xi = (xs - xsb as f64) as f32;
+    let yi = (ys - ysb as f64) as f32;
+
+    // Prime pre-multiplication for hash.
+    let xsbp = Wrapping(xsb as i64) * Wrapping(PRIME_X);
+    let ysbp = Wrapping(ysb as i64) * Wrapping(PRIME_Y);
+
+    // Unskew.
+    let t = (xi + yi) * UNSKEW_2D as f32;
+    let dx0 = xi + t;
+    let dy0 = yi + t;
+
+    // First vertex.
+    let mut derivatives = [0.0; 2];
+    let mut value = 0.0;
+    let a0 = RSQUARED_2D - dx0 * dx0 - dy0 * dy0;
+    if a0 > 0.0 {
+        value = contribution2(gradients, &mut derivatives, a0, seed, xsbp, ysbp, dx0, dy0);
+    }
+
+    // Second vertex.
+    let a1 = (2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 / UNSKEW_2D + 2.0)) as f32 * t
+        + ((-2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 + 2.0 * UNSKEW_2D)) as f32 + a0);
+    if a1 > 0.0 {
+        let dx1 = dx0 - (1.0 + 2.0 * UNSKEW_2D) as f32;
+        let dy1 = dy0 - (1.0 + 2.0 * UNSKEW_2D) as f32;
+        value += contribution2(
+            gradients,
+            &mut derivatives,
+            a1,
+            seed,
+            xsbp + Wrapping(PRIME_X),
+            ysbp + Wrapping(PRIME_Y),
+            dx1,
+            dy1,
+        );
+    }
+
+    // Third vertex.
+    if dy0 > dx0 {
+        let dx2 = dx0 - UNSKEW_2D as f32;
+        let dy2 = dy0 - (UNSKEW_2D + 1.0) as f32;
+        let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+        if a2 > 0.0 {
+            value += contribution2(
+                gradients,
+                &mut derivatives,
+                a2,
+                seed,
+                xsbp,
+                ysbp + Wrapping(PRIME_Y),
+                dx2,
+                dy2,
+            );
+        }
+    } else {
+        let dx2 = dx0 - (UNSKEW_2D + 1.0) as f32;
+        let dy2 = dy0 - UNSKEW_2D as f32;
+        let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+        if a2 > 0.0 {
+            value += contribution2(
+                gradients,
+                &mut derivatives,
+                a2,
+                seed,
+                xsbp + Wrapping(PRIME_X),
+                ysbp,
+                dx2,
+                dy2,
+            );
+        }
+    }
+
+    // Every offset is the unskewed base offset plus a constant.
+    let unskew = (derivatives[0] + derivatives[1]) * UNSKEW_2D as f32;
+    (value, [derivatives[0] + unskew, derivatives[1] + unskew])
+}
+
+/**
+    Generate overlapping cubic lattices for 3D OpenSimplex2 noise,
+    with derivatives with respect to (xr, yr, zr).
+*/
+fn noise3_UnrotatedBase_Derivatives(
+    gradients: &Gradients,
+    seed: i64,
+    xr: f64,
+    yr: f64,
+    zr: f64,
+) -> (f32, [f32; 3]) {
+    let mut seed = Wrapping(seed);
+
+    // Get base points and offsets.
+    let xrb = fastRound(xr);
+    let yrb = fastRound(yr);
+    let zrb = fastRound(zr);
+    let mut xri = (xr - xrb as f64) as f32;
+    let mut yri = (yr - yrb as f64) as f32;
+    let mut zri = (zr - zrb as f64) as f32;
+
+    // -1 if positive, 1 if negative.
+    let mut xNSign = (-1.0 - xri) as i32 | 1;
+    let mut yNSign = (-1.0 - yri) as i32 | 1;
+    let mut zNSign = (-1.0 - zri) as i32 | 1;
+
+    // Compute absolute values, using the above as a shortcut. This was faster in my tests for some reason.
+    let mut ax0 = xNSign as f32 * -xri;
+    let mut ay0 = yNSign as f32 * -yri;
+    let mut az0 = zNSign as f32 * -zri;
+
+    // Prime pre-multiplication for hash.
+    let mut xrbp = Wrapping(xrb as i64) * Wrapping(PRIME_X);
+    let mut yrbp = Wrapping(yrb as i64) * Wrapping(PRIME_Y);
+    let mut zrbp = Wrapping(zrb as i64) * Wrapping(PRIME_Z);
+
+    // Loop: Pick an edge on each lattice copy.
+    let mut derivatives = [0.0; 3];
+    let mut value = 0.0;
+    let mut a = (RSQUARED_3D - xri * xri) - (yri * yri + zri * zri);
+    for l in 0.. {
+        // Closest point on cube.
+        if a > 0.0 {
+            value += contribution3(
+                gradients,
+                &mut derivatives,
+                a,
+                seed,
+                xrbp,
+                yrbp,
+                zrbp,
+                xri,
+                yri,
+                zri,
+            );
+        }
+
+        // Second-closest point.
+        if ax0 >= ay0 && ax0 >= az0 {
+            let mut b = a + ax0 + ax0;
+            if b > 1.0 {
+                b -= 1.0;
+                value += contribution3(
+                    gradients,
+                    &mut derivatives,
+                    b,
+                    seed,
+                    xrbp - Wrapping(xNSign as i64) * Wrapping(PRIME_X),
+                    yrbp,
+                    zrbp,
+                    xri + xNSign as f32,
+                    yri,
+                    zri,
+                );
+            }
+        } else if ay0 > ax0 && ay0 >= az0 {
+            let mut b = a + ay0 + ay0;
+            if b > 1.0 {
+                b -= 1.0;
+                value += contribution3(
+                    gradients,
+                    &mut derivatives,
+                    b,
+                    seed,
+                    xrbp,
+                    yrbp - Wrapping(yNSign as i64) * Wrapping(PRIME_Y),
+                    zrbp,
+                    xri,
+                    yri + yNSign as f32,
+                    zri,
+                );
+            }
+        } else {
+            let mut b = a + az0 + az0;
+            if b > 1.0 {
+                b -= 1.0;
+                value += contribution3(
+                    gradients,
+                    &mut derivatives,
+                    b,
+                    seed,
+                    xrbp,
+                    yrbp,
+                    zrbp - Wrapping(zNSign as i64) * Wrapping(PRIME_Z),
+                    xri,
+                    yri,
+                    zri + zNSign as f32,
+                );
+            }
+        }
+
+        // Break from loop if we're done, skipping updates below.
+        if l == 1 {
+            break;
+        }
+
+        // Update absolute value.
+        ax0 = 0.5 - ax0;
+        ay0 = 0.5 - ay0;
+        az0 = 0.5 - az0;
+
+        // Update relative coordinate.
+        xri = xNSign as f32 * ax0;
+        yri = yNSign as f32 * ay0;
+        zri = zNSign as f32 * az0;
+
+        // Update falloff.
+        a += (0.75 - ax0) - (ay0 + az0);
+
+        // Update prime for hash.
+        xrbp += (xNSign as i64 >> 1) & PRIME_X;
+        yrbp += (yNSign as i64 >> 1) & PRIME_Y;
+        zrbp += (zNSign as i64 >> 1) & PRIME_Z;
+
+        // Update the reverse sign indicators.
+        xNSign = -xNSign;
+        yNSign = -yNSign;
+        zNSign = -zNSign;
+
+        // And finally update the seed for the other lattice copy.
+        seed ^= SEED_FLIP_3D;
+    }
+
+    (value, derivatives)
+}
+
+/**
+    4D OpenSimplex2 noise base, with derivatives with respect to (xs, ys, zs, ws).
+*/
+fn noise4_UnskewedBase_Derivatives(
+    gradients: &Gradients,
+    seed: i64,
+    xs: f64,
+    ys: f64,
+    zs: f64,
+    ws: f64,
+) -> (f32, [f32; 4]) {
+    let mut seed = Wrapping(seed);
+
+    // Get base points and offsets
+    let xsb = fastFloor(xs);
+    let ysb = fastFloor(ys);
+    let zsb = fastFloor(zs);
+    let wsb = fastFloor(ws);
+    let mut xsi = (xs - xsb as f64) as f32;
+    let mut ysi = (ys - ysb as f64) as f32;
+    let mut zsi = (zs - zsb as f64) as f32;
+    let mut wsi = (ws - wsb as f64) as f32;
+
+    // Determine which lattice we can be confident has a contributing point its corresponding cell's base simplex.
+    // We only look at the spaces between the diagonal planes. This proved effective in all of my tests.
+    let siSum = (xsi + ysi) + (zsi + wsi);
+    let startingLattice = (siSum * 1.25) as i32;
+
+    // Offset for seed based on first lattice copy.
+    seed += Wrapping(startingLattice as i64) * Wrapping(SEED_OFFSET_4D);
+
+    // Offset for lattice point relative positions (skewed)
+    let startingLatticeOffset = startingLattice as f32 * -LATTICE_STEP_4D;
+    xsi += startingLatticeOffset;
+    ysi += startingLatticeOffset;
+    zsi += startingLatticeOffset;
+    wsi += startingLatticeOffset;
+
+    // Prep for vertex contributions.
+    let mut ssi = (siSum + startingLatticeOffset * 4.0) * UNSKEW_4D;
+
+    // Prime pre-multiplication for hash.
+    let mut xsvp = Wrapping(xsb as i64) * Wrapping(PRIME_X);
+    let mut ysvp = Wrapping(ysb as i64) * Wrapping(PRIME_Y);
+    let mut zsvp = Wrapping(zsb as i64) * Wrapping(PRIME_Z);
+    let mut wsvp = Wrapping(wsb as i64) * Wrapping(PRIME_W);
+
+    // Five points to add, total, from five copies of the A4 lattice.
+    let mut derivatives = [0.0; 4];
+    let mut value = 0.0;
+    for i in 0.. {
+        // Next point is the closest vertex on the 4-simplex whose base vertex is the aforementioned vertex.
+        let score0 = 1.0 + ssi * (-1.0 / UNSKEW_4D); // Seems slightly faster than 1.0-xsi-ysi-zsi-wsi
+        if xsi >= ysi && xsi >= zsi && xsi >= wsi && xsi >= score0 {
+            xsvp += PRIME_X;
+            xsi -= 1.0;
+            ssi -= UNSKEW_4D;
+        } else if ysi > xsi && ysi >= zsi && ysi >= wsi && ysi >= score0 {
+            ysvp += PRIME_Y;
+            ysi -= 1.0;
+            ssi -= UNSKEW_4D;
+        } else if zsi > xsi && zsi > ysi && zsi >= wsi && zsi >= score0 {
+            zsvp += PRIME_Z;
+            zsi -= 1.0;
+            ssi -= UNSKEW_4D;
+        } else if wsi > xsi && wsi > ysi && wsi > zsi && wsi >= score0 {
+            wsvp += PRIME_W;
+            wsi -= 1.0;
+            ssi -= UNSKEW_4D;
+        }
+
+        // gradient contribution with falloff.
+        let dx = xsi + ssi;
+        let dy = ysi + ssi;
+        let dz = zsi + ssi;
+        let dw = wsi + ssi;
+        let a = (dx * dx + dy * dy) + (dz * dz + dw * dw);
+        if a < RSQUARED_4D {
+            value += contribution4(
+                gradients,
+                &mut derivatives,
+                RSQUARED_4D - a,
+                seed,
+                xsvp,
+                ysvp,
+                zsvp,
+                wsvp,
+                dx,
+                dy,
+                dz,
+                dw,
+            );
+        }
+
+        // Break from loop if we're done, skipping updates below.
+        if i == 4 {
+            break;
+        }
+
+        // Update for next lattice copy shifted down by <-0.2, -0.2, -0.2, -0.2>.
+        xsi += LATTICE_STEP_4D;
+        ysi += LATTICE_STEP_4D;
+        zsi += LATTICE_STEP_4D;
+        wsi += LATTICE_STEP_4D;
+        ssi += LATTICE_STEP_4D * 4.0 * UNSKEW_4D;
+        seed -= SEED_OFFSET_4D;
+
+        // Because we don't always start on the same lattice copy, there's a special reset case.
+        if i == startingLattice {
+            xsvp -= PRIME_X;
+            ysvp -= PRIME_Y;
+            zsvp -= PRIME_Z;
+            wsvp -= PRIME_W;
+            seed += SEED_OFFSET_4D * 5;
+        }
+    }
+
+    // Every offset is the unskewed base offset plus a constant.
+    let unskew =
+        ((derivatives[0] + derivatives[1]) + (derivatives[2] + derivatives[3])) * UNSKEW_4D;
+    (
+        value,
+        [
+            derivatives[0] + unskew,
+            derivatives[1] + unskew,
+            derivatives[2] + unskew,
+            derivatives[3] + unskew,
+        ],
+    )
+}
+
+/*
+    Periodic Evaluators
+
+    Tileable noise with an integer period per axis, so that
+    noise(x + period[0], y, ...) == noise(x, y, ...) and likewise for the other axes.
+*/
+
+/**
+    2D noise that repeats every period[0] units in X and every period[1] units in Y.
+    Each axis is wrapped onto a circle with that circumference, and the resulting
+    torus is sampled with noise4_ImproveXY_ImproveZW.
+*/
+pub fn noise2_Periodic(seed: i64, x: f64, y: f64, period: [u32; 2]) -> f32 {
+    checkPeriod(&period);
+    let [xc, xs] = torusCoordinates(x, period[0]);
+    let [yc, ys] = torusCoordinates(y, period[1]);
+
+    noise4_ImproveXY_ImproveZW(seed, xc, xs, yc, ys)
+}
+
+/**
+    3D OpenSimplex2 noise that repeats every period[i] units along each axis.
+    Both cubic lattices are hashed with their coordinates wrapped to the period.
+    The lattices can't be re-oriented without breaking the period, so this has
+    more axis-aligned bias than noise3_ImproveXY or noise3_ImproveXZ.
+*/
+pub fn noise3_Periodic(seed: i64, x: f64, y: f64, z: f64, period: [u32; 3]) -> f32 {
+    checkPeriod(&period);
+
+    noise3_PeriodicBase(getGradients(), seed, x, y, z, period)
+}
+
+/**
+    Generate overlapping cubic lattices with wrapped hashes.
+    Every vertex of the cell around the point is checked on both lattice copies.
+*/
+fn noise3_PeriodicBase(
+    gradients: &Gradients,
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    period: [u32; 3],
+) -> f32 {
+    let mut seed = Wrapping(seed);
+    let mut value = 0.0;
+
+    // The second copy is offset by half a unit; its vertex at i - 0.5 is hashed as i.
+    for offset in [0.0, 0.5] {
+        let xb = fastFloor(x + offset);
+        let yb = fastFloor(y + offset);
+        let zb = fastFloor(z + offset);
+        let xi = (x + offset - xb as f64) as f32;
+        let yi = (y + offset - yb as f64) as f32;
+        let zi = (z + offset - zb as f64) as f32;
+
+        for vertex in 0..8 {
+            let (cx, cy, cz) = (vertex & 1, (vertex >> 1) & 1, vertex >> 2);
+            let dx = xi - cx as f32;
+            let dy = yi - cy as f32;
+            let dz = zi - cz as f32;
+            let a = RSQUARED_3D - dx * dx - dy * dy - dz * dz;
+            if a > 0.0 {
+                value += (a * a)
+                    * (a * a)
+                    * grad3(
+                        gradients,
+                        seed,
+                        wrappedPrime(xb + cx, period[0], PRIME_X),
+                        wrappedPrime(yb + cy, period[1], PRIME_Y),
+                        wrappedPrime(zb + cz, period[2], PRIME_Z),
+                        dx,
+                        dy,
+                        dz,
+                    );
+            }
+        }
+
+        seed ^= SEED_FLIP_3D;
+    }
+
+    value
+}
+
+/*
+    Utility
+*/
+
+fn grad2Cached(
+    cache: &mut impl LatticeCache<2>,
+    slot: usize,
+    gradients: &Gradients,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+) -> f32 {
+    let g = cache.gradient(slot, || gradVector2(gradients, seed, xsvp, ysvp));
+    g[0] * dx + g[1] * dy
+}
+
+fn grad3Cached(
+    cache: &mut impl LatticeCache<3>,
+    slot: usize,
+    gradients: &Gradients,
+    seed: Wrapping<i64>,
+    xrvp: Wrapping<i64>,
+    yrvp: Wrapping<i64>,
+    zrvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+) -> f32 {
+    let g = cache.gradient(slot, || gradVector3(gradients, seed, xrvp, yrvp, zrvp));
+    g[0] * dx + g[1] * dy + g[2] * dz
+}
+
+fn grad3(
+    gradients: &Gradients,
+    seed: Wrapping<i64>,
+    xrvp: Wrapping<i64>,
+    yrvp: Wrapping<i64>,
+    zrvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+) -> f32 {
+    let g = gradVector3(gradients, seed, xrvp, yrvp, zrvp);
+    g[0] * dx + g[1] * dy + g[2] * dz
+}
+
+fn grad4(
+    gradients: &Gradients,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    zsvp: Wrapping<i64>,
+    wsvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+    dw: f32,
+) -> f32 {
+    let g = gradVector4(gradients, seed, xsvp, ysvp, zsvp, wsvp);
+    (g[0] * dx + g[1] * dy) + (g[2] * dz + g[3] * dw)
+}
+
+fn gradVector2(
+    gradients: &Gradients,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+) -> [f32; 2] {
+    let mut hash = seed ^ xsvp ^ ysvp;
+    hash *= HASH_MULTIPLIER;
+    hash ^= hash.0 >> (64 - N_GRADS_2D_EXPONENT + 1);
+    let gi = (hash.0 as i32 & ((N_GRADS_2D - 1) << 1)) as usize;
+    let grads = &gradients.gradients2D;
+    [grads[gi | 0], grads[gi | 1]]
+}
+
+fn gradVector3(
+    gradients: &Gradients,
+    seed: Wrapping<i64>,
+    xrvp: Wrapping<i64>,
+    yrvp: Wrapping<i64>,
+    zrvp: Wrapping<i64>,
+) -> [f32; 3] {
+    let mut hash = (seed ^ xrvp) ^ (yrvp ^ zrvp);
+    hash *= HASH_MULTIPLIER;
+    hash ^= hash.0 >> (64 - N_GRADS_3D_EXPONENT + 2);
+    let gi = (hash.0 as i32 & ((N_GRADS_3D - 1) << 2)) as usize;
+    let grads = &gradients.gradients3D;
+    [grads[gi | 0], grads[gi | 1], grads[gi | 2]]
+}
+
+fn gradVector4(
+    gradients: &Gradients,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    zsvp: Wrapping<i64>,
+    wsvp: Wrapping<i64>,
+) -> [f32; 4] {
+    let mut hash = seed ^ (xsvp ^ ysvp) ^ (zsvp ^ wsvp);
+    hash *= HASH_MULTIPLIER;
+    hash ^= hash.0 >> (64 - N_GRADS_4D_EXPONENT + 2);
+    let gi = (hash.0 as i32 & ((N_GRADS_4D - 1) << 2)) as usize;
+    let grads = &gradients.gradients4D;
+    [grads[gi | 0], grads[gi | 1], grads[gi | 2], grads[gi | 3]]
+}
+
+/**
+    Vertex contribution a^4 * <g, d>. Adds its derivative with respect to the
+    offset d, a^4 * g - 8 * a^3 * <g, d> * d, to `derivatives`.
+*/
+fn contribution2(
+    gradients: &Gradients,
+    derivatives: &mut [f32; 2],
+    a: f32,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+) -> f32 {
+    let g = gradVector2(gradients, seed, xsvp, ysvp);
+    let dot = g[0] * dx + g[1] * dy;
+    falloffDerivatives(derivatives, a, dot, g, [dx, dy])
+}
+
+fn contribution3(
+    gradients: &Gradients,
+    derivatives: &mut [f32; 3],
+    a: f32,
+    seed: Wrapping<i64>,
+    xrvp: Wrapping<i64>,
+    yrvp: Wrapping<i64>,
+    zrvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+) -> f32 {
+    let g = gradVector3(gradients, seed, xrvp, yrvp, zrvp);
+    let dot = g[0] * dx + g[1] * dy + g[2] * dz;
+    falloffDerivatives(derivatives, a, dot, g, [dx, dy, dz])
+}
+
+fn contribution4(
+    gradients: &Gradients,
+    derivatives: &mut [f32; 4],
+    a: f32,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    zsvp: Wrapping<i64>,
+    wsvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+    dw: f32,
+) -> f32 {
+    let g = gradVector4(gradients, seed, xsvp, ysvp, zsvp, wsvp);
+    let dot = (g[0] * dx + g[1] * dy) + (g[2] * dz + g[3] * dw);
+    falloffDerivatives(derivatives, a, dot, g, [dx, dy, dz, dw])
+}
+
+fn falloffDerivatives<const D: usize>(
+    derivatives: &mut [f32; D],
+    a: f32,
+    dot: f32,
+    g: [f32; D],
+    d: [f32; D],
+) -> f32 {
+    let aa = a * a;
+    let slope = -8.0 * aa * a * dot;
+    for ((derivative, g), d) in derivatives.iter_mut().zip(g).zip(d) {
+        *derivative += (aa * aa) * g + slope * d;
+    }
+    (aa * aa) * dot
+}
+
+fn select<T>(condition: bool, a: T, b: T) -> T {
+    if condition {
+        a
+    } else {
+        b
+    }
+}
+
+fn checkPeriod(period: &[u32]) {
+    assert!(period.iter().all(|&p| p > 0), "period must be positive");
+}
+
+/**
+    Point on a circle with circumference `period`, so that the distance
+    travelled along the circle matches the distance travelled along the axis.
+*/
+fn torusCoordinates(t: f64, period: u32) -> [f64; 2] {
+    let radius = period as f64 / std::f64::consts::TAU;
+    let angle = t / radius;
+    [radius * angle.cos(), radius * angle.sin()]
+}
+
+fn wrappedPrime(i: i32, period: u32, prime: i64) -> Wrapping<i64> {
+    Wrapping((i as i64).rem_euclid(period as i64)) * Wrapping(prime)
+}
+
+/**
+    Where the 2D and 3D bases get their vertex gradients. A base calls `enter` with its lattice
+    cell once per sample, then asks for each vertex by a slot number that names the same vertex
+    for every sample in that cell.
+*/
+trait LatticeCache<const D: usize> {
+    fn enter(&mut self, cell: [i32; 4]);
+    fn gradient(&mut self, slot: usize, hash: impl FnOnce() -> [f32; D]) -> [f32; D];
+}
+
+/**
+    Hashes every vertex, for the single-point functions.
+*/
+struct NoCache;
+
+impl<const D: usize> LatticeCache<D> for NoCache {
+    fn enter(&mut self, _cell: [i32; 4]) {}
+
+    fn gradient(&mut self, _slot: usize, hash: impl FnOnce() -> [f32; D]) -> [f32; D] {
+        hash()
+    }
+}
+
+/**
+    Keeps the gradients of the current cell, so grid samples that land in the same cell as the
+    previous one only hash the vertices it didn't use.
+*/
+struct CellCache<const D: usize> {
+    cell: [i32; 4],
+    filled: u32,
+    gradients: [[f32; D]; 16],
+}
+
+impl<const D: usize> CellCache<D> {
+    fn new() -> Self {
+        CellCache {
+            cell: [0; 4],
+            filled: 0,
+            gradients: [[0.0; D]; 16],
+        }
+    }
+}
+
+impl<const D: usize> LatticeCache<D> for CellCache<D> {
+    fn enter(&mut self, cell: [i32; 4]) {
+        if cell != self.cell {
+            self.cell = cell;
+            self.filled = 0;
+        }
+    }
+
+    fn gradient(&mut self, slot: usize, hash: impl FnOnce() -> [f32; D]) -> [f32; D] {
+        if self.filled & (1 << slot) == 0 {
+            self.gradients[slot] = hash();
+            self.filled |= 1 << slot;
+        }
+        self.gradients[slot]
+    }
+}
+
+fn gridAxis(origin: f64, step: f64, count: usize) -> Vec<f64> {
+    (0..count).map(|i| origin + i as f64 * step).collect()
+}
+
+fn checkGridLength(count: usize, out: &[f32]) {
+    assert_eq!(
+        out.len(),
+        count,
+        "output buffer length must match the grid size"
+    );
+}
+
+fn fastFloor(x: f64) -> i32 {
+    let xi = x as i32;
+    if x < xi as f64 {
+        xi - 1
+    } else {
+        xi
+    }
+}
+
+fn fastRound(x: f64) -> i32 {
+    if x < 0.0 {
+        (x - 0.5) as i32
+    } else {
+        (x + 0.5) as i32
+    }
+}
+
+/*
+    gradients
+*/
+
+struct Gradients {
+    gradients2D: Vec<f32>,
+    gradients3D: Vec<f32>,
+    gradients4D: Vec<f32>,
+}
+
+static GRADIENTS: OnceLock<Gradients> = OnceLock::new();
+
+fn getGradients() -> &'static Gradients {
+    GRADIENTS.get_or_init(initGradients)
+}
+
+/**
+    Builds the gradient tables now, so the first noise call doesn't pay for it.
+    Calling it again, or from several threads at once, is harmless.
+*/
+pub fn warm_up() {
+    getGradients();
+}
+
+fn initGradients() -> Gradients {
+    let gradients2D: Vec<_> = GRAD2_SRC
+        .iter()
+        .map(|v| (v / NORMALIZER_2D) as f32)
+        .collect::<Vec<_>>() // cache divisions
+        .into_iter()
+        .cycle()
+        .take((N_GRADS_2D * 2) as usize)
+        .collect();
+
+    let gradients3D: Vec<_> = GRAD3_SRC
+        .iter()
+        .map(|v| (v / NORMALIZER_3D) as f32)
+        .collect::<Vec<_>>() // cache divisions
+        .into_iter()
+        .cycle()
+        .take((N_GRADS_3D * 4) as usize)
+        .collect();
+
+    let gradients4D: Vec<_> = GRAD4_SRC
+        .iter()
         .map(|v| (v / NORMALIZER_4D) as f32)
         .collect::<Vec<_>>() // cache divisions
         .into_iter()
@@ -1,7 +1,39 @@
-use std::ffi::{c_double, c_float, c_longlong};
+use std::ffi::{c_char, c_double, c_float, c_int, c_longlong, c_uchar, c_uint, CStr};
+use std::ptr;
 
+use three_d::{CpuMesh, Indices, Positions, Srgba, Vec3};
+
+use crate::cellular::{self, CellularOutput, DistanceFunction};
+use crate::configuration::ConfigurationMessage;
+use crate::domain_warp::DomainWarp;
+use crate::fractal::FractalType;
+use crate::noise_graph::NoiseModule;
+use crate::noise_source::{Algorithm, Orientation};
+use crate::opensimplex2::OpenSimplex2;
+use crate::terrain_configuration::{
+    check_terrain_limits, generate_heightmap, terrain_mesh, terrain_size, update_configuration,
+    TerrainConfiguration,
+};
 use crate::{fast, smooth};
 
+pub const OPENSIMPLEX2_OK: c_int = 0;
+pub const OPENSIMPLEX2_ERROR_NULL_POINTER: c_int = -1;
+pub const OPENSIMPLEX2_ERROR_BAD_LENGTH: c_int = -2;
+pub const OPENSIMPLEX2_ERROR_BAD_ARGUMENT: c_int = -3;
+/**
+    The library broke one of its own invariants; a bug rather than a bad call.
+*/
+pub const OPENSIMPLEX2_ERROR_INTERNAL: c_int = -4;
+
+/**
+    Builds the fast and smooth lookup tables ahead of the first noise call.
+*/
+#[unsafe(no_mangle)]
+pub extern "C" fn opensimplex2_warm_up() {
+    fast::warm_up();
+    smooth::warm_up();
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn opensimplex2_fast_noise2(seed: c_longlong, x: c_double, y: c_double) -> c_float {
     fast::noise2(seed, x, y)
@@ -203,3 +235,618 @@ pub extern "C" fn opensimplex2_smooth_noise4_Fallback(
 ) -> c_float {
     smooth::noise4_Fallback(seed, x, y, z, w)
 }
+
+/**
+    `distance`: 0 Euclidean, 1 Manhattan, 2 Chebyshev.
+    `output`: 0 F1, 1 F2, 2 F2 - F1, 3 cell id.
+    Returns NaN for any other code, and for coordinates that are not finite.
+*/
+#[unsafe(no_mangle)]
+pub extern "C" fn opensimplex2_cellular_noise2(
+    seed: c_longlong,
+    x: c_double,
+    y: c_double,
+    distance: c_int,
+    output: c_int,
+) -> c_float {
+    match (cellular_distance(distance), cellular_output(output)) {
+        (Some(distance), Some(output)) => cellular::cellular2(seed, x, y, distance, output),
+        _ => c_float::NAN,
+    }
+}
+
+/**
+    Same codes as `opensimplex2_cellular_noise2`.
+*/
+#[unsafe(no_mangle)]
+pub extern "C" fn opensimplex2_cellular_noise3(
+    seed: c_longlong,
+    x: c_double,
+    y: c_double,
+    z: c_double,
+    distance: c_int,
+    output: c_int,
+) -> c_float {
+    match (cellular_distance(distance), cellular_output(output)) {
+        (Some(distance), Some(output)) => cellular::cellular3(seed, x, y, z, distance, output),
+        _ => c_float::NAN,
+    }
+}
+
+fn cellular_distance(code: c_int) -> Option<DistanceFunction> {
+    match code {
+        0 => Some(DistanceFunction::Euclidean),
+        1 => Some(DistanceFunction::Manhattan),
+        2 => Some(DistanceFunction::Chebyshev),
+        _ => None,
+    }
+}
+
+fn cellular_output(code: c_int) -> Option<CellularOutput> {
+    match code {
+        0 => Some(CellularOutput::F1),
+        1 => Some(CellularOutput::F2),
+        2 => Some(CellularOutput::F2MinusF1),
+        3 => Some(CellularOutput::CellId),
+        _ => None,
+    }
+}
+
+/*
+    Handle API
+
+    A handle owns one seeded generator. Batch calls fill a caller-owned buffer, X varying
+    fastest, and return one of the OPENSIMPLEX2_* codes instead of panicking across the
+    boundary: null handles or buffers, a `len` that doesn't match the grid size, and unknown
+    codes are reported and leave the buffer untouched.
+*/
+
+/**
+    `variant`: 0 fast, 1 smooth.
+    Returns NULL for any other code. Release the handle with `opensimplex2_destroy`.
+*/
+#[unsafe(no_mangle)]
+pub extern "C" fn opensimplex2_create(seed: c_longlong, variant: c_int) -> *mut OpenSimplex2 {
+    let Some(algorithm) = noise_algorithm(variant) else {
+        return ptr::null_mut();
+    };
+    Box::into_raw(Box::new(OpenSimplex2::new(algorithm, seed)))
+}
+
+fn noise_algorithm(code: c_int) -> Option<Algorithm> {
+    match code {
+        0 => Some(Algorithm::Fast),
+        1 => Some(Algorithm::Smooth),
+        _ => None,
+    }
+}
+
+/**
+    Frees a handle from `opensimplex2_create`. NULL is ignored.
+
+    # Safety
+    `handle` must be NULL or a live handle, and must not be used afterwards.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_destroy(handle: *mut OpenSimplex2) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/**
+    Scales every input coordinate before sampling. Defaults to 1.
+
+    # Safety
+    `handle` must be NULL or a live handle.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_set_frequency(
+    handle: *mut OpenSimplex2,
+    frequency: c_double,
+) -> c_int {
+    match handle.as_mut() {
+        Some(generator) => {
+            generator.frequency = frequency;
+            OPENSIMPLEX2_OK
+        }
+        None => OPENSIMPLEX2_ERROR_NULL_POINTER,
+    }
+}
+
+/**
+    Added to every scaled input coordinate before sampling. Defaults to 0.
+
+    # Safety
+    `handle` must be NULL or a live handle.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_set_offset(
+    handle: *mut OpenSimplex2,
+    x: c_double,
+    y: c_double,
+    z: c_double,
+    w: c_double,
+) -> c_int {
+    match handle.as_mut() {
+        Some(generator) => {
+            generator.offset = [x, y, z, w];
+            OPENSIMPLEX2_OK
+        }
+        None => OPENSIMPLEX2_ERROR_NULL_POINTER,
+    }
+}
+
+/**
+    Fills `out` with `size_x * size_y` samples starting at (`x`, `y`).
+    `orientation`: 0 standard, 1 ImproveX.
+
+    # Safety
+    `handle` must be NULL or a live handle, and `out` NULL or valid for `len` writes.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_fill_grid2(
+    handle: *const OpenSimplex2,
+    out: *mut c_float,
+    len: usize,
+    orientation: c_int,
+    x: c_double,
+    y: c_double,
+    step_x: c_double,
+    step_y: c_double,
+    size_x: usize,
+    size_y: usize,
+) -> c_int {
+    let grid = match orientation {
+        0 => OpenSimplex2::noise2_Grid,
+        1 => OpenSimplex2::noise2_ImproveX_Grid,
+        _ => return OPENSIMPLEX2_ERROR_BAD_ARGUMENT,
+    };
+    let size = [size_x, size_y];
+    match grid_buffer(handle, out, len, &size) {
+        Ok((generator, out)) => {
+            grid(generator, [x, y], [step_x, step_y], size, out);
+            OPENSIMPLEX2_OK
+        }
+        Err(code) => code,
+    }
+}
+
+/**
+    Fills `out` with `size_x * size_y * size_z` samples starting at (`x`, `y`, `z`).
+    `orientation`: 0 ImproveXY, 1 ImproveXZ, 2 Fallback.
+
+    # Safety
+    `handle` must be NULL or a live handle, and `out` NULL or valid for `len` writes.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_fill_grid3(
+    handle: *const OpenSimplex2,
+    out: *mut c_float,
+    len: usize,
+    orientation: c_int,
+    x: c_double,
+    y: c_double,
+    z: c_double,
+    step_x: c_double,
+    step_y: c_double,
+    step_z: c_double,
+    size_x: usize,
+    size_y: usize,
+    size_z: usize,
+) -> c_int {
+    let grid = match orientation {
+        0 => OpenSimplex2::noise3_ImproveXY_Grid,
+        1 => OpenSimplex2::noise3_ImproveXZ_Grid,
+        2 => OpenSimplex2::noise3_Fallback_Grid,
+        _ => return OPENSIMPLEX2_ERROR_BAD_ARGUMENT,
+    };
+    let size = [size_x, size_y, size_z];
+    match grid_buffer(handle, out, len, &size) {
+        Ok((generator, out)) => {
+            grid(generator, [x, y, z], [step_x, step_y, step_z], size, out);
+            OPENSIMPLEX2_OK
+        }
+        Err(code) => code,
+    }
+}
+
+/**
+    Checks the handle and buffer of a batch call, and that `len` is exactly the grid size.
+*/
+unsafe fn grid_buffer<'a>(
+    handle: *const OpenSimplex2,
+    out: *mut c_float,
+    len: usize,
+    size: &[usize],
+) -> Result<(&'a OpenSimplex2, &'a mut [c_float]), c_int> {
+    let generator = handle.as_ref().ok_or(OPENSIMPLEX2_ERROR_NULL_POINTER)?;
+    if out.is_null() {
+        return Err(OPENSIMPLEX2_ERROR_NULL_POINTER);
+    }
+    let count = size
+        .iter()
+        .try_fold(1usize, |count, &n| count.checked_mul(n))
+        .ok_or(OPENSIMPLEX2_ERROR_BAD_LENGTH)?;
+    if count != len {
+        return Err(OPENSIMPLEX2_ERROR_BAD_LENGTH);
+    }
+    Ok((generator, std::slice::from_raw_parts_mut(out, len)))
+}
+
+/*
+    Terrain API
+
+    Runs the viewer's pipeline (fractal or noise graph, domain warp, height curve and falloff)
+    from a plain settings struct. Query the sizes first, then pass buffers of exactly that
+    length. Heights are stored with the width axis varying fastest. Meshes are built once into
+    a handle, whose buffers hold xyz positions, triangle indices and rgba colors, one entry per
+    vertex. Any mesh buffer may be NULL to skip it.
+*/
+
+/**
+    Settings of the viewer's terrain. The viewer's mesh mode, skirts, volume, color ramp, water
+    and thread count are not exposed: meshes are always stacked cubes in the shaded base color,
+    without water, built on one thread per core.
+
+    Every float must be finite, and cube_size and failoff positive. The terrain may be at most
+    65536 columns along each axis and 4096 x 4096 in all, max_height at most 4096 cubes, and
+    fractal_octaves at most 64. Other settings give OPENSIMPLEX2_ERROR_BAD_ARGUMENT.
+*/
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OpenSimplex2TerrainConfig {
+    pub tot_width: c_float,
+    pub tot_depth: c_float,
+    pub seed: c_longlong,
+    pub cube_size: c_float,
+    pub color: [c_uchar; 3],
+    pub max_height: c_float,
+    pub failoff: c_float,
+    pub z: c_double,
+    pub fractal_octaves: c_int,
+    pub fractal_amplitude: c_float,
+    pub fractal_frequency: c_double,
+    /**
+        0 fbm, 1 ridged, 2 billow, 3 hybrid multifractal, 4 swiss, 5 jordan.
+    */
+    pub fractal_type: c_int,
+    /**
+        Nested domain warps, from 0 (no warping) to 2.
+    */
+    pub warp_levels: c_uint,
+    pub warp_seed: c_longlong,
+    pub warp_strength: c_double,
+    pub warp_frequency: c_double,
+    /**
+        0 fast, 1 smooth.
+    */
+    pub noise_algorithm: c_int,
+    /**
+        0 ImproveX, 1 ImproveXY, 2 ImproveXZ, 3 ImproveXYZ, 4 ImproveXY_ImproveZW, 5 Fallback.
+    */
+    pub noise_orientation: c_int,
+    /**
+        Noise module graph replacing the fractal and height curve, as NUL-terminated JSON in
+        the format of the REST API's /api/terrain/graph. NULL for none.
+    */
+    pub graph: *const c_char,
+}
+
+impl OpenSimplex2TerrainConfig {
+    /**
+        Converts to the viewer's configuration, or None for settings outside
+        `check_terrain_limits`, unknown codes and invalid graphs.
+
+        # Safety
+        `graph` must be NULL or a valid NUL-terminated string.
+    */
+    pub(crate) unsafe fn terrain_configuration(&self) -> Option<TerrainConfiguration> {
+        if self.warp_levels > DomainWarp::MAX_LEVELS {
+            return None;
+        }
+        let graph = if self.graph.is_null() {
+            None
+        } else {
+            let json = CStr::from_ptr(self.graph).to_str().ok()?;
+            let graph: NoiseModule = serde_json::from_str(json).ok()?;
+            graph.validate().ok()?;
+            Some(graph)
+        };
+        let messages = [
+            ConfigurationMessage::TerrainFractalType(fractal_type(self.fractal_type)?),
+            ConfigurationMessage::TerrainWarpLevels(self.warp_levels),
+            ConfigurationMessage::TerrainWarpSeed(self.warp_seed),
+            ConfigurationMessage::TerrainWarpStrength(self.warp_strength),
+            ConfigurationMessage::TerrainWarpFrequency(self.warp_frequency),
+            ConfigurationMessage::TerrainNoiseAlgorithm(noise_algorithm(self.noise_algorithm)?),
+            ConfigurationMessage::TerrainNoiseOrientation(noise_orientation(
+                self.noise_orientation,
+            )?),
+            ConfigurationMessage::TerrainGraph(graph),
+        ];
+
+        let [red, green, blue] = self.color;
+        let mut terrain_configuration = TerrainConfiguration::new(
+            self.tot_width,
+            self.tot_depth,
+            self.seed,
+            self.cube_size,
+            format!("{:02x}{:02x}{:02x}", red, green, blue),
+            self.max_height,
+            self.failoff,
+            self.z,
+            self.fractal_octaves,
+            self.fractal_amplitude,
+            self.fractal_frequency,
+        );
+        for msg in messages {
+            terrain_configuration = update_configuration(terrain_configuration, Some(msg));
+        }
+        check_terrain_limits(&terrain_configuration).ok()?;
+        Some(terrain_configuration)
+    }
+}
+
+fn fractal_type(code: c_int) -> Option<FractalType> {
+    match code {
+        0 => Some(FractalType::Fbm),
+        1 => Some(FractalType::Ridged),
+        2 => Some(FractalType::Billow),
+        3 => Some(FractalType::HybridMulti),
+        4 => Some(FractalType::Swiss),
+        5 => Some(FractalType::Jordan),
+        _ => None,
+    }
+}
+
+fn noise_orientation(code: c_int) -> Option<Orientation> {
+    match code {
+        0 => Some(Orientation::ImproveX),
+        1 => Some(Orientation::ImproveXY),
+        2 => Some(Orientation::ImproveXZ),
+        3 => Some(Orientation::ImproveXYZ),
+        4 => Some(Orientation::ImproveXYImproveZW),
+        5 => Some(Orientation::Fallback),
+        _ => None,
+    }
+}
+
+/**
+    The viewer's starting terrain.
+*/
+#[unsafe(no_mangle)]
+pub extern "C" fn opensimplex2_terrain_default_config() -> OpenSimplex2TerrainConfig {
+    let warp = DomainWarp::default();
+    OpenSimplex2TerrainConfig {
+        tot_width: 20.0,
+        tot_depth: 20.0,
+        seed: 40000345266,
+        cube_size: 0.1,
+        color: [0x30, 0x46, 0x30],
+        max_height: 2.0,
+        failoff: 200.0,
+        z: 21.0,
+        fractal_octaves: 8,
+        fractal_amplitude: 0.4,
+        fractal_frequency: 1.0,
+        fractal_type: 0,
+        warp_levels: warp.levels,
+        warp_seed: warp.seed,
+        warp_strength: warp.strength,
+        warp_frequency: warp.frequency,
+        // SuperSimplex with Y vertical
+        noise_algorithm: 1,
+        noise_orientation: 2,
+        graph: ptr::null(),
+    }
+}
+
+/**
+    Writes the number of columns along the width and depth.
+
+    # Safety
+    Every pointer must be NULL or valid, and `config->graph` NULL or a valid string.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_terrain_size(
+    config: *const OpenSimplex2TerrainConfig,
+    size_x: *mut usize,
+    size_y: *mut usize,
+) -> c_int {
+    let terrain_configuration = match terrain_configuration(config) {
+        Ok(terrain_configuration) => terrain_configuration,
+        Err(code) => return code,
+    };
+    let (Some(size_x), Some(size_y)) = (size_x.as_mut(), size_y.as_mut()) else {
+        return OPENSIMPLEX2_ERROR_NULL_POINTER;
+    };
+    (*size_x, *size_y) = terrain_size(&terrain_configuration);
+    OPENSIMPLEX2_OK
+}
+
+/**
+    Fills `out` with `size_x * size_y` column heights.
+
+    # Safety
+    `config` must be NULL or valid, `config->graph` NULL or a valid string, and `out` NULL or
+    valid for `len` writes.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_terrain_heights(
+    config: *const OpenSimplex2TerrainConfig,
+    out: *mut c_float,
+    len: usize,
+) -> c_int {
+    let terrain_configuration = match terrain_configuration(config) {
+        Ok(terrain_configuration) => terrain_configuration,
+        Err(code) => return code,
+    };
+    if out.is_null() {
+        return OPENSIMPLEX2_ERROR_NULL_POINTER;
+    }
+    let (size_x, size_y) = terrain_size(&terrain_configuration);
+    if size_x.checked_mul(size_y) != Some(len) {
+        return OPENSIMPLEX2_ERROR_BAD_LENGTH;
+    }
+
+    let heightmap = generate_heightmap(&terrain_configuration);
+    std::slice::from_raw_parts_mut(out, len).copy_from_slice(&heightmap.heights);
+    OPENSIMPLEX2_OK
+}
+
+/**
+    The voxel mesh of one generated terrain. Sizes and buffers are read from the same mesh, so
+    the terrain is generated once however many calls read it.
+*/
+pub struct OpenSimplex2TerrainMesh {
+    positions: Vec<Vec3>,
+    indices: Vec<u32>,
+    colors: Vec<Srgba>,
+}
+
+/**
+    Generates the configured terrain and meshes it into a new handle written to `*mesh`.
+    Release it with `opensimplex2_terrain_mesh_destroy`. A noise graph can raise columns past
+    the max_height limit; any column more than 4096 cubes high, or not finite, gives
+    OPENSIMPLEX2_ERROR_BAD_ARGUMENT.
+
+    # Safety
+    `config` must be NULL or valid, `config->graph` NULL or a valid string, and `mesh` NULL or
+    valid for one write.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_terrain_mesh_create(
+    config: *const OpenSimplex2TerrainConfig,
+    mesh: *mut *mut OpenSimplex2TerrainMesh,
+) -> c_int {
+    let terrain_configuration = match terrain_configuration(config) {
+        Ok(terrain_configuration) => terrain_configuration,
+        Err(code) => return code,
+    };
+    let Some(mesh) = mesh.as_mut() else {
+        return OPENSIMPLEX2_ERROR_NULL_POINTER;
+    };
+    let heightmap = generate_heightmap(&terrain_configuration);
+    let levels = |height: &f32| height.abs() / heightmap.cell_size;
+    if !heightmap
+        .heights
+        .iter()
+        .all(|height| levels(height) <= TerrainConfiguration::MAX_LEVELS)
+    {
+        return OPENSIMPLEX2_ERROR_BAD_ARGUMENT;
+    }
+    let cpu_mesh = terrain_mesh(&heightmap, &terrain_configuration);
+    let CpuMesh {
+        positions: Positions::F32(positions),
+        indices: Indices::U32(indices),
+        colors: Some(colors),
+        ..
+    } = cpu_mesh
+    else {
+        // Terrain meshes are built with f32 positions, u32 indices and colors
+        return OPENSIMPLEX2_ERROR_INTERNAL;
+    };
+    *mesh = Box::into_raw(Box::new(OpenSimplex2TerrainMesh {
+        positions,
+        indices,
+        colors,
+    }));
+    OPENSIMPLEX2_OK
+}
+
+/**
+    Frees a handle from `opensimplex2_terrain_mesh_create`. NULL is ignored.
+
+    # Safety
+    `mesh` must be NULL or a live handle, and must not be used afterwards.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_terrain_mesh_destroy(mesh: *mut OpenSimplex2TerrainMesh) {
+    if !mesh.is_null() {
+        drop(Box::from_raw(mesh));
+    }
+}
+
+/**
+    Writes the vertex and index counts of the mesh.
+
+    # Safety
+    Every pointer must be NULL or valid, and `mesh` a live handle if not NULL.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_terrain_mesh_size(
+    mesh: *const OpenSimplex2TerrainMesh,
+    vertex_count: *mut usize,
+    index_count: *mut usize,
+) -> c_int {
+    let (Some(mesh), Some(vertex_count), Some(index_count)) =
+        (mesh.as_ref(), vertex_count.as_mut(), index_count.as_mut())
+    else {
+        return OPENSIMPLEX2_ERROR_NULL_POINTER;
+    };
+    *vertex_count = mesh.positions.len();
+    *index_count = mesh.indices.len();
+    OPENSIMPLEX2_OK
+}
+
+/**
+    Fills the mesh buffers: `3 * vertex_count` positions, `index_count` indices and
+    `4 * vertex_count` colors. NULL buffers are skipped.
+
+    # Safety
+    `mesh` must be NULL or a live handle, and each buffer NULL or valid for its length in
+    writes.
+*/
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn opensimplex2_terrain_mesh(
+    mesh: *const OpenSimplex2TerrainMesh,
+    positions: *mut c_float,
+    positions_len: usize,
+    indices: *mut c_uint,
+    indices_len: usize,
+    colors: *mut c_uchar,
+    colors_len: usize,
+) -> c_int {
+    let Some(mesh) = mesh.as_ref() else {
+        return OPENSIMPLEX2_ERROR_NULL_POINTER;
+    };
+    let lengths = [
+        (positions.is_null(), positions_len, 3 * mesh.positions.len()),
+        (indices.is_null(), indices_len, mesh.indices.len()),
+        (colors.is_null(), colors_len, 4 * mesh.colors.len()),
+    ];
+    if lengths
+        .iter()
+        .any(|&(skipped, len, expected)| !skipped && len != expected)
+    {
+        return OPENSIMPLEX2_ERROR_BAD_LENGTH;
+    }
+
+    if !positions.is_null() {
+        let out = std::slice::from_raw_parts_mut(positions, positions_len);
+        for (out, position) in out.chunks_exact_mut(3).zip(&mesh.positions) {
+            out.copy_from_slice(&[position.x, position.y, position.z]);
+        }
+    }
+    if !indices.is_null() {
+        std::slice::from_raw_parts_mut(indices, indices_len).copy_from_slice(&mesh.indices);
+    }
+    if !colors.is_null() {
+        let out = std::slice::from_raw_parts_mut(colors, colors_len);
+        for (out, color) in out.chunks_exact_mut(4).zip(&mesh.colors) {
+            out.copy_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+    }
+    OPENSIMPLEX2_OK
+}
+
+unsafe fn terrain_configuration(
+    config: *const OpenSimplex2TerrainConfig,
+) -> Result<TerrainConfiguration, c_int> {
+    config
+        .as_ref()
+        .ok_or(OPENSIMPLEX2_ERROR_NULL_POINTER)?
+        .terrain_configuration()
+        .ok_or(OPENSIMPLEX2_ERROR_BAD_ARGUMENT)
+}
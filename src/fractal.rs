@@ -0,0 +1,210 @@
+/*!
+    Fractal sums of 3D noise octaves, each normalized to [0, 1].
+
+    Octave `i` is sampled at `lacunarity^i` times the base frequency with an amplitude of `gain^i`.
+    fBm keeps the original terrain's normalisation, `2 * (1 - 0.5^octaves)`, so existing worlds
+    look the same; that is the amplitude sum at a gain of 0.5, and higher gains saturate at 0 and 1.
+    The other types divide by the sum of their amplitudes, which bounds them whatever the gain.
+    The terrain's fBm scales only x and y per octave, so z picks a fixed slice; noise graphs scale
+    all three axes, as their fractal nodes always have.
+*/
+
+use crate::noise_source::NoiseSource;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FractalType {
+    /**
+        Plain fractional Brownian motion.
+    */
+    #[default]
+    Fbm,
+    /**
+        Musgrave's ridged multifractal: sharp ridges where the noise crosses zero,
+        with each octave weighted by the one below it.
+    */
+    Ridged,
+    /**
+        Absolute value of each octave, giving rounded, puffy hills.
+    */
+    Billow,
+    /**
+        Octaves weighted by the running product of the lower ones, so valleys stay
+        smooth while peaks pick up detail.
+    */
+    HybridMulti,
+    /**
+        de Carpentier's swiss turbulence: ridged octaves whose domain is warped by the
+        accumulated gradient, and whose amplitude fades in low areas.
+    */
+    Swiss,
+    /**
+        de Carpentier's jordan turbulence: squared octaves with gradient warping, and
+        detail damped on flat ground.
+    */
+    Jordan,
+}
+
+// How strongly each ridged octave weights the next, and the swiss/jordan warp and damping strengths.
+const RIDGED_WEIGHT: f32 = 2.0;
+const SWISS_WARP: f32 = 0.15;
+const JORDAN_WARP: f32 = 0.35;
+const JORDAN_DAMP: f32 = 0.8;
+
+/**
+    Evaluates `octaves` octaves of `fractal_type` at `point`, returning a value in [0, 1].
+    Returns 0.5 when there are no octaves to sum. With `scale_z` false, z is sampled at the base
+    frequency for every octave.
+*/
+#[allow(clippy::too_many_arguments)]
+pub fn fractal<N: NoiseSource + ?Sized>(
+    noise: &N,
+    fractal_type: FractalType,
+    point: [f64; 3],
+    octaves: i32,
+    gain: f32,
+    lacunarity: f64,
+    scale_z: bool,
+) -> f32 {
+    if octaves < 1 {
+        return 0.5;
+    }
+
+    let mut total: f32 = 0.0;
+    let mut amplitude: f32 = 1.0;
+    for _ in 0..octaves {
+        total += amplitude;
+        amplitude *= gain;
+    }
+
+    let z_frequency = |frequency: f64| if scale_z { frequency } else { 1.0 };
+    let sample = |frequency: f64, offset: [f32; 3]| {
+        noise.sample3(
+            point[0] * frequency + f64::from(offset[0]),
+            point[1] * frequency + f64::from(offset[1]),
+            point[2] * z_frequency(frequency) + f64::from(offset[2]),
+        )
+    };
+    let sample_with_derivatives = |frequency: f64, offset: [f32; 3]| {
+        noise.sample3_with_derivatives(
+            point[0] * frequency + f64::from(offset[0]),
+            point[1] * frequency + f64::from(offset[1]),
+            point[2] * z_frequency(frequency) + f64::from(offset[2]),
+        )
+    };
+
+    let mut value: f32 = 0.0;
+    let mut amplitude: f32 = 1.0;
+    let mut frequency: f64 = 1.0;
+    match fractal_type {
+        FractalType::Fbm => {
+            for _ in 0..octaves {
+                value += sample(frequency, [0.0; 3]) * amplitude;
+                amplitude *= gain;
+                frequency *= lacunarity;
+            }
+            let max_height = 2.0 * (1.0 - 0.5_f32.powi(octaves));
+            ((value / max_height + 1.0) * 0.5).clamp(0.0, 1.0)
+        }
+        FractalType::Billow => {
+            for _ in 0..octaves {
+                value += (sample(frequency, [0.0; 3]).abs() * 2.0 - 1.0) * amplitude;
+                amplitude *= gain;
+                frequency *= lacunarity;
+            }
+            (value / total + 1.0) * 0.5
+        }
+        FractalType::Ridged => {
+            let mut weight: f32 = 1.0;
+            for _ in 0..octaves {
+                let ridge = 1.0 - sample(frequency, [0.0; 3]).abs();
+                let signal = ridge * ridge * weight;
+                weight = (signal * RIDGED_WEIGHT).clamp(0.0, 1.0);
+                value += signal * amplitude;
+                amplitude *= gain;
+                frequency *= lacunarity;
+            }
+            value / total
+        }
+        FractalType::HybridMulti => {
+            let mut weight: f32 = 1.0;
+            for _ in 0..octaves {
+                let signal = (sample(frequency, [0.0; 3]) + 1.0) * 0.5;
+                value += weight * signal * amplitude;
+                weight *= signal;
+                amplitude *= gain;
+                frequency *= lacunarity;
+            }
+            value / total
+        }
+        FractalType::Swiss => {
+            let mut slope = [0.0f32; 3];
+            for _ in 0..octaves {
+                let frequency32 = frequency as f32;
+                let warp = slope.map(|d| d * SWISS_WARP * frequency32);
+                let (n, derivatives) = sample_with_derivatives(frequency, warp);
+                value += amplitude * (1.0 - n.abs());
+                for (d, derivative) in slope.iter_mut().zip(derivatives) {
+                    *d -= amplitude * derivative * n;
+                }
+                amplitude *= gain * value.clamp(0.0, 1.0);
+                frequency *= lacunarity;
+            }
+            value / total
+        }
+        FractalType::Jordan => {
+            let mut warp = [0.0f32; 3];
+            let mut damp = [0.0f32; 3];
+            let mut damped_amplitude: f32 = 1.0;
+            for octave in 0..octaves {
+                let (n, derivatives) = sample_with_derivatives(frequency, warp);
+                let squared = n * n;
+                value += damped_amplitude * squared;
+                for ((w, d), derivative) in warp.iter_mut().zip(damp.iter_mut()).zip(derivatives) {
+                    *w += JORDAN_WARP * n * derivative;
+                    *d += JORDAN_DAMP * n * derivative;
+                }
+                amplitude *= gain;
+                frequency *= lacunarity;
+                if octave + 1 < octaves {
+                    let steepness: f32 = damp.iter().map(|d| d * d).sum();
+                    damped_amplitude = amplitude * (1.0 - 1.0 / (1.0 + steepness));
+                }
+            }
+            value / total
+        }
+    }
+}
+
+impl fmt::Display for FractalType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FractalType::Fbm => "fbm",
+            FractalType::Ridged => "ridged",
+            FractalType::Billow => "billow",
+            FractalType::HybridMulti => "hybrid_multi",
+            FractalType::Swiss => "swiss",
+            FractalType::Jordan => "jordan",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for FractalType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fbm" => Ok(FractalType::Fbm),
+            "ridged" => Ok(FractalType::Ridged),
+            "billow" => Ok(FractalType::Billow),
+            "hybrid_multi" => Ok(FractalType::HybridMulti),
+            "swiss" => Ok(FractalType::Swiss),
+            "jordan" => Ok(FractalType::Jordan),
+            _ => Err(format!("unknown fractal type: {}", s)),
+        }
+    }
+}
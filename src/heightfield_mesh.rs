@@ -0,0 +1,145 @@
+/*!
+    Continuous surface through the heightmap samples, for smooth previews of the terrain.
+
+    Each sample becomes one vertex at its column origin and true height, and every cell between
+    four samples is split into two triangles. Skirts optionally drop the border to the ground so
+    the terrain reads as a solid block from the side.
+*/
+
+use crate::color_ramp::{slope, Palette};
+use crate::heightmap::Heightmap;
+use crate::progress::Progress;
+use rayon::prelude::*;
+use three_d::{vec3, CpuMesh, Indices, InnerSpace, Positions, Srgba, Vec3};
+
+/**
+    Number of `progress` steps `heightfield_mesh` takes for a `width` by `depth` heightmap.
+*/
+pub fn mesh_steps(_width: usize, depth: usize) -> usize {
+    depth
+}
+
+/**
+    Surface of `heightmap` colored by `palette`. Triangles wind counter-clockwise seen from
+    above (and from outside, for skirts). Returns `None` if `progress` is cancelled.
+*/
+pub fn heightfield_mesh(
+    heightmap: &Heightmap,
+    palette: &Palette,
+    skirts: bool,
+    progress: &Progress,
+) -> Option<CpuMesh> {
+    let (width, depth) = (heightmap.width, heightmap.depth);
+    let xs = heightmap.x_positions();
+    let zs = heightmap.z_positions();
+    let vertex = |i: usize, j: usize| vec3(xs[i], heightmap.get(i, j), zs[j]);
+
+    let rows: Option<Vec<Vertices>> = (0..depth)
+        .into_par_iter()
+        .map(|j| {
+            if progress.is_cancelled() {
+                return None;
+            }
+            let mut row = Vertices::default();
+            for i in 0..width {
+                // Central differences inside, one-sided on the border
+                let (i0, i1) = (i.saturating_sub(1), (i + 1).min(width - 1));
+                let (j0, j1) = (j.saturating_sub(1), (j + 1).min(depth - 1));
+                let dx = derivative(vertex(i0, j), vertex(i1, j), |p| p.x);
+                let dz = derivative(vertex(i, j0), vertex(i, j1), |p| p.z);
+                let position = vertex(i, j);
+                let normal = vec3(-dx, 1.0, -dz).normalize();
+                let color = palette.color(position.y, position.y, slope(normal));
+                row.push(position, normal, color);
+            }
+            progress.advance();
+            Some(row)
+        })
+        .collect();
+
+    let mut vertices = Vertices::default();
+    for row in rows? {
+        vertices.append(row);
+    }
+    let index = |i: usize, j: usize| (i + width * j) as u32;
+    let mut indices = Vec::new();
+    for j in 1..depth {
+        for i in 1..width {
+            let (a, b, c, d) = (
+                index(i - 1, j - 1),
+                index(i - 1, j),
+                index(i, j),
+                index(i, j - 1),
+            );
+            indices.extend([a, b, c, a, c, d]);
+        }
+    }
+
+    if skirts && width > 1 && depth > 1 {
+        // Border vertices in the order that makes every skirt face outwards
+        let border: Vec<(usize, usize)> = (0..width - 1)
+            .map(|i| (i, depth - 1))
+            .chain((1..depth).rev().map(|j| (width - 1, j)))
+            .chain((1..width).rev().map(|i| (i, 0)))
+            .chain((0..depth - 1).map(|j| (0, j)))
+            .collect();
+        for (k, &(i, j)) in border.iter().enumerate() {
+            let (ni, nj) = border[(k + 1) % border.len()];
+            vertices.push_skirt(&mut indices, vertex(i, j), vertex(ni, nj), palette);
+        }
+    }
+
+    Some(CpuMesh {
+        positions: Positions::F32(vertices.positions),
+        indices: Indices::U32(indices),
+        normals: Some(vertices.normals),
+        colors: Some(vertices.colors),
+        ..Default::default()
+    })
+}
+
+fn derivative(a: Vec3, b: Vec3, along: impl Fn(Vec3) -> f32) -> f32 {
+    let run = along(b) - along(a);
+    if run == 0.0 {
+        return 0.0;
+    }
+    (b.y - a.y) / run
+}
+
+#[derive(Default)]
+struct Vertices {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    colors: Vec<Srgba>,
+}
+
+impl Vertices {
+    fn push(&mut self, position: Vec3, normal: Vec3, color: Srgba) {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.colors.push(color);
+    }
+
+    fn append(&mut self, other: Vertices) {
+        self.positions.extend(other.positions);
+        self.normals.extend(other.normals);
+        self.colors.extend(other.colors);
+    }
+
+    /**
+        A wall from the border edge `from`-`to` down to the ground, facing outwards. Its own
+        vertices keep the flat outward normal apart from the surface normals.
+    */
+    fn push_skirt(&mut self, indices: &mut Vec<u32>, from: Vec3, to: Vec3, palette: &Palette) {
+        let along = to - from;
+        let normal = vec3(-along.z, 0.0, along.x).normalize();
+        let start = self.positions.len() as u32;
+        for top in [from, to] {
+            let ground = vec3(top.x, 0.0, top.z);
+            self.push(top, normal, palette.color(top.y, top.y, 1.0));
+            self.push(ground, normal, palette.color(0.0, 0.0, 1.0));
+        }
+        // from, from on the ground, to, to on the ground
+        indices.extend([start, start + 1, start + 3, start, start + 3, start + 2]);
+    }
+}
@@ -0,0 +1,108 @@
+/*!
+    Terrain column heights on a regular grid, kept apart from mesh building so a terrain can be
+    generated and inspected without a rendering context.
+*/
+
+/**
+    One height per terrain column, with the grid it was sampled on.
+
+    Heights are stored row by row with the width varying fastest, so the column at width step
+    `x` and depth step `z` is `heights[x + width * z]`.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightmap {
+    /**
+        Number of columns along the terrain width.
+    */
+    pub width: usize,
+    /**
+        Number of columns along the terrain depth.
+    */
+    pub depth: usize,
+    /**
+        Side of one column, which is also the spacing between samples.
+    */
+    pub cell_size: f32,
+    pub heights: Vec<f32>,
+    /**
+        Seed of the noise the heights were sampled from.
+    */
+    pub seed: i64,
+}
+
+impl Heightmap {
+    /**
+        Panics if `heights` does not hold exactly `width * depth` values.
+    */
+    pub fn new(width: usize, depth: usize, cell_size: f32, heights: Vec<f32>, seed: i64) -> Self {
+        assert_eq!(
+            heights.len(),
+            width * depth,
+            "heightmap needs one height per column"
+        );
+        Self {
+            width,
+            depth,
+            cell_size,
+            heights,
+            seed,
+        }
+    }
+
+    pub fn get(&self, x: usize, z: usize) -> f32 {
+        assert!(x < self.width && z < self.depth, "column out of range");
+        self.heights[x + self.width * z]
+    }
+
+    /**
+        Lowest column height, or `None` for an empty map.
+    */
+    pub fn min_height(&self) -> Option<f32> {
+        self.heights.iter().copied().reduce(f32::min)
+    }
+
+    /**
+        Highest column height, or `None` for an empty map.
+    */
+    pub fn max_height(&self) -> Option<f32> {
+        self.heights.iter().copied().reduce(f32::max)
+    }
+
+    /**
+        Column origins along the width, the same positions the heights were sampled at.
+    */
+    pub fn x_positions(&self) -> Vec<f32> {
+        positions(self.width, self.cell_size)
+    }
+
+    /**
+        Column origins along the depth, the same positions the heights were sampled at.
+    */
+    pub fn z_positions(&self) -> Vec<f32> {
+        positions(self.depth, self.cell_size)
+    }
+}
+
+// Accumulated rather than multiplied, so column positions match the original generator.
+pub(crate) fn positions(count: usize, cell_size: f32) -> Vec<f32> {
+    let mut positions = Vec::with_capacity(count);
+    let mut position: f32 = 0.0;
+    for _ in 0..count {
+        positions.push(position);
+        position += cell_size;
+    }
+    positions
+}
+
+/**
+    Number of columns of size `cell_size` starting inside `total`.
+*/
+pub(crate) fn column_count(total: f32, cell_size: f32) -> usize {
+    let mut count = 0;
+    let mut position: f32 = 0.0;
+    while position < total {
+        count += 1;
+        position += cell_size;
+    }
+    count
+}
@@ -1,11 +1,35 @@
 #![allow(non_snake_case)]
 
+pub mod bridge;
 pub mod camera_configuration;
+pub mod cellular;
+pub mod color_ramp;
 pub mod configuration;
+pub mod domain_warp;
 pub mod fast;
 pub mod ffi;
+pub mod fractal;
+pub mod heightfield_mesh;
+pub mod heightmap;
+pub mod light_configuration;
+pub mod mesh_mode;
+pub mod noise_graph;
+pub mod noise_source;
+pub mod opensimplex2;
+pub mod progress;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod routes;
 pub mod smooth;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod startup;
 pub mod terrain_configuration;
+pub mod terrain_worker;
 pub mod visualization;
+pub mod volume;
+pub mod volume_mesh;
+pub mod voxel_mesh;
+pub mod water;
+#[cfg(target_arch = "wasm32")]
+pub mod web;
@@ -0,0 +1,114 @@
+use crate::configuration::ConfigurationMessage;
+use three_d::*;
+
+#[derive(Debug, Clone)]
+pub struct LightConfiguration {
+    direction_x: f32,
+    direction_y: f32,
+    direction_z: f32,
+    color: String,
+    intensity: f32,
+    ambient_intensity: f32,
+    /**
+        Direction the sun shines along: the configured one, or the last non-zero one while the
+        configured direction is zero, which has no direction to normalize to.
+    */
+    sun_direction: Vec3,
+}
+
+impl LightConfiguration {
+    pub fn new(
+        direction_x: f32,
+        direction_y: f32,
+        direction_z: f32,
+        color: String,
+        intensity: f32,
+        ambient_intensity: f32,
+    ) -> Self {
+        Self {
+            direction_x,
+            direction_y,
+            direction_z,
+            color,
+            intensity,
+            ambient_intensity,
+            sun_direction: vec3(0.0, -1.0, 0.0),
+        }
+        .with_sun_direction()
+    }
+
+    pub fn direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
+    fn with_sun_direction(self) -> Self {
+        let direction = vec3(self.direction_x, self.direction_y, self.direction_z);
+        if direction.magnitude2() > 0.0 && direction.magnitude2().is_finite() {
+            Self {
+                sun_direction: direction,
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+}
+
+/**
+    The sun, a directional light shining along the configured direction, and a white ambient
+    light that keeps faces turned away from the sun visible.
+*/
+pub fn configure_lights(
+    context: &Context,
+    light_configuration: &LightConfiguration,
+) -> (DirectionalLight, AmbientLight) {
+    let color_r = u8::from_str_radix(&light_configuration.color[0..2], 16).unwrap();
+    let color_g = u8::from_str_radix(&light_configuration.color[2..4], 16).unwrap();
+    let color_b = u8::from_str_radix(&light_configuration.color[4..6], 16).unwrap();
+
+    let sun = DirectionalLight::new(
+        context,
+        light_configuration.intensity,
+        Srgba::new_opaque(color_r, color_g, color_b),
+        light_configuration.direction(),
+    );
+    let ambient = AmbientLight::new(context, light_configuration.ambient_intensity, Srgba::WHITE);
+    (sun, ambient)
+}
+
+pub fn update_configuration(
+    light_configuration: LightConfiguration,
+    msg: Option<ConfigurationMessage>,
+) -> LightConfiguration {
+    match msg {
+        Some(ConfigurationMessage::LightDirectionX(value)) => LightConfiguration {
+            direction_x: value,
+            ..light_configuration
+        }
+        .with_sun_direction(),
+        Some(ConfigurationMessage::LightDirectionY(value)) => LightConfiguration {
+            direction_y: value,
+            ..light_configuration
+        }
+        .with_sun_direction(),
+        Some(ConfigurationMessage::LightDirectionZ(value)) => LightConfiguration {
+            direction_z: value,
+            ..light_configuration
+        }
+        .with_sun_direction(),
+        Some(ConfigurationMessage::LightColor(value)) => LightConfiguration {
+            color: value,
+            ..light_configuration
+        },
+        Some(ConfigurationMessage::LightIntensity(value)) => LightConfiguration {
+            intensity: value,
+            ..light_configuration
+        },
+        Some(ConfigurationMessage::LightAmbientIntensity(value)) => LightConfiguration {
+            ambient_intensity: value,
+            ..light_configuration
+        },
+        None => light_configuration,
+        _ => light_configuration,
+    }
+}
@@ -1,18 +1,39 @@
+#[cfg(not(target_arch = "wasm32"))]
 use std::sync::mpsc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
 use std::thread;
+#[cfg(not(target_arch = "wasm32"))]
 use terrainopensimplex2::configuration::ConfigurationMessage;
+#[cfg(not(target_arch = "wasm32"))]
+use terrainopensimplex2::{fast, smooth};
+#[cfg(not(target_arch = "wasm32"))]
 use terrainopensimplex2::startup::start_server;
+#[cfg(not(target_arch = "wasm32"))]
+use terrainopensimplex2::terrain_worker::TerrainStatus;
+#[cfg(not(target_arch = "wasm32"))]
 use terrainopensimplex2::visualization::window;
 
+#[cfg(not(target_arch = "wasm32"))]
 #[tokio::main]
 async fn main() {
+    fast::warm_up();
+    smooth::warm_up();
+
     let (tx, rx) = mpsc::channel::<ConfigurationMessage>();
+    let status = Arc::new(TerrainStatus::new());
 
+    let server_status = status.clone();
     thread::spawn(move || {
         actix_web::rt::System::new().block_on(async {
-            start_server(tx).await.unwrap();
+            start_server(tx, server_status).await.unwrap();
         });
     });
 
-    window(rx);
+    window(rx, status);
 }
+
+// The browser build starts from `web::run`, called by web/index.html.
+#[cfg(target_arch = "wasm32")]
+fn main() {}
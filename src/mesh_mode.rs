@@ -0,0 +1,37 @@
+/*!
+    How the viewer turns a heightmap into triangles.
+*/
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeshMode {
+    /**
+        Stacked cubes, one column per heightmap sample.
+    */
+    #[default]
+    Voxels,
+    /**
+        One continuous triangulated surface through the samples, with per-vertex normals.
+    */
+    Heightfield,
+    /**
+        Surface of a 3D density built on the heightmap, with caves and overhangs.
+    */
+    Volume,
+}
+
+impl FromStr for MeshMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "voxels" => Ok(MeshMode::Voxels),
+            "heightfield" => Ok(MeshMode::Heightfield),
+            "volume" => Ok(MeshMode::Volume),
+            _ => Err(format!("unknown mesh mode: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,415 @@
+/*!
+    libnoise-style module graph evaluated on top of a `NoiseSource`.
+
+    A graph is plain serde data, so it can be built and sent over the REST API without
+    touching Rust code. Modules are tagged by `type`, for example:
+
+    ```json
+    {
+        "type": "scale_bias",
+        "scale": 0.5,
+        "bias": 0.5,
+        "source": { "type": "fractal", "frequency": 0.5, "octaves": 6 }
+    }
+    ```
+*/
+
+use crate::fractal::{fractal, FractalType};
+use crate::noise_source::NoiseSource;
+use crate::terrain_configuration::piecewise_linear;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NoiseModule {
+    /**
+        The same value everywhere.
+    */
+    Constant {
+        value: f32,
+    },
+    /**
+        A single sample of the noise source, in [-1, 1].
+    */
+    Noise {
+        #[serde(default = "one")]
+        frequency: f64,
+        #[serde(default)]
+        seed_offset: i64,
+    },
+    /**
+        Octaves of the noise source, normalized to [-1, 1].
+    */
+    Fractal {
+        #[serde(default)]
+        fractal_type: FractalType,
+        #[serde(default = "one")]
+        frequency: f64,
+        #[serde(default = "default_octaves")]
+        octaves: i32,
+        #[serde(default = "default_persistence")]
+        persistence: f32,
+        #[serde(default = "default_lacunarity")]
+        lacunarity: f64,
+        #[serde(default)]
+        seed_offset: i64,
+    },
+    Add {
+        sources: Vec<NoiseModule>,
+    },
+    Multiply {
+        sources: Vec<NoiseModule>,
+    },
+    Min {
+        sources: Vec<NoiseModule>,
+    },
+    Max {
+        sources: Vec<NoiseModule>,
+    },
+    /**
+        `high` where `control` lies within [lower_bound, upper_bound] and `low` elsewhere,
+        blended with an S-curve over `edge_falloff` on both sides of each bound.
+    */
+    Select {
+        control: Box<NoiseModule>,
+        low: Box<NoiseModule>,
+        high: Box<NoiseModule>,
+        lower_bound: f32,
+        upper_bound: f32,
+        #[serde(default)]
+        edge_falloff: f32,
+    },
+    Clamp {
+        source: Box<NoiseModule>,
+        lower: f32,
+        upper: f32,
+    },
+    /**
+        `source * scale + bias`.
+    */
+    ScaleBias {
+        source: Box<NoiseModule>,
+        #[serde(default = "one_f32")]
+        scale: f32,
+        #[serde(default)]
+        bias: f32,
+    },
+    /**
+        Remaps the source through a piecewise linear table of `(input, output)` points,
+        sorted by input.
+    */
+    Curve {
+        source: Box<NoiseModule>,
+        points: Vec<(f32, f32)>,
+    },
+    /**
+        Flattens the source into terraces between the given sorted levels.
+        Each terrace rises steeply after a level, or before it when `invert` is set.
+    */
+    Terrace {
+        source: Box<NoiseModule>,
+        points: Vec<f32>,
+        #[serde(default)]
+        invert: bool,
+    },
+    /**
+        Samples the source at coordinates displaced by up to `power` along each axis.
+        The displacement is fractal noise with `roughness` octaves.
+    */
+    Turbulence {
+        source: Box<NoiseModule>,
+        #[serde(default = "one")]
+        frequency: f64,
+        power: f64,
+        #[serde(default = "default_roughness")]
+        roughness: i32,
+        #[serde(default)]
+        seed_offset: i64,
+    },
+}
+
+fn one() -> f64 {
+    1.0
+}
+
+fn one_f32() -> f32 {
+    1.0
+}
+
+fn default_octaves() -> i32 {
+    6
+}
+
+fn default_persistence() -> f32 {
+    0.5
+}
+
+fn default_lacunarity() -> f64 {
+    2.0
+}
+
+fn default_roughness() -> i32 {
+    3
+}
+
+// Keeps the three turbulence displacements off each other's lattice.
+const TURBULENCE_OFFSETS: [[f64; 3]; 3] = [
+    [12414.0 / 65536.0, 65124.0 / 65536.0, 31337.0 / 65536.0],
+    [26519.0 / 65536.0, 18128.0 / 65536.0, 60493.0 / 65536.0],
+    [53820.0 / 65536.0, 11213.0 / 65536.0, 44845.0 / 65536.0],
+];
+
+impl NoiseModule {
+    /**
+        Evaluates the graph at a point. Noise leaves sample a copy of `noise` with their offset
+        added to its seed.
+    */
+    pub fn get<N: NoiseSource>(&self, noise: &N, x: f64, y: f64, z: f64) -> f32 {
+        match self {
+            NoiseModule::Constant { value } => *value,
+            NoiseModule::Noise {
+                frequency,
+                seed_offset,
+            } => offset_seed(noise, *seed_offset).sample3(
+                x * frequency,
+                y * frequency,
+                z * frequency,
+            ),
+            NoiseModule::Fractal {
+                fractal_type,
+                frequency,
+                octaves,
+                persistence,
+                lacunarity,
+                seed_offset,
+            } => {
+                let value = fractal(
+                    &offset_seed(noise, *seed_offset),
+                    *fractal_type,
+                    [x * frequency, y * frequency, z * frequency],
+                    *octaves,
+                    *persistence,
+                    *lacunarity,
+                    true,
+                );
+                value * 2.0 - 1.0
+            }
+            NoiseModule::Add { sources } => sources
+                .iter()
+                .map(|source| source.get(noise, x, y, z))
+                .sum(),
+            NoiseModule::Multiply { sources } => sources
+                .iter()
+                .map(|source| source.get(noise, x, y, z))
+                .product(),
+            NoiseModule::Min { sources } => sources
+                .iter()
+                .map(|source| source.get(noise, x, y, z))
+                .fold(f32::INFINITY, f32::min),
+            NoiseModule::Max { sources } => sources
+                .iter()
+                .map(|source| source.get(noise, x, y, z))
+                .fold(f32::NEG_INFINITY, f32::max),
+            NoiseModule::Select {
+                control,
+                low,
+                high,
+                lower_bound,
+                upper_bound,
+                edge_falloff,
+            } => {
+                let control = control.get(noise, x, y, z);
+                let falloff = edge_falloff.min((upper_bound - lower_bound) * 0.5);
+                let weight = select_weight(control, *lower_bound, *upper_bound, falloff);
+                if weight <= 0.0 {
+                    low.get(noise, x, y, z)
+                } else if weight >= 1.0 {
+                    high.get(noise, x, y, z)
+                } else {
+                    let low = low.get(noise, x, y, z);
+                    let high = high.get(noise, x, y, z);
+                    low + (high - low) * weight
+                }
+            }
+            NoiseModule::Clamp {
+                source,
+                lower,
+                upper,
+            } => source.get(noise, x, y, z).clamp(*lower, *upper),
+            NoiseModule::ScaleBias {
+                source,
+                scale,
+                bias,
+            } => source.get(noise, x, y, z) * scale + bias,
+            NoiseModule::Curve { source, points } => {
+                piecewise_linear(points, source.get(noise, x, y, z))
+            }
+            NoiseModule::Terrace {
+                source,
+                points,
+                invert,
+            } => terrace(points, *invert, source.get(noise, x, y, z)),
+            NoiseModule::Turbulence {
+                source,
+                frequency,
+                power,
+                roughness,
+                seed_offset,
+            } => {
+                let turbulence = offset_seed(noise, *seed_offset);
+                let displacement = TURBULENCE_OFFSETS.map(|[ox, oy, oz]| {
+                    let point = [
+                        (x + ox) * frequency,
+                        (y + oy) * frequency,
+                        (z + oz) * frequency,
+                    ];
+                    let value = fractal(
+                        &turbulence,
+                        FractalType::Fbm,
+                        point,
+                        *roughness,
+                        0.5,
+                        2.0,
+                        true,
+                    );
+                    f64::from(value * 2.0 - 1.0) * power
+                });
+                source.get(
+                    noise,
+                    x + displacement[0],
+                    y + displacement[1],
+                    z + displacement[2],
+                )
+            }
+        }
+    }
+
+    /**
+        Checks the parameters a graph cannot be evaluated without, describing the first problem found.
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            NoiseModule::Constant { .. } | NoiseModule::Noise { .. } => Ok(()),
+            NoiseModule::Fractal { octaves, .. } => {
+                if *octaves < 1 {
+                    return Err(format!(
+                        "fractal needs at least one octave, got {}",
+                        octaves
+                    ));
+                }
+                Ok(())
+            }
+            NoiseModule::Add { sources } | NoiseModule::Multiply { sources } => {
+                sources.iter().try_for_each(NoiseModule::validate)
+            }
+            NoiseModule::Min { sources } | NoiseModule::Max { sources } => {
+                if sources.is_empty() {
+                    return Err("min and max need at least one source".to_string());
+                }
+                sources.iter().try_for_each(NoiseModule::validate)
+            }
+            NoiseModule::Select {
+                control,
+                low,
+                high,
+                lower_bound,
+                upper_bound,
+                ..
+            } => {
+                if lower_bound > upper_bound {
+                    return Err(format!(
+                        "select lower bound {} is above upper bound {}",
+                        lower_bound, upper_bound
+                    ));
+                }
+                control.validate()?;
+                low.validate()?;
+                high.validate()
+            }
+            NoiseModule::Clamp {
+                source,
+                lower,
+                upper,
+            } => {
+                if lower > upper {
+                    return Err(format!("clamp lower {} is above upper {}", lower, upper));
+                }
+                source.validate()
+            }
+            NoiseModule::ScaleBias { source, .. } => source.validate(),
+            NoiseModule::Curve { source, points } => {
+                if points.len() < 2 || points.windows(2).any(|w| w[0].0 >= w[1].0) {
+                    return Err(
+                        "curve needs at least two points with increasing inputs".to_string()
+                    );
+                }
+                source.validate()
+            }
+            NoiseModule::Terrace { source, points, .. } => {
+                if points.len() < 2 || points.windows(2).any(|w| w[0] >= w[1]) {
+                    return Err("terrace needs at least two increasing points".to_string());
+                }
+                source.validate()
+            }
+            NoiseModule::Turbulence {
+                source, roughness, ..
+            } => {
+                if *roughness < 1 {
+                    return Err(format!(
+                        "turbulence needs a roughness of at least one, got {}",
+                        roughness
+                    ));
+                }
+                source.validate()
+            }
+        }
+    }
+}
+
+fn s_curve(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn offset_seed<N: NoiseSource>(noise: &N, seed_offset: i64) -> N {
+    noise.with_seed(noise.seed().wrapping_add(seed_offset))
+}
+
+fn select_weight(control: f32, lower: f32, upper: f32, falloff: f32) -> f32 {
+    if falloff <= 0.0 {
+        return if control >= lower && control <= upper {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    if control < lower - falloff || control > upper + falloff {
+        0.0
+    } else if control < lower + falloff {
+        s_curve((control - (lower - falloff)) / (2.0 * falloff))
+    } else if control > upper - falloff {
+        s_curve(((upper + falloff) - control) / (2.0 * falloff))
+    } else {
+        1.0
+    }
+}
+
+fn terrace(points: &[f32], invert: bool, value: f32) -> f32 {
+    let upper = points
+        .iter()
+        .position(|&point| point > value)
+        .unwrap_or(points.len());
+    let index0 = upper.saturating_sub(1).min(points.len() - 1);
+    let index1 = upper.min(points.len() - 1);
+    if index0 == index1 {
+        return points[index1];
+    }
+
+    let (mut value0, mut value1) = (points[index0], points[index1]);
+    let mut alpha = (value - value0) / (value1 - value0);
+    if invert {
+        alpha = 1.0 - alpha;
+        std::mem::swap(&mut value0, &mut value1);
+    }
+    alpha *= alpha;
+    value0 + (value1 - value0) * alpha
+}
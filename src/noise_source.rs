@@ -0,0 +1,256 @@
+/*!
+    Common interface over the OpenSimplex2 variants in `fast` and `smooth`.
+*/
+
+use crate::{fast, smooth};
+use std::fmt;
+use std::str::FromStr;
+
+/**
+    Anything that can be sampled as 2D, 3D and 4D noise. A source carries its own seed, and
+    layers that must not repeat it, such as the domain warp or offset graph leaves, sample a
+    copy under another seed.
+*/
+pub trait NoiseSource {
+    fn seed(&self) -> i64;
+
+    /**
+        The same noise under `seed`. Sources without a seed return an unchanged copy.
+    */
+    fn with_seed(&self, seed: i64) -> Self
+    where
+        Self: Sized;
+
+    fn sample2(&self, x: f64, y: f64) -> f32;
+    fn sample3(&self, x: f64, y: f64, z: f64) -> f32;
+    fn sample4(&self, x: f64, y: f64, z: f64, w: f64) -> f32;
+
+    /**
+        3D sample with its gradient. Defaults to central differences of `sample3`.
+    */
+    fn sample3_with_derivatives(&self, x: f64, y: f64, z: f64) -> (f32, [f32; 3]) {
+        const STEP: f64 = 1e-4;
+        let difference = |dx: f64, dy: f64, dz: f64| {
+            let forward = self.sample3(x + dx, y + dy, z + dz);
+            let backward = self.sample3(x - dx, y - dy, z - dz);
+            ((f64::from(forward) - f64::from(backward)) / (2.0 * STEP)) as f32
+        };
+        (
+            self.sample3(x, y, z),
+            [
+                difference(STEP, 0.0, 0.0),
+                difference(0.0, STEP, 0.0),
+                difference(0.0, 0.0, STEP),
+            ],
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Fast,
+    Smooth,
+}
+
+/**
+    Lattice orientation. Each one picks the closest matching function per dimension:
+
+    | Orientation          | 2D              | 3D                | 4D                           |
+    |----------------------|-----------------|-------------------|------------------------------|
+    | `ImproveX`           | noise2_ImproveX | noise3_ImproveXZ  | noise4_ImproveXYZ_ImproveXZ  |
+    | `ImproveXY`          | noise2          | noise3_ImproveXY  | noise4_ImproveXYZ_ImproveXY  |
+    | `ImproveXZ`          | noise2          | noise3_ImproveXZ  | noise4_ImproveXYZ_ImproveXZ  |
+    | `ImproveXYZ`         | noise2          | noise3_Fallback   | noise4_ImproveXYZ            |
+    | `ImproveXYImproveZW` | noise2          | noise3_ImproveXY  | noise4_ImproveXY_ImproveZW   |
+    | `Fallback`           | noise2          | noise3_Fallback   | noise4_Fallback              |
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    ImproveX,
+    ImproveXY,
+    ImproveXZ,
+    ImproveXYZ,
+    ImproveXYImproveZW,
+    Fallback,
+}
+
+/**
+    One of the `fast`/`smooth` function families, selected at runtime. Like those functions it
+    takes the seed with every sample; `OpenSimplex2` is the seeded `NoiseSource` over it.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoiseVariant {
+    pub algorithm: Algorithm,
+    pub orientation: Orientation,
+}
+
+type Noise2 = fn(i64, f64, f64) -> f32;
+type Noise3 = fn(i64, f64, f64, f64) -> f32;
+type Noise4 = fn(i64, f64, f64, f64, f64) -> f32;
+type Noise3WithDerivatives = fn(i64, f64, f64, f64) -> (f32, [f32; 3]);
+
+impl NoiseVariant {
+    pub fn new(algorithm: Algorithm, orientation: Orientation) -> Self {
+        Self {
+            algorithm,
+            orientation,
+        }
+    }
+
+    pub fn sample2(&self, seed: i64, x: f64, y: f64) -> f32 {
+        (self.functions().0)(seed, x, y)
+    }
+
+    pub fn sample3(&self, seed: i64, x: f64, y: f64, z: f64) -> f32 {
+        (self.functions().1)(seed, x, y, z)
+    }
+
+    pub fn sample4(&self, seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+        (self.functions().2)(seed, x, y, z, w)
+    }
+
+    /**
+        3D sample with its analytic gradient.
+    */
+    pub fn sample3_with_derivatives(&self, seed: i64, x: f64, y: f64, z: f64) -> (f32, [f32; 3]) {
+        (self.functions().3)(seed, x, y, z)
+    }
+
+    fn functions(&self) -> (Noise2, Noise3, Noise4, Noise3WithDerivatives) {
+        match (self.algorithm, self.orientation) {
+            (Algorithm::Fast, Orientation::ImproveX) => (
+                fast::noise2_ImproveX,
+                fast::noise3_ImproveXZ,
+                fast::noise4_ImproveXYZ_ImproveXZ,
+                fast::noise3_ImproveXZ_with_derivatives,
+            ),
+            (Algorithm::Fast, Orientation::ImproveXY) => (
+                fast::noise2,
+                fast::noise3_ImproveXY,
+                fast::noise4_ImproveXYZ_ImproveXY,
+                fast::noise3_ImproveXY_with_derivatives,
+            ),
+            (Algorithm::Fast, Orientation::ImproveXZ) => (
+                fast::noise2,
+                fast::noise3_ImproveXZ,
+                fast::noise4_ImproveXYZ_ImproveXZ,
+                fast::noise3_ImproveXZ_with_derivatives,
+            ),
+            (Algorithm::Fast, Orientation::ImproveXYZ) => (
+                fast::noise2,
+                fast::noise3_Fallback,
+                fast::noise4_ImproveXYZ,
+                fast::noise3_Fallback_with_derivatives,
+            ),
+            (Algorithm::Fast, Orientation::ImproveXYImproveZW) => (
+                fast::noise2,
+                fast::noise3_ImproveXY,
+                fast::noise4_ImproveXY_ImproveZW,
+                fast::noise3_ImproveXY_with_derivatives,
+            ),
+            (Algorithm::Fast, Orientation::Fallback) => (
+                fast::noise2,
+                fast::noise3_Fallback,
+                fast::noise4_Fallback,
+                fast::noise3_Fallback_with_derivatives,
+            ),
+            (Algorithm::Smooth, Orientation::ImproveX) => (
+                smooth::noise2_ImproveX,
+                smooth::noise3_ImproveXZ,
+                smooth::noise4_ImproveXYZ_ImproveXZ,
+                smooth::noise3_ImproveXZ_with_derivatives,
+            ),
+            (Algorithm::Smooth, Orientation::ImproveXY) => (
+                smooth::noise2,
+                smooth::noise3_ImproveXY,
+                smooth::noise4_ImproveXYZ_ImproveXY,
+                smooth::noise3_ImproveXY_with_derivatives,
+            ),
+            (Algorithm::Smooth, Orientation::ImproveXZ) => (
+                smooth::noise2,
+                smooth::noise3_ImproveXZ,
+                smooth::noise4_ImproveXYZ_ImproveXZ,
+                smooth::noise3_ImproveXZ_with_derivatives,
+            ),
+            (Algorithm::Smooth, Orientation::ImproveXYZ) => (
+                smooth::noise2,
+                smooth::noise3_Fallback,
+                smooth::noise4_ImproveXYZ,
+                smooth::noise3_Fallback_with_derivatives,
+            ),
+            (Algorithm::Smooth, Orientation::ImproveXYImproveZW) => (
+                smooth::noise2,
+                smooth::noise3_ImproveXY,
+                smooth::noise4_ImproveXY_ImproveZW,
+                smooth::noise3_ImproveXY_with_derivatives,
+            ),
+            (Algorithm::Smooth, Orientation::Fallback) => (
+                smooth::noise2,
+                smooth::noise3_Fallback,
+                smooth::noise4_Fallback,
+                smooth::noise3_Fallback_with_derivatives,
+            ),
+        }
+    }
+}
+
+impl Default for NoiseVariant {
+    /**
+        SuperSimplex with Y vertical, the terrain generator's original noise.
+    */
+    fn default() -> Self {
+        Self::new(Algorithm::Smooth, Orientation::ImproveXZ)
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Algorithm::Fast => "fast",
+            Algorithm::Smooth => "smooth",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fast" => Ok(Algorithm::Fast),
+            "smooth" => Ok(Algorithm::Smooth),
+            _ => Err(format!("unknown noise algorithm: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Orientation::ImproveX => "ImproveX",
+            Orientation::ImproveXY => "ImproveXY",
+            Orientation::ImproveXZ => "ImproveXZ",
+            Orientation::ImproveXYZ => "ImproveXYZ",
+            Orientation::ImproveXYImproveZW => "ImproveXY_ImproveZW",
+            Orientation::Fallback => "Fallback",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ImproveX" => Ok(Orientation::ImproveX),
+            "ImproveXY" => Ok(Orientation::ImproveXY),
+            "ImproveXZ" => Ok(Orientation::ImproveXZ),
+            "ImproveXYZ" => Ok(Orientation::ImproveXYZ),
+            "ImproveXY_ImproveZW" => Ok(Orientation::ImproveXYImproveZW),
+            "Fallback" => Ok(Orientation::Fallback),
+            _ => Err(format!("unknown noise orientation: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,329 @@
+/*!
+    Seeded OpenSimplex2 generator, for callers that keep one noise layer around instead of
+    passing a raw seed to every `fast`/`smooth` call.
+*/
+
+use crate::noise_source::{Algorithm, NoiseSource, NoiseVariant, Orientation};
+use crate::{fast, smooth};
+
+/**
+    One noise layer: an algorithm, a seed, and the transform applied to every input point.
+
+    Coordinates are mapped to `point * frequency + offset` before sampling, so the offset is
+    in noise space and shifts the pattern by the same amount at every frequency.
+
+    As a `NoiseSource`, `orientation` picks the functions sampled. Without a transform, the
+    layer samples exactly like its `NoiseVariant` under its seed.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpenSimplex2 {
+    pub algorithm: Algorithm,
+    /**
+        Used by the `NoiseSource` samples only; the named methods each fix their own.
+    */
+    pub orientation: Orientation,
+    pub seed: i64,
+    pub frequency: f64,
+    pub offset: [f64; 4],
+}
+
+impl OpenSimplex2 {
+    pub fn new(algorithm: Algorithm, seed: i64) -> Self {
+        Self {
+            algorithm,
+            // Y vertical, as in the terrain
+            orientation: Orientation::ImproveXZ,
+            seed,
+            frequency: 1.0,
+            offset: [0.0; 4],
+        }
+    }
+
+    /**
+        Generator over `fast`, the faster OpenSimplex2 variant.
+    */
+    pub fn fast(seed: i64) -> Self {
+        Self::new(Algorithm::Fast, seed)
+    }
+
+    /**
+        Generator over `smooth`, the OpenSimplex2S variant.
+    */
+    pub fn smooth(seed: i64) -> Self {
+        Self::new(Algorithm::Smooth, seed)
+    }
+
+    pub fn with_orientation(self, orientation: Orientation) -> Self {
+        Self {
+            orientation,
+            ..self
+        }
+    }
+
+    pub fn with_frequency(self, frequency: f64) -> Self {
+        Self { frequency, ..self }
+    }
+
+    pub fn with_offset(self, offset: [f64; 4]) -> Self {
+        Self { offset, ..self }
+    }
+
+    /*
+     * Noise Evaluators
+     */
+
+    /**
+        2D Simplex noise, standard lattice orientation.
+    */
+    pub fn noise2(&self, x: f64, y: f64) -> f32 {
+        let [x, y] = self.transform([x, y]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise2(self.seed, x, y),
+            Algorithm::Smooth => smooth::noise2(self.seed, x, y),
+        }
+    }
+
+    /**
+        2D Simplex noise, with Y pointing down the main diagonal.
+    */
+    pub fn noise2_ImproveX(&self, x: f64, y: f64) -> f32 {
+        let [x, y] = self.transform([x, y]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise2_ImproveX(self.seed, x, y),
+            Algorithm::Smooth => smooth::noise2_ImproveX(self.seed, x, y),
+        }
+    }
+
+    /**
+        3D OpenSimplex2 noise, with better visual isotropy in (X, Y).
+    */
+    pub fn noise3_ImproveXY(&self, x: f64, y: f64, z: f64) -> f32 {
+        let [x, y, z] = self.transform([x, y, z]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise3_ImproveXY(self.seed, x, y, z),
+            Algorithm::Smooth => smooth::noise3_ImproveXY(self.seed, x, y, z),
+        }
+    }
+
+    /**
+        3D OpenSimplex2 noise, with better visual isotropy in (X, Z).
+    */
+    pub fn noise3_ImproveXZ(&self, x: f64, y: f64, z: f64) -> f32 {
+        let [x, y, z] = self.transform([x, y, z]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise3_ImproveXZ(self.seed, x, y, z),
+            Algorithm::Smooth => smooth::noise3_ImproveXZ(self.seed, x, y, z),
+        }
+    }
+
+    /**
+        3D OpenSimplex2 noise, fallback rotation option.
+    */
+    pub fn noise3_Fallback(&self, x: f64, y: f64, z: f64) -> f32 {
+        let [x, y, z] = self.transform([x, y, z]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise3_Fallback(self.seed, x, y, z),
+            Algorithm::Smooth => smooth::noise3_Fallback(self.seed, x, y, z),
+        }
+    }
+
+    /**
+        4D OpenSimplex2 noise, with XYZ oriented like noise3_ImproveXY.
+    */
+    pub fn noise4_ImproveXYZ_ImproveXY(&self, x: f64, y: f64, z: f64, w: f64) -> f32 {
+        let [x, y, z, w] = self.transform([x, y, z, w]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise4_ImproveXYZ_ImproveXY(self.seed, x, y, z, w),
+            Algorithm::Smooth => smooth::noise4_ImproveXYZ_ImproveXY(self.seed, x, y, z, w),
+        }
+    }
+
+    /**
+        4D OpenSimplex2 noise, with XYZ oriented like noise3_ImproveXZ.
+    */
+    pub fn noise4_ImproveXYZ_ImproveXZ(&self, x: f64, y: f64, z: f64, w: f64) -> f32 {
+        let [x, y, z, w] = self.transform([x, y, z, w]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise4_ImproveXYZ_ImproveXZ(self.seed, x, y, z, w),
+            Algorithm::Smooth => smooth::noise4_ImproveXYZ_ImproveXZ(self.seed, x, y, z, w),
+        }
+    }
+
+    /**
+        4D OpenSimplex2 noise, with XYZ oriented like noise3_Fallback.
+    */
+    pub fn noise4_ImproveXYZ(&self, x: f64, y: f64, z: f64, w: f64) -> f32 {
+        let [x, y, z, w] = self.transform([x, y, z, w]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise4_ImproveXYZ(self.seed, x, y, z, w),
+            Algorithm::Smooth => smooth::noise4_ImproveXYZ(self.seed, x, y, z, w),
+        }
+    }
+
+    /**
+        4D OpenSimplex2 noise, with XY and ZW forming orthogonal triangular-based planes.
+    */
+    pub fn noise4_ImproveXY_ImproveZW(&self, x: f64, y: f64, z: f64, w: f64) -> f32 {
+        let [x, y, z, w] = self.transform([x, y, z, w]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise4_ImproveXY_ImproveZW(self.seed, x, y, z, w),
+            Algorithm::Smooth => smooth::noise4_ImproveXY_ImproveZW(self.seed, x, y, z, w),
+        }
+    }
+
+    /**
+        4D OpenSimplex2 noise, fallback lattice orientation.
+    */
+    pub fn noise4_Fallback(&self, x: f64, y: f64, z: f64, w: f64) -> f32 {
+        let [x, y, z, w] = self.transform([x, y, z, w]);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise4_Fallback(self.seed, x, y, z, w),
+            Algorithm::Smooth => smooth::noise4_Fallback(self.seed, x, y, z, w),
+        }
+    }
+
+    /*
+     * Grid Evaluators
+     *
+     * Same layout as the `fast`/`smooth` grid functions. The frequency and offset are applied
+     * to the grid origin and step, so samples can differ from the scalar methods by rounding.
+     */
+
+    /**
+        2D Simplex noise, standard lattice orientation, on a regular grid.
+    */
+    pub fn noise2_Grid(&self, origin: [f64; 2], step: [f64; 2], size: [usize; 2], out: &mut [f32]) {
+        let (origin, step) = self.transform_grid(origin, step);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise2_Grid(self.seed, origin, step, size, out),
+            Algorithm::Smooth => smooth::noise2_Grid(self.seed, origin, step, size, out),
+        }
+    }
+
+    /**
+        2D Simplex noise, with Y pointing down the main diagonal, on a regular grid.
+    */
+    pub fn noise2_ImproveX_Grid(
+        &self,
+        origin: [f64; 2],
+        step: [f64; 2],
+        size: [usize; 2],
+        out: &mut [f32],
+    ) {
+        let (origin, step) = self.transform_grid(origin, step);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise2_ImproveX_Grid(self.seed, origin, step, size, out),
+            Algorithm::Smooth => smooth::noise2_ImproveX_Grid(self.seed, origin, step, size, out),
+        }
+    }
+
+    /**
+        3D OpenSimplex2 noise, with better visual isotropy in (X, Y), on a regular grid.
+    */
+    pub fn noise3_ImproveXY_Grid(
+        &self,
+        origin: [f64; 3],
+        step: [f64; 3],
+        size: [usize; 3],
+        out: &mut [f32],
+    ) {
+        let (origin, step) = self.transform_grid(origin, step);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise3_ImproveXY_Grid(self.seed, origin, step, size, out),
+            Algorithm::Smooth => smooth::noise3_ImproveXY_Grid(self.seed, origin, step, size, out),
+        }
+    }
+
+    /**
+        3D OpenSimplex2 noise, with better visual isotropy in (X, Z), on a regular grid.
+    */
+    pub fn noise3_ImproveXZ_Grid(
+        &self,
+        origin: [f64; 3],
+        step: [f64; 3],
+        size: [usize; 3],
+        out: &mut [f32],
+    ) {
+        let (origin, step) = self.transform_grid(origin, step);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise3_ImproveXZ_Grid(self.seed, origin, step, size, out),
+            Algorithm::Smooth => smooth::noise3_ImproveXZ_Grid(self.seed, origin, step, size, out),
+        }
+    }
+
+    /**
+        3D OpenSimplex2 noise, fallback rotation option, on a regular grid.
+    */
+    pub fn noise3_Fallback_Grid(
+        &self,
+        origin: [f64; 3],
+        step: [f64; 3],
+        size: [usize; 3],
+        out: &mut [f32],
+    ) {
+        let (origin, step) = self.transform_grid(origin, step);
+        match self.algorithm {
+            Algorithm::Fast => fast::noise3_Fallback_Grid(self.seed, origin, step, size, out),
+            Algorithm::Smooth => smooth::noise3_Fallback_Grid(self.seed, origin, step, size, out),
+        }
+    }
+
+    /*
+     * Utility
+     */
+
+    fn variant(&self) -> NoiseVariant {
+        NoiseVariant::new(self.algorithm, self.orientation)
+    }
+
+    fn transform<const N: usize>(&self, point: [f64; N]) -> [f64; N] {
+        let mut transformed = point;
+        for (i, value) in transformed.iter_mut().enumerate() {
+            *value = *value * self.frequency + self.offset[i];
+        }
+        transformed
+    }
+
+    fn transform_grid<const N: usize>(
+        &self,
+        origin: [f64; N],
+        step: [f64; N],
+    ) -> ([f64; N], [f64; N]) {
+        (self.transform(origin), step.map(|s| s * self.frequency))
+    }
+}
+
+impl NoiseSource for OpenSimplex2 {
+    fn seed(&self) -> i64 {
+        self.seed
+    }
+
+    fn with_seed(&self, seed: i64) -> Self {
+        Self { seed, ..*self }
+    }
+
+    fn sample2(&self, x: f64, y: f64) -> f32 {
+        let [x, y] = self.transform([x, y]);
+        self.variant().sample2(self.seed, x, y)
+    }
+
+    fn sample3(&self, x: f64, y: f64, z: f64) -> f32 {
+        let [x, y, z] = self.transform([x, y, z]);
+        self.variant().sample3(self.seed, x, y, z)
+    }
+
+    fn sample4(&self, x: f64, y: f64, z: f64, w: f64) -> f32 {
+        let [x, y, z, w] = self.transform([x, y, z, w]);
+        self.variant().sample4(self.seed, x, y, z, w)
+    }
+
+    /**
+        The variant's analytic gradient, scaled by the frequency for the input coordinates.
+    */
+    fn sample3_with_derivatives(&self, x: f64, y: f64, z: f64) -> (f32, [f32; 3]) {
+        let [x, y, z] = self.transform([x, y, z]);
+        let (value, derivatives) = self.variant().sample3_with_derivatives(self.seed, x, y, z);
+        let frequency = self.frequency as f32;
+        (value, derivatives.map(|d| d * frequency))
+    }
+}
@@ -0,0 +1,50 @@
+/*!
+    Progress and cancellation of one terrain build, shared between the thread running it and
+    the threads watching it.
+*/
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/**
+    Counts finished rows out of the rows a build will process. Cancelling only raises a flag:
+    the build checks it between rows and gives up at the next one.
+*/
+#[derive(Debug, Default)]
+pub struct Progress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /**
+        Share of the build completed, in [0, 1]. Zero until the build knows its size.
+    */
+    pub fn fraction(&self) -> f32 {
+        let total = self.total.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.0;
+        }
+        (self.done.load(Ordering::Relaxed).min(total) as f64 / total as f64) as f32
+    }
+
+    pub(crate) fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub(crate) fn advance(&self) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,305 @@
+/*!
+    Python extension module, enabled by the `python` feature.
+
+    Scalar functions mirror the C exports (`fast_noise2`, `smooth_noise3_ImproveXZ`, ...).
+    Grids come back as NumPy arrays indexed `[z][y][x]`, and `heightmap` runs the viewer's
+    terrain pipeline from a dict with the same keys as `OpenSimplex2TerrainConfig`, validated
+    like the REST API's settings.
+*/
+
+use numpy::{PyArray1, PyArray2, PyArray3, PyArrayMethods};
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::bridge;
+use crate::ffi::opensimplex2_terrain_default_config;
+use crate::noise_source::Algorithm;
+use crate::opensimplex2::OpenSimplex2;
+use crate::terrain_configuration::{
+    check_terrain_limits, generate_heightmap, update_configuration,
+};
+use crate::{fast, smooth};
+
+/*
+ * Noise Evaluators
+ */
+
+#[pyfunction]
+fn fast_noise2(seed: i64, x: f64, y: f64) -> f32 {
+    fast::noise2(seed, x, y)
+}
+
+#[pyfunction]
+fn fast_noise2_ImproveX(seed: i64, x: f64, y: f64) -> f32 {
+    fast::noise2_ImproveX(seed, x, y)
+}
+
+#[pyfunction]
+fn fast_noise3_ImproveXY(seed: i64, x: f64, y: f64, z: f64) -> f32 {
+    fast::noise3_ImproveXY(seed, x, y, z)
+}
+
+#[pyfunction]
+fn fast_noise3_ImproveXZ(seed: i64, x: f64, y: f64, z: f64) -> f32 {
+    fast::noise3_ImproveXZ(seed, x, y, z)
+}
+
+#[pyfunction]
+fn fast_noise3_Fallback(seed: i64, x: f64, y: f64, z: f64) -> f32 {
+    fast::noise3_Fallback(seed, x, y, z)
+}
+
+#[pyfunction]
+fn fast_noise4_ImproveXYZ_ImproveXY(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    fast::noise4_ImproveXYZ_ImproveXY(seed, x, y, z, w)
+}
+
+#[pyfunction]
+fn fast_noise4_ImproveXYZ_ImproveXZ(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    fast::noise4_ImproveXYZ_ImproveXZ(seed, x, y, z, w)
+}
+
+#[pyfunction]
+fn fast_noise4_ImproveXYZ(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    fast::noise4_ImproveXYZ(seed, x, y, z, w)
+}
+
+#[pyfunction]
+fn fast_noise4_ImproveXY_ImproveZW(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    fast::noise4_ImproveXY_ImproveZW(seed, x, y, z, w)
+}
+
+#[pyfunction]
+fn fast_noise4_Fallback(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    fast::noise4_Fallback(seed, x, y, z, w)
+}
+
+#[pyfunction]
+fn smooth_noise2(seed: i64, x: f64, y: f64) -> f32 {
+    smooth::noise2(seed, x, y)
+}
+
+#[pyfunction]
+fn smooth_noise2_ImproveX(seed: i64, x: f64, y: f64) -> f32 {
+    smooth::noise2_ImproveX(seed, x, y)
+}
+
+#[pyfunction]
+fn smooth_noise3_ImproveXY(seed: i64, x: f64, y: f64, z: f64) -> f32 {
+    smooth::noise3_ImproveXY(seed, x, y, z)
+}
+
+#[pyfunction]
+fn smooth_noise3_ImproveXZ(seed: i64, x: f64, y: f64, z: f64) -> f32 {
+    smooth::noise3_ImproveXZ(seed, x, y, z)
+}
+
+#[pyfunction]
+fn smooth_noise3_Fallback(seed: i64, x: f64, y: f64, z: f64) -> f32 {
+    smooth::noise3_Fallback(seed, x, y, z)
+}
+
+#[pyfunction]
+fn smooth_noise4_ImproveXYZ_ImproveXY(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    smooth::noise4_ImproveXYZ_ImproveXY(seed, x, y, z, w)
+}
+
+#[pyfunction]
+fn smooth_noise4_ImproveXYZ_ImproveXZ(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    smooth::noise4_ImproveXYZ_ImproveXZ(seed, x, y, z, w)
+}
+
+#[pyfunction]
+fn smooth_noise4_ImproveXYZ(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    smooth::noise4_ImproveXYZ(seed, x, y, z, w)
+}
+
+#[pyfunction]
+fn smooth_noise4_ImproveXY_ImproveZW(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    smooth::noise4_ImproveXY_ImproveZW(seed, x, y, z, w)
+}
+
+#[pyfunction]
+fn smooth_noise4_Fallback(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
+    smooth::noise4_Fallback(seed, x, y, z, w)
+}
+
+/*
+ * Grid Evaluators
+ */
+
+/**
+    2D noise on a regular grid, as an array of shape `(size[1], size[0])`.
+    `orientation` is "standard" or "ImproveX".
+*/
+#[pyfunction]
+#[pyo3(signature = (seed, origin, step, size, algorithm = "smooth", orientation = "standard"))]
+fn grid2<'py>(
+    py: Python<'py>,
+    seed: i64,
+    origin: [f64; 2],
+    step: [f64; 2],
+    size: [usize; 2],
+    algorithm: &str,
+    orientation: &str,
+) -> PyResult<Bound<'py, PyArray2<f32>>> {
+    let generator = generator(algorithm, seed)?;
+    let grid = match orientation {
+        "standard" => OpenSimplex2::noise2_Grid,
+        "ImproveX" => OpenSimplex2::noise2_ImproveX_Grid,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unknown 2D orientation: {}",
+                orientation
+            )))
+        }
+    };
+    let mut out = grid_buffer(&size)?;
+    py.detach(|| grid(&generator, origin, step, size, &mut out));
+    PyArray1::from_vec(py, out).reshape([size[1], size[0]])
+}
+
+/**
+    3D noise on a regular grid, as an array of shape `(size[2], size[1], size[0])`.
+    `orientation` is "ImproveXY", "ImproveXZ" or "Fallback".
+*/
+#[pyfunction]
+#[pyo3(signature = (seed, origin, step, size, algorithm = "smooth", orientation = "ImproveXZ"))]
+fn grid3<'py>(
+    py: Python<'py>,
+    seed: i64,
+    origin: [f64; 3],
+    step: [f64; 3],
+    size: [usize; 3],
+    algorithm: &str,
+    orientation: &str,
+) -> PyResult<Bound<'py, PyArray3<f32>>> {
+    let generator = generator(algorithm, seed)?;
+    let grid = match orientation {
+        "ImproveXY" => OpenSimplex2::noise3_ImproveXY_Grid,
+        "ImproveXZ" => OpenSimplex2::noise3_ImproveXZ_Grid,
+        "Fallback" => OpenSimplex2::noise3_Fallback_Grid,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unknown 3D orientation: {}",
+                orientation
+            )))
+        }
+    };
+    let mut out = grid_buffer(&size)?;
+    py.detach(|| grid(&generator, origin, step, size, &mut out));
+    PyArray1::from_vec(py, out).reshape([size[2], size[1], size[0]])
+}
+
+/*
+ * Terrain
+ */
+
+// Dict keys, the `OpenSimplex2TerrainConfig` field names, and the REST endpoints parsing them
+const TERRAIN_SETTINGS: [(&str, &str); 19] = [
+    ("tot_width", "terrain/width"),
+    ("tot_depth", "terrain/depth"),
+    ("seed", "terrain/seed"),
+    ("cube_size", "terrain/cubesize"),
+    ("color", "terrain/color"),
+    ("max_height", "terrain/height"),
+    ("failoff", "terrain/failoff"),
+    ("z", "terrain/z"),
+    ("fractal_octaves", "terrain/fractal/octaves"),
+    ("fractal_amplitude", "terrain/fractal/amplitude"),
+    ("fractal_frequency", "terrain/fractal/frequency"),
+    ("fractal_type", "terrain/fractal/type"),
+    ("warp_levels", "terrain/warp/levels"),
+    ("warp_seed", "terrain/warp/seed"),
+    ("warp_strength", "terrain/warp/strength"),
+    ("warp_frequency", "terrain/warp/frequency"),
+    ("noise_algorithm", "terrain/noise/algorithm"),
+    ("noise_orientation", "terrain/noise/orientation"),
+    ("graph", "terrain/graph"),
+];
+
+/**
+    Column heights of the viewer's terrain, as an array of shape `(depth, width)`.
+    Keys are the `OpenSimplex2TerrainConfig` field names, and values are what the REST API
+    takes for the same setting: `color` is a hex string like "304630", `fractal_type`,
+    `noise_algorithm` and `noise_orientation` are names like "ridged", and `graph` is a noise
+    graph of nested dicts, or None. Missing keys take the viewer's starting values.
+*/
+#[pyfunction]
+#[pyo3(signature = (config = None))]
+fn heightmap<'py>(
+    py: Python<'py>,
+    config: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyArray2<f32>>> {
+    // The default config has no graph to read
+    let mut terrain_configuration =
+        unsafe { opensimplex2_terrain_default_config().terrain_configuration() }
+            .expect("the viewer's starting terrain is valid");
+    if let Some(config) = config {
+        let json = py.import("json")?;
+        for (key, value) in config.iter() {
+            let key: String = key.extract()?;
+            let Some((_, endpoint)) = TERRAIN_SETTINGS.iter().find(|(name, _)| *name == key) else {
+                return Err(PyKeyError::new_err(format!(
+                    "unknown terrain setting: {}",
+                    key
+                )));
+            };
+            let value: String = json.call_method1("dumps", (value,))?.extract()?;
+            let msg = bridge::message(endpoint, &value)
+                .map_err(|e| PyValueError::new_err(format!("{}: {}", key, e)))?;
+            terrain_configuration = update_configuration(terrain_configuration, Some(msg));
+        }
+    }
+
+    // terrain_size counts columns one by one, so a tiny cube size must be refused first
+    check_terrain_limits(&terrain_configuration).map_err(PyValueError::new_err)?;
+    let heightmap = py.detach(|| generate_heightmap(&terrain_configuration));
+    PyArray1::from_vec(py, heightmap.heights).reshape([heightmap.depth, heightmap.width])
+}
+
+/*
+ * Utility
+ */
+
+fn generator(algorithm: &str, seed: i64) -> PyResult<OpenSimplex2> {
+    let algorithm: Algorithm = algorithm.parse().map_err(PyValueError::new_err)?;
+    Ok(OpenSimplex2::new(algorithm, seed))
+}
+
+fn grid_buffer(size: &[usize]) -> PyResult<Vec<f32>> {
+    let count = size
+        .iter()
+        .try_fold(1usize, |count, &n| count.checked_mul(n))
+        .ok_or_else(|| PyValueError::new_err("grid size overflows"))?;
+    Ok(vec![0.0; count])
+}
+
+#[pymodule]
+fn terrainopensimplex2(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(fast_noise2, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_noise2_ImproveX, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_noise3_ImproveXY, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_noise3_ImproveXZ, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_noise3_Fallback, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_noise4_ImproveXYZ_ImproveXY, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_noise4_ImproveXYZ_ImproveXZ, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_noise4_ImproveXYZ, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_noise4_ImproveXY_ImproveZW, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_noise4_Fallback, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise2, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise2_ImproveX, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise3_ImproveXY, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise3_ImproveXZ, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise3_Fallback, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise4_ImproveXYZ_ImproveXY, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise4_ImproveXYZ_ImproveXZ, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise4_ImproveXYZ, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise4_ImproveXY_ImproveZW, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth_noise4_Fallback, m)?)?;
+    m.add_function(wrap_pyfunction!(grid2, m)?)?;
+    m.add_function(wrap_pyfunction!(grid3, m)?)?;
+    m.add_function(wrap_pyfunction!(heightmap, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use crate::configuration::ConfigurationMessage;
+use actix_web::web;
+use actix_web::HttpResponse;
+use std::sync::mpsc::Sender;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct DirectionX {
+    value: f32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": -1.0 }' -X PUT http://127.0.0.1:8090/api/light/direction/x
+pub async fn light_change_direction_x(
+    data: web::Json<DirectionX>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_direction_x = data.value;
+    let _ = tx.send(ConfigurationMessage::LightDirectionX(new_direction_x));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct DirectionY {
+    value: f32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": -0.5 }' -X PUT http://127.0.0.1:8090/api/light/direction/y
+pub async fn light_change_direction_y(
+    data: web::Json<DirectionY>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_direction_y = data.value;
+    let _ = tx.send(ConfigurationMessage::LightDirectionY(new_direction_y));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct DirectionZ {
+    value: f32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 0.3 }' -X PUT http://127.0.0.1:8090/api/light/direction/z
+pub async fn light_change_direction_z(
+    data: web::Json<DirectionZ>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_direction_z = data.value;
+    let _ = tx.send(ConfigurationMessage::LightDirectionZ(new_direction_z));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct LightColor {
+    value: String,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": "fff2d9" }' -X PUT http://127.0.0.1:8090/api/light/color
+pub async fn light_change_color(
+    data: web::Json<LightColor>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_color = &data.value;
+    if new_color.len() != 6 {
+        return HttpResponse::BadRequest().finish();
+    }
+
+    let _ = match u8::from_str_radix(&new_color[0..2], 16) {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let _ = match u8::from_str_radix(&new_color[2..4], 16) {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let _ = match u8::from_str_radix(&new_color[4..6], 16) {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let _ = tx.send(ConfigurationMessage::LightColor(new_color.clone()));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct Intensity {
+    value: f32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 2.5 }' -X PUT http://127.0.0.1:8090/api/light/intensity
+pub async fn light_change_intensity(
+    data: web::Json<Intensity>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_intensity = data.value;
+    if new_intensity < 0.0f32 {
+        return HttpResponse::BadRequest().finish();
+    }
+    let _ = tx.send(ConfigurationMessage::LightIntensity(new_intensity));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct AmbientIntensity {
+    value: f32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 0.4 }' -X PUT http://127.0.0.1:8090/api/light/ambient/intensity
+pub async fn light_change_ambient_intensity(
+    data: web::Json<AmbientIntensity>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_ambient_intensity = data.value;
+    if new_ambient_intensity < 0.0f32 {
+        return HttpResponse::BadRequest().finish();
+    }
+    let _ = tx.send(ConfigurationMessage::LightAmbientIntensity(new_ambient_intensity));
+    HttpResponse::Ok().finish()
+}
@@ -1,7 +1,9 @@
 mod camera;
 mod health_check;
+mod light;
 mod terrain;
 
 pub use camera::*;
 pub use health_check::*;
+pub use light::*;
 pub use terrain::*;
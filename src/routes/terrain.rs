@@ -1,4 +1,12 @@
+use crate::color_ramp::ColorRamp;
 use crate::configuration::ConfigurationMessage;
+use crate::domain_warp::DomainWarp;
+use crate::fractal::FractalType;
+use crate::mesh_mode::MeshMode;
+use crate::noise_graph::NoiseModule;
+use crate::noise_source::{Algorithm, Orientation};
+use crate::terrain_configuration::TerrainConfiguration;
+use crate::terrain_worker::TerrainStatus;
 use actix_web::web;
 use actix_web::HttpResponse;
 use std::sync::mpsc::Sender;
@@ -191,3 +199,368 @@ pub async fn terrain_change_fractal_frequency(
     let _ = tx.send(ConfigurationMessage::TerrainFractalFrequency(new_fractal_frequency));
     HttpResponse::Ok().finish()
 }
+
+#[derive(Deserialize)]
+pub struct FractalTypeValue {
+    value: String,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": "ridged" }' -X PUT http://127.0.0.1:8090/api/terrain/fractal/type
+pub async fn terrain_change_fractal_type(
+    data: web::Json<FractalTypeValue>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_fractal_type = match data.value.parse::<FractalType>() {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+    let _ = tx.send(ConfigurationMessage::TerrainFractalType(new_fractal_type));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct WarpLevels {
+    value: u32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 2 }' -X PUT http://127.0.0.1:8090/api/terrain/warp/levels
+pub async fn terrain_change_warp_levels(
+    data: web::Json<WarpLevels>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_warp_levels = data.value;
+    if new_warp_levels > DomainWarp::MAX_LEVELS {
+        return HttpResponse::BadRequest().finish();
+    }
+    let _ = tx.send(ConfigurationMessage::TerrainWarpLevels(new_warp_levels));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct WarpSeed {
+    value: i64,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 1337 }' -X PUT http://127.0.0.1:8090/api/terrain/warp/seed
+pub async fn terrain_change_warp_seed(
+    data: web::Json<WarpSeed>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_warp_seed = data.value;
+    let _ = tx.send(ConfigurationMessage::TerrainWarpSeed(new_warp_seed));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct WarpStrength {
+    value: f64,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 1.5 }' -X PUT http://127.0.0.1:8090/api/terrain/warp/strength
+pub async fn terrain_change_warp_strength(
+    data: web::Json<WarpStrength>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_warp_strength = data.value;
+    let _ = tx.send(ConfigurationMessage::TerrainWarpStrength(new_warp_strength));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct WarpFrequency {
+    value: f64,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 0.3 }' -X PUT http://127.0.0.1:8090/api/terrain/warp/frequency
+pub async fn terrain_change_warp_frequency(
+    data: web::Json<WarpFrequency>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_warp_frequency = data.value;
+    let _ = tx.send(ConfigurationMessage::TerrainWarpFrequency(new_warp_frequency));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct NoiseAlgorithm {
+    value: String,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": "fast" }' -X PUT http://127.0.0.1:8090/api/terrain/noise/algorithm
+pub async fn terrain_change_noise_algorithm(
+    data: web::Json<NoiseAlgorithm>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_noise_algorithm = match data.value.parse::<Algorithm>() {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+    let _ = tx.send(ConfigurationMessage::TerrainNoiseAlgorithm(new_noise_algorithm));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct NoiseOrientation {
+    value: String,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": "ImproveXY" }' -X PUT http://127.0.0.1:8090/api/terrain/noise/orientation
+pub async fn terrain_change_noise_orientation(
+    data: web::Json<NoiseOrientation>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_noise_orientation = match data.value.parse::<Orientation>() {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+    let _ = tx.send(ConfigurationMessage::TerrainNoiseOrientation(new_noise_orientation));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct Graph {
+    value: Option<NoiseModule>,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": { "type": "scale_bias", "scale": 0.5, "bias": 0.5, "source": { "type": "fractal", "octaves": 6 } } }' -X PUT http://127.0.0.1:8090/api/terrain/graph
+pub async fn terrain_change_graph(
+    data: web::Json<Graph>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_graph = data.into_inner().value;
+    if let Some(graph) = &new_graph {
+        if let Err(e) = graph.validate() {
+            return HttpResponse::BadRequest().body(e);
+        }
+    }
+    let _ = tx.send(ConfigurationMessage::TerrainGraph(new_graph));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct Threads {
+    value: usize,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 4 }' -X PUT http://127.0.0.1:8090/api/terrain/threads
+pub async fn terrain_change_threads(
+    data: web::Json<Threads>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_threads = data.value;
+    if new_threads > TerrainConfiguration::MAX_THREADS {
+        return HttpResponse::BadRequest().finish();
+    }
+    let _ = tx.send(ConfigurationMessage::TerrainThreads(new_threads));
+    HttpResponse::Ok().finish()
+}
+
+// curl -i http://127.0.0.1:8090/api/terrain/status
+pub async fn terrain_status(status: web::Data<TerrainStatus>) -> HttpResponse {
+    HttpResponse::Ok().json(status.report())
+}
+
+// Land share and coastline of the newest ready terrain, null before the first build
+// curl -i http://127.0.0.1:8090/api/terrain/stats
+pub async fn terrain_stats(status: web::Data<TerrainStatus>) -> HttpResponse {
+    HttpResponse::Ok().json(status.stats())
+}
+
+#[derive(Deserialize)]
+pub struct Mode {
+    value: String,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": "heightfield" }' -X PUT http://127.0.0.1:8090/api/terrain/mode
+pub async fn terrain_change_mode(
+    data: web::Json<Mode>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_mode = match data.value.parse::<MeshMode>() {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+    let _ = tx.send(ConfigurationMessage::TerrainMode(new_mode));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct Skirts {
+    value: bool,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": false }' -X PUT http://127.0.0.1:8090/api/terrain/skirts
+pub async fn terrain_change_skirts(
+    data: web::Json<Skirts>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_skirts = data.value;
+    let _ = tx.send(ConfigurationMessage::TerrainSkirts(new_skirts));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct VolumeThreshold {
+    value: f32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 0.2 }' -X PUT http://127.0.0.1:8090/api/terrain/volume/threshold
+pub async fn terrain_change_volume_threshold(
+    data: web::Json<VolumeThreshold>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_volume_threshold = data.value;
+    let _ = tx.send(ConfigurationMessage::TerrainVolumeThreshold(new_volume_threshold));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct VolumeCaveFrequency {
+    value: f64,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 4.0 }' -X PUT http://127.0.0.1:8090/api/terrain/volume/cavefrequency
+pub async fn terrain_change_volume_cave_frequency(
+    data: web::Json<VolumeCaveFrequency>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_volume_cave_frequency = data.value;
+    let _ = tx.send(ConfigurationMessage::TerrainVolumeCaveFrequency(new_volume_cave_frequency));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct VolumeVerticalBias {
+    value: f32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 2.5 }' -X PUT http://127.0.0.1:8090/api/terrain/volume/verticalbias
+pub async fn terrain_change_volume_vertical_bias(
+    data: web::Json<VolumeVerticalBias>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_volume_vertical_bias = data.value;
+    let _ = tx.send(ConfigurationMessage::TerrainVolumeVerticalBias(new_volume_vertical_bias));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct ColorRampValue {
+    value: Option<ColorRamp>,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": { "stops": [ { "height": 0.0, "color": "c2b280" }, { "height": 0.1, "color": "4f7a3a" }, { "height": 0.55, "color": "7a746b" }, { "height": 0.8, "color": "f4f4f4" } ], "slope": { "start": 0.4, "end": 0.6, "color": "6e6a64" } } }' -X PUT http://127.0.0.1:8090/api/terrain/colorramp
+pub async fn terrain_change_color_ramp(
+    data: web::Json<ColorRampValue>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_color_ramp = data.into_inner().value;
+    if let Some(color_ramp) = &new_color_ramp {
+        if let Err(e) = color_ramp.validate() {
+            return HttpResponse::BadRequest().body(e);
+        }
+    }
+    let _ = tx.send(ConfigurationMessage::TerrainColorRamp(new_color_ramp));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct WaterLevel {
+    value: f32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 0.5 }' -X PUT http://127.0.0.1:8090/api/terrain/water/level
+pub async fn terrain_change_water_level(
+    data: web::Json<WaterLevel>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_water_level = data.value;
+    let _ = tx.send(ConfigurationMessage::TerrainWaterLevel(new_water_level));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct WaterColor {
+    value: String,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": "2f6f9f" }' -X PUT http://127.0.0.1:8090/api/terrain/water/color
+pub async fn terrain_change_water_color(
+    data: web::Json<WaterColor>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_color = &data.value;
+    if new_color.len() != 6 {
+        return HttpResponse::BadRequest().finish();
+    }
+
+    let _ = match u8::from_str_radix(&new_color[0..2], 16) {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let _ = match u8::from_str_radix(&new_color[2..4], 16) {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let _ = match u8::from_str_radix(&new_color[4..6], 16) {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let _ = tx.send(ConfigurationMessage::TerrainWaterColor(new_color.clone()));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct WaterOpacity {
+    value: f32,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": 0.6 }' -X PUT http://127.0.0.1:8090/api/terrain/water/opacity
+pub async fn terrain_change_water_opacity(
+    data: web::Json<WaterOpacity>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_water_opacity = data.value;
+    if !(0.0f32..=1.0f32).contains(&new_water_opacity) {
+        return HttpResponse::BadRequest().finish();
+    }
+    let _ = tx.send(ConfigurationMessage::TerrainWaterOpacity(new_water_opacity));
+    HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+pub struct WaterSeabedColor {
+    value: String,
+}
+
+// curl -i -H "Content-Type: application/json" -d '{ "value": "8c7a5b" }' -X PUT http://127.0.0.1:8090/api/terrain/water/seabedcolor
+pub async fn terrain_change_water_seabed_color(
+    data: web::Json<WaterSeabedColor>,
+    tx: web::Data<Sender<ConfigurationMessage>>,
+) -> HttpResponse {
+    let new_color = &data.value;
+    if new_color.len() != 6 {
+        return HttpResponse::BadRequest().finish();
+    }
+
+    let _ = match u8::from_str_radix(&new_color[0..2], 16) {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let _ = match u8::from_str_radix(&new_color[2..4], 16) {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let _ = match u8::from_str_radix(&new_color[4..6], 16) {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+
+    let _ = tx.send(ConfigurationMessage::TerrainWaterSeabedColor(new_color.clone()));
+    HttpResponse::Ok().finish()
+}
@@ -2,7 +2,10 @@
     K.jpg's OpenSimplex 2, smooth variant ("SuperSimplex")
 */
 
-use std::{num::Wrapping, sync::Once};
+// Literals, index arithmetic and the base function signatures follow the Java original.
+#![allow(clippy::excessive_precision, clippy::identity_op, clippy::too_many_arguments)]
+
+use std::{num::Wrapping, sync::OnceLock};
 
 const PRIME_X: i64 = 0x5205402B9270C86F;
 const PRIME_Y: i64 = 0x598CD327003817B5;
@@ -11,7 +14,7 @@ const PRIME_W: i64 = 0x56CC5227E58F554B;
 const HASH_MULTIPLIER: i64 = 0x53A3F72DEEC546F5;
 const SEED_FLIP_3D: i64 = -0x52D547B2E96ED629;
 
-const ROOT2OVER2: f64 = 0.7071067811865476;
+const ROOT2OVER2: f64 = std::f64::consts::FRAC_1_SQRT_2;
 const SKEW_2D: f64 = 0.366025403784439;
 const UNSKEW_2D: f64 = -0.21132486540518713;
 
@@ -50,7 +53,7 @@ pub fn noise2(seed: i64, x: f64, y: f64) -> f32 {
     let xs = x + s;
     let ys = y + s;
 
-    noise2_UnskewedBase(seed, xs, ys)
+    noise2_UnskewedBase(getStaticData(), seed, xs, ys, &mut NoCache)
 }
 
 /**
@@ -65,13 +68,19 @@ pub fn noise2_ImproveX(seed: i64, x: f64, y: f64) -> f32 {
     let xx = x * ROOT2OVER2;
     let yy = y * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D));
 
-    noise2_UnskewedBase(seed, yy + xx, yy - xx)
+    noise2_UnskewedBase(getStaticData(), seed, yy + xx, yy - xx, &mut NoCache)
 }
 
 /**
     2D  OpenSimplex2S/SuperSimplex noise base.
 */
-fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
+fn noise2_UnskewedBase(
+    staticData: &StaticData,
+    seed: i64,
+    xs: f64,
+    ys: f64,
+    cache: &mut impl LatticeCache<2>,
+) -> f32 {
     let seed = Wrapping(seed);
 
     // Get base points and offsets.
@@ -79,6 +88,7 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
     let ysb = fastFloor(ys);
     let xi = (xs - xsb as f64) as f32;
     let yi = (ys - ysb as f64) as f32;
+    cache.enter([xsb, ysb, 0, 0]);
 
     // Prime pre-multiplication for hash.
     let xsbp = Wrapping(xsb as i64) * Wrapping(PRIME_X);
@@ -91,7 +101,8 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
 
     // First vertex.
     let a0 = RSQUARED_2D - dx0 * dx0 - dy0 * dy0;
-    let mut value = (a0 * a0) * (a0 * a0) * grad2(seed, xsbp, ysbp, dx0, dy0);
+    let mut value =
+        (a0 * a0) * (a0 * a0) * grad2Cached(cache, 0, staticData, seed, xsbp, ysbp, dx0, dy0);
 
     // Second vertex.
     let a1 = (2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 / UNSKEW_2D + 2.0)) as f32 * t
@@ -100,7 +111,10 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
     let dy1 = dy0 - (1.0 + 2.0 * UNSKEW_2D) as f32;
     value += (a1 * a1)
         * (a1 * a1)
-        * grad2(
+        * grad2Cached(
+            cache,
+            1,
+            staticData,
             seed,
             xsbp + Wrapping(PRIME_X),
             ysbp + Wrapping(PRIME_Y),
@@ -119,7 +133,10 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
             if a2 > 0.0 {
                 value += (a2 * a2)
                     * (a2 * a2)
-                    * grad2(
+                    * grad2Cached(
+                        cache,
+                        2,
+                        staticData,
                         seed,
                         xsbp + Wrapping(PRIME_X << 1),
                         ysbp + Wrapping(PRIME_Y),
@@ -132,8 +149,18 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
             let dy2 = dy0 - (UNSKEW_2D + 1.0) as f32;
             let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
             if a2 > 0.0 {
-                value +=
-                    (a2 * a2) * (a2 * a2) * grad2(seed, xsbp, ysbp + Wrapping(PRIME_Y), dx2, dy2);
+                value += (a2 * a2)
+                    * (a2 * a2)
+                    * grad2Cached(
+                        cache,
+                        3,
+                        staticData,
+                        seed,
+                        xsbp,
+                        ysbp + Wrapping(PRIME_Y),
+                        dx2,
+                        dy2,
+                    );
             }
         }
 
@@ -144,7 +171,10 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
             if a3 > 0.0 {
                 value += (a3 * a3)
                     * (a3 * a3)
-                    * grad2(
+                    * grad2Cached(
+                        cache,
+                        4,
+                        staticData,
                         seed,
                         xsbp + Wrapping(PRIME_X),
                         ysbp + Wrapping(PRIME_Y << 1),
@@ -157,8 +187,18 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
             let dy3 = dy0 - UNSKEW_2D as f32;
             let a3 = RSQUARED_2D - dx3 * dx3 - dy3 * dy3;
             if a3 > 0.0 {
-                value +=
-                    (a3 * a3) * (a3 * a3) * grad2(seed, xsbp + Wrapping(PRIME_X), ysbp, dx3, dy3);
+                value += (a3 * a3)
+                    * (a3 * a3)
+                    * grad2Cached(
+                        cache,
+                        5,
+                        staticData,
+                        seed,
+                        xsbp + Wrapping(PRIME_X),
+                        ysbp,
+                        dx3,
+                        dy3,
+                    );
             }
         }
     } else {
@@ -167,16 +207,36 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
             let dy2 = dy0 + UNSKEW_2D as f32;
             let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
             if a2 > 0.0 {
-                value +=
-                    (a2 * a2) * (a2 * a2) * grad2(seed, xsbp - Wrapping(PRIME_X), ysbp, dx2, dy2);
+                value += (a2 * a2)
+                    * (a2 * a2)
+                    * grad2Cached(
+                        cache,
+                        6,
+                        staticData,
+                        seed,
+                        xsbp - Wrapping(PRIME_X),
+                        ysbp,
+                        dx2,
+                        dy2,
+                    );
             }
         } else {
             let dx2 = dx0 - (UNSKEW_2D + 1.0) as f32;
             let dy2 = dy0 - UNSKEW_2D as f32;
             let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
             if a2 > 0.0 {
-                value +=
-                    (a2 * a2) * (a2 * a2) * grad2(seed, xsbp + Wrapping(PRIME_X), ysbp, dx2, dy2);
+                value += (a2 * a2)
+                    * (a2 * a2)
+                    * grad2Cached(
+                        cache,
+                        7,
+                        staticData,
+                        seed,
+                        xsbp + Wrapping(PRIME_X),
+                        ysbp,
+                        dx2,
+                        dy2,
+                    );
             }
         }
 
@@ -185,16 +245,36 @@ fn noise2_UnskewedBase(seed: i64, xs: f64, ys: f64) -> f32 {
             let dy2 = dy0 + (UNSKEW_2D + 1.0) as f32;
             let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
             if a2 > 0.0 {
-                value +=
-                    (a2 * a2) * (a2 * a2) * grad2(seed, xsbp, ysbp - Wrapping(PRIME_Y), dx2, dy2);
+                value += (a2 * a2)
+                    * (a2 * a2)
+                    * grad2Cached(
+                        cache,
+                        8,
+                        staticData,
+                        seed,
+                        xsbp,
+                        ysbp - Wrapping(PRIME_Y),
+                        dx2,
+                        dy2,
+                    );
             }
         } else {
             let dx2 = dx0 - UNSKEW_2D as f32;
             let dy2 = dy0 - (UNSKEW_2D + 1.0) as f32;
             let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
             if a2 > 0.0 {
-                value +=
-                    (a2 * a2) * (a2 * a2) * grad2(seed, xsbp, ysbp + Wrapping(PRIME_Y), dx2, dy2);
+                value += (a2 * a2)
+                    * (a2 * a2)
+                    * grad2Cached(
+                        cache,
+                        9,
+                        staticData,
+                        seed,
+                        xsbp,
+                        ysbp + Wrapping(PRIME_Y),
+                        dx2,
+                        dy2,
+                    );
             }
         }
     }
@@ -222,7 +302,7 @@ pub fn noise3_ImproveXY(seed: i64, x: f64, y: f64, z: f64) -> f32 {
     let zr = xy * -ROOT3OVER3 + zz;
 
     // Evaluate both lattices to form a BCC lattice.
-    noise3_UnrotatedBase(seed, xr, yr, zr)
+    noise3_UnrotatedBase(getStaticData(), seed, xr, yr, zr, &mut NoCache)
 }
 
 /**
@@ -245,7 +325,7 @@ pub fn noise3_ImproveXZ(seed: i64, x: f64, y: f64, z: f64) -> f32 {
     let yr = xz * -ROOT3OVER3 + yy;
 
     // Evaluate both lattices to form a BCC lattice.
-    noise3_UnrotatedBase(seed, xr, yr, zr)
+    noise3_UnrotatedBase(getStaticData(), seed, xr, yr, zr, &mut NoCache)
 }
 
 /**
@@ -262,7 +342,7 @@ pub fn noise3_Fallback(seed: i64, x: f64, y: f64, z: f64) -> f32 {
     let zr = r - z;
 
     // Evaluate both lattices to form a BCC lattice.
-    noise3_UnrotatedBase(seed, xr, yr, zr)
+    noise3_UnrotatedBase(getStaticData(), seed, xr, yr, zr, &mut NoCache)
 }
 
 /**
@@ -271,7 +351,14 @@ pub fn noise3_Fallback(seed: i64, x: f64, y: f64, z: f64) -> f32 {
     It was actually faster to narrow down the points in the loop itself,
     than to build up the index with enough info to isolate 8 points.
 */
-fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
+fn noise3_UnrotatedBase(
+    staticData: &StaticData,
+    seed: i64,
+    xr: f64,
+    yr: f64,
+    zr: f64,
+    cache: &mut impl LatticeCache<3>,
+) -> f32 {
     let seed = Wrapping(seed);
 
     // Get base points and offsets.
@@ -293,6 +380,9 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
     let yNMask = (-0.5 - yi) as i32;
     let zNMask = (-0.5 - zi) as i32;
 
+    // The vertices only depend on the base point and which half of the cell we're in per axis.
+    cache.enter([xrb, yrb, zrb, (xNMask & 1) | (yNMask & 2) | (zNMask & 4)]);
+
     // First vertex.
     let x0 = xi + xNMask as f32;
     let y0 = yi + yNMask as f32;
@@ -300,7 +390,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
     let a0 = RSQUARED_3D - x0 * x0 - y0 * y0 - z0 * z0;
     let mut value = (a0 * a0)
         * (a0 * a0)
-        * grad3(
+        * grad3Cached(
+            cache,
+            0,
+            staticData,
             seed,
             xrbp + (Wrapping(xNMask as i64) & Wrapping(PRIME_X)),
             yrbp + (Wrapping(yNMask as i64) & Wrapping(PRIME_Y)),
@@ -317,7 +410,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
     let a1 = RSQUARED_3D - x1 * x1 - y1 * y1 - z1 * z1;
     value += (a1 * a1)
         * (a1 * a1)
-        * grad3(
+        * grad3Cached(
+            cache,
+            1,
+            staticData,
             seed2,
             xrbp + Wrapping(PRIME_X),
             yrbp + Wrapping(PRIME_Y),
@@ -344,7 +440,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
         let z2 = z0;
         value += (a2 * a2)
             * (a2 * a2)
-            * grad3(
+            * grad3Cached(
+                cache,
+                2,
+                staticData,
                 seed,
                 xrbp + (Wrapping(!xNMask as i64) & Wrapping(PRIME_X)),
                 yrbp + (Wrapping(yNMask as i64) & Wrapping(PRIME_Y)),
@@ -361,7 +460,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
             let z3 = z0 - (zNMask | 1) as f32;
             value += (a3 * a3)
                 * (a3 * a3)
-                * grad3(
+                * grad3Cached(
+                    cache,
+                    3,
+                    staticData,
                     seed,
                     xrbp + (Wrapping(xNMask as i64) & Wrapping(PRIME_X)),
                     yrbp + (Wrapping(!yNMask as i64) & Wrapping(PRIME_Y)),
@@ -379,7 +481,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
             let z4 = z1;
             value += (a4 * a4)
                 * (a4 * a4)
-                * grad3(
+                * grad3Cached(
+                    cache,
+                    4,
+                    staticData,
                     seed2,
                     xrbp + (Wrapping(xNMask as i64) & (Wrapping(PRIME_X) << 1)),
                     yrbp + Wrapping(PRIME_Y),
@@ -400,7 +505,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
         let z6 = z0;
         value += (a6 * a6)
             * (a6 * a6)
-            * grad3(
+            * grad3Cached(
+                cache,
+                5,
+                staticData,
                 seed,
                 xrbp + (Wrapping(xNMask as i64) & Wrapping(PRIME_X)),
                 yrbp + (Wrapping(!yNMask as i64) & Wrapping(PRIME_Y)),
@@ -417,7 +525,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
             let z7 = z0 - (zNMask | 1) as f32;
             value += (a7 * a7)
                 * (a7 * a7)
-                * grad3(
+                * grad3Cached(
+                    cache,
+                    6,
+                    staticData,
                     seed,
                     xrbp + (Wrapping(!xNMask as i64) & Wrapping(PRIME_X)),
                     yrbp + (Wrapping(yNMask as i64) & Wrapping(PRIME_Y)),
@@ -435,7 +546,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
             let z8 = z1;
             value += (a8 * a8)
                 * (a8 * a8)
-                * grad3(
+                * grad3Cached(
+                    cache,
+                    7,
+                    staticData,
                     seed2,
                     xrbp + Wrapping(PRIME_X),
                     yrbp + (Wrapping(yNMask as i64) & (Wrapping(PRIME_Y) << 1)),
@@ -456,7 +570,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
         let zA = z0 - (zNMask | 1) as f32;
         value += (aA * aA)
             * (aA * aA)
-            * grad3(
+            * grad3Cached(
+                cache,
+                8,
+                staticData,
                 seed,
                 xrbp + (Wrapping(xNMask as i64) & Wrapping(PRIME_X)),
                 yrbp + (Wrapping(yNMask as i64) & Wrapping(PRIME_Y)),
@@ -473,7 +590,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
             let zB = z0;
             value += (aB * aB)
                 * (aB * aB)
-                * grad3(
+                * grad3Cached(
+                    cache,
+                    9,
+                    staticData,
                     seed,
                     xrbp + (Wrapping(!xNMask as i64) & Wrapping(PRIME_X)),
                     yrbp + (Wrapping(!yNMask as i64) & Wrapping(PRIME_Y)),
@@ -491,7 +611,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
             let zC = (zNMask | 1) as f32 + z1;
             value += (aC * aC)
                 * (aC * aC)
-                * grad3(
+                * grad3Cached(
+                    cache,
+                    10,
+                    staticData,
                     seed2,
                     xrbp + Wrapping(PRIME_X),
                     yrbp + Wrapping(PRIME_Y),
@@ -512,7 +635,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
             let z5 = (zNMask | 1) as f32 + z1;
             value += (a5 * a5)
                 * (a5 * a5)
-                * grad3(
+                * grad3Cached(
+                    cache,
+                    11,
+                    staticData,
                     seed2,
                     xrbp + Wrapping(PRIME_X),
                     yrbp + (Wrapping(yNMask as i64) & (Wrapping(PRIME_Y) << 1)),
@@ -532,7 +658,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
             let z9 = (zNMask | 1) as f32 + z1;
             value += (a9 * a9)
                 * (a9 * a9)
-                * grad3(
+                * grad3Cached(
+                    cache,
+                    12,
+                    staticData,
                     seed2,
                     xrbp + (Wrapping(xNMask as i64) & (Wrapping(PRIME_X) << 1)),
                     yrbp + Wrapping(PRIME_Y),
@@ -552,7 +681,10 @@ fn noise3_UnrotatedBase(seed: i64, xr: f64, yr: f64, zr: f64) -> f32 {
             let zD = z1;
             value += (aD * aD)
                 * (aD * aD)
-                * grad3(
+                * grad3Cached(
+                    cache,
+                    13,
+                    staticData,
                     seed2,
                     xrbp + (Wrapping(xNMask as i64) & (Wrapping(PRIME_X) << 1)),
                     yrbp + (Wrapping(yNMask as i64) & (Wrapping(PRIME_Y) << 1)),
@@ -583,7 +715,7 @@ pub fn noise4_ImproveXYZ_ImproveXY(seed: i64, x: f64, y: f64, z: f64, w: f64) ->
     let zr = xy * -0.57735026918962599998 + (zz + ww);
     let wr = z * -0.866025403784439 + ww;
 
-    noise4_UnskewedBase(seed, xr, yr, zr, wr)
+    noise4_UnskewedBase(getStaticData(), seed, xr, yr, zr, wr)
 }
 
 /**
@@ -602,7 +734,7 @@ pub fn noise4_ImproveXYZ_ImproveXZ(seed: i64, x: f64, y: f64, z: f64, w: f64) ->
     let yr = xz * -0.57735026918962599998 + (yy + ww);
     let wr = y * -0.866025403784439 + ww;
 
-    noise4_UnskewedBase(seed, xr, yr, zr, wr)
+    noise4_UnskewedBase(getStaticData(), seed, xr, yr, zr, wr)
 }
 
 /**
@@ -620,7 +752,7 @@ pub fn noise4_ImproveXYZ(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
     let zs = z + s2;
     let ws = -0.5 * xyz + ww;
 
-    noise4_UnskewedBase(seed, xs, ys, zs, ws)
+    noise4_UnskewedBase(getStaticData(), seed, xs, ys, zs, ws)
 }
 
 /**
@@ -636,7 +768,7 @@ pub fn noise4_ImproveXY_ImproveZW(seed: i64, x: f64, y: f64, z: f64, w: f64) ->
     let zs = z + t2;
     let ws = w + t2;
 
-    noise4_UnskewedBase(seed, xs, ys, zs, ws)
+    noise4_UnskewedBase(getStaticData(), seed, xs, ys, zs, ws)
 }
 
 /**
@@ -650,7 +782,7 @@ pub fn noise4_Fallback(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
     let zs = z + s;
     let ws = w + s;
 
-    noise4_UnskewedBase(seed, xs, ys, zs, ws)
+    noise4_UnskewedBase(getStaticData(), seed, xs, ys, zs, ws)
 }
 
 /**
@@ -659,7 +791,14 @@ pub fn noise4_Fallback(seed: i64, x: f64, y: f64, z: f64, w: f64) -> f32 {
     This isn't as elegant or SIMD/GPU/etc. portable as other approaches,
     but it competes performance-wise with optimized 2014 OpenSimplex.
 */
-fn noise4_UnskewedBase(seed: i64, xs: f64, ys: f64, zs: f64, ws: f64) -> f32 {
+fn noise4_UnskewedBase(
+    staticData: &StaticData,
+    seed: i64,
+    xs: f64,
+    ys: f64,
+    zs: f64,
+    ws: f64,
+) -> f32 {
     let seed = Wrapping(seed);
 
     // Get base points and offsets
@@ -692,7 +831,6 @@ fn noise4_UnskewedBase(seed: i64, xs: f64, ys: f64, zs: f64, ws: f64) -> f32 {
         | ((fastFloor(ws * 4.0) & 3) << 6);
 
     // Point contributions
-    let staticData = getStaticData();
     let mut value = 0.0;
     let secondaryIndexStartAndStop = staticData.lookup4DA[index as usize];
     let secondaryIndexStart = secondaryIndexStartAndStop & 0xFFFF;
@@ -710,6 +848,7 @@ fn noise4_UnskewedBase(seed: i64, xs: f64, ys: f64, zs: f64, ws: f64) -> f32 {
             value += a
                 * a
                 * grad4(
+                    staticData,
                     seed,
                     xsvp + Wrapping(c.xsvp),
                     ysvp + Wrapping(c.ysvp),
@@ -726,136 +865,1948 @@ fn noise4_UnskewedBase(seed: i64, xs: f64, ys: f64, zs: f64, ws: f64) -> f32 {
 }
 
 /*
-    Utility
+    Grid Evaluators
+
+    Each function samples its scalar counterpart on a regular grid and writes the
+    results into `out`, X varying fastest: sample (i, j, ...) is taken at
+    (origin[0] + i * step[0], origin[1] + j * step[1], ...) and stored at
+    out[i + size[0] * (j + size[1] * (...))].
+    Results are bit-identical to the scalar functions. The orientation terms that
+    only depend on some of the axes are computed once per row/plane, and the 2D and 3D
+    functions keep the gradients of the lattice cell the previous sample fell in, so
+    neighbouring samples in the same cell skip the hashing for the vertices it already
+    looked up. The 4D functions only share the orientation terms.
+
+    `cargo bench --bench grid` times 64x64 and 16x16x16 grids against the scalar loop.
+    On one x86-64 core, with a step of 0.05 (many samples per cell), noise2_Grid ran about
+    45% faster than the loop and noise3_ImproveXZ_Grid about 30% faster; with a step of 1.0
+    the cache rarely hits and they ran about as fast as it.
 */
 
-fn grad2(seed: Wrapping<i64>, xsvp: Wrapping<i64>, ysvp: Wrapping<i64>, dx: f32, dy: f32) -> f32 {
-    let mut hash = seed ^ xsvp ^ ysvp;
-    hash *= HASH_MULTIPLIER;
-    hash ^= hash.0 >> (64 - N_GRADS_2D_EXPONENT + 1);
-    let gi = (hash.0 as i32 & ((N_GRADS_2D - 1) << 1)) as usize;
-    let grads = &getStaticData().gradients2D;
-    grads[gi | 0] * dx + grads[gi | 1] * dy
+/**
+    2D OpenSimplex2S/SuperSimplex noise, standard lattice orientation, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise2_Grid(seed: i64, origin: [f64; 2], step: [f64; 2], size: [usize; 2], out: &mut [f32]) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let mut cache = CellCache::new();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+
+    for (row, &y) in out.chunks_exact_mut(size[0].max(1)).zip(&ys) {
+        for (value, &x) in row.iter_mut().zip(&xs) {
+            let s = SKEW_2D * (x + y);
+            *value = noise2_UnskewedBase(staticData, seed, x + s, y + s, &mut cache);
+        }
+    }
 }
 
-fn grad3(
-    seed: Wrapping<i64>,
-    xrvp: Wrapping<i64>,
-    yrvp: Wrapping<i64>,
-    zrvp: Wrapping<i64>,
-    dx: f32,
-    dy: f32,
-    dz: f32,
-) -> f32 {
-    let mut hash = (seed ^ xrvp) ^ (yrvp ^ zrvp);
-    hash *= HASH_MULTIPLIER;
-    hash ^= hash.0 >> (64 - N_GRADS_3D_EXPONENT + 2);
-    let gi = (hash.0 as i32 & ((N_GRADS_3D - 1) << 2)) as usize;
-    let grads = &getStaticData().gradients3D;
-    grads[gi | 0] * dx + grads[gi | 1] * dy + grads[gi | 2] * dz
+/**
+    2D OpenSimplex2S/SuperSimplex noise, with Y pointing down the main diagonal, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise2_ImproveX_Grid(
+    seed: i64,
+    origin: [f64; 2],
+    step: [f64; 2],
+    size: [usize; 2],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let mut cache = CellCache::new();
+    let xxs: Vec<f64> = gridAxis(origin[0], step[0], size[0])
+        .into_iter()
+        .map(|x| x * ROOT2OVER2)
+        .collect();
+    let yys: Vec<f64> = gridAxis(origin[1], step[1], size[1])
+        .into_iter()
+        .map(|y| y * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D)))
+        .collect();
+
+    for (row, &yy) in out.chunks_exact_mut(size[0].max(1)).zip(&yys) {
+        for (value, &xx) in row.iter_mut().zip(&xxs) {
+            *value = noise2_UnskewedBase(staticData, seed, yy + xx, yy - xx, &mut cache);
+        }
+    }
 }
 
-fn grad4(
-    seed: Wrapping<i64>,
-    xsvp: Wrapping<i64>,
-    ysvp: Wrapping<i64>,
-    zsvp: Wrapping<i64>,
-    wsvp: Wrapping<i64>,
-    dx: f32,
-    dy: f32,
-    dz: f32,
-    dw: f32,
-) -> f32 {
-    let mut hash = seed ^ (xsvp ^ ysvp) ^ (zsvp ^ wsvp);
-    hash *= HASH_MULTIPLIER;
-    hash ^= hash.0 >> (64 - N_GRADS_4D_EXPONENT + 2);
-    let gi = (hash.0 as i32 & ((N_GRADS_4D - 1) << 2)) as usize;
-    let grads = &getStaticData().gradients4D;
-    (grads[gi | 0] * dx + grads[gi | 1] * dy) + (grads[gi | 2] * dz + grads[gi | 3] * dw)
+/**
+    3D OpenSimplex2S/SuperSimplex noise, with better visual isotropy in (X, Y), on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise3_ImproveXY_Grid(
+    seed: i64,
+    origin: [f64; 3],
+    step: [f64; 3],
+    size: [usize; 3],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let mut cache = CellCache::new();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+
+    // The XY part of the rotation is shared by every Z plane.
+    let mut rotatedXY = Vec::with_capacity(xs.len() * ys.len());
+    for &y in &ys {
+        for &x in &xs {
+            let xy = x + y;
+            let s2 = xy * ROTATE3_ORTHOGONALIZER;
+            rotatedXY.push((x + s2, y + s2, xy * -ROOT3OVER3));
+        }
+    }
+
+    for (plane, &z) in out.chunks_exact_mut(rotatedXY.len().max(1)).zip(&zs) {
+        let zz = z * ROOT3OVER3;
+        for (value, &(xs2, ys2, xyr)) in plane.iter_mut().zip(&rotatedXY) {
+            *value =
+                noise3_UnrotatedBase(staticData, seed, xs2 + zz, ys2 + zz, xyr + zz, &mut cache);
+        }
+    }
 }
 
-fn fastFloor(x: f64) -> i32 {
-    let xi = x as i32;
-    if x < xi as f64 {
-        xi - 1
-    } else {
-        xi
+/**
+    3D OpenSimplex2S/SuperSimplex noise, with better visual isotropy in (X, Z), on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise3_ImproveXZ_Grid(
+    seed: i64,
+    origin: [f64; 3],
+    step: [f64; 3],
+    size: [usize; 3],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let mut cache = CellCache::new();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let yys: Vec<f64> = ys.iter().map(|y| y * ROOT3OVER3).collect();
+
+    for (plane, &z) in out.chunks_exact_mut((xs.len() * ys.len()).max(1)).zip(&zs) {
+        // The XZ part of the rotation is shared by every row of the plane.
+        let rotatedXZ: Vec<_> = xs
+            .iter()
+            .map(|&x| {
+                let xz = x + z;
+                let s2 = xz * -0.211324865405187;
+                (x + s2, z + s2, xz * -ROOT3OVER3)
+            })
+            .collect();
+
+        for (row, &yy) in plane.chunks_exact_mut(xs.len().max(1)).zip(&yys) {
+            for (value, &(xs2, zs2, xzr)) in row.iter_mut().zip(&rotatedXZ) {
+                *value = noise3_UnrotatedBase(
+                    staticData,
+                    seed,
+                    xs2 + yy,
+                    xzr + yy,
+                    zs2 + yy,
+                    &mut cache,
+                );
+            }
+        }
     }
 }
 
-/*
-    Lookup Tables & Gradients
+/**
+    3D OpenSimplex2S/SuperSimplex noise, fallback rotation option, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
 */
+pub fn noise3_Fallback_Grid(
+    seed: i64,
+    origin: [f64; 3],
+    step: [f64; 3],
+    size: [usize; 3],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let mut cache = CellCache::new();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+
+    let mut samples = out.iter_mut();
+    for &z in &zs {
+        for &y in &ys {
+            for (&x, value) in xs.iter().zip(samples.by_ref()) {
+                let r = FALLBACK_ROTATE3 * (x + y + z);
+                *value = noise3_UnrotatedBase(staticData, seed, r - x, r - y, r - z, &mut cache);
+            }
+        }
+    }
+}
 
-#[derive(Clone, Default)]
-struct LatticeVertex4D {
-    pub dx: f32,
-    pub dy: f32,
-    pub dz: f32,
-    pub dw: f32,
-    pub xsvp: i64,
-    pub ysvp: i64,
-    pub zsvp: i64,
-    pub wsvp: i64,
+/**
+    4D SuperSimplex noise, with XYZ oriented like noise3_ImproveXY, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_ImproveXYZ_ImproveXY_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut partsXY = Vec::with_capacity(xs.len() * ys.len());
+    for &y in &ys {
+        for &x in &xs {
+            let xy = x + y;
+            partsXY.push((
+                x,
+                y,
+                xy * -0.21132486540518699998,
+                xy * -0.57735026918962599998,
+            ));
+        }
+    }
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        let ww = w * 1.118033988749894;
+        for &z in &zs {
+            let zz = z * 0.28867513459481294226;
+            let zzww = zz + ww;
+            let wr = z * -0.866025403784439 + ww;
+            for (&(x, y, s2, xyr), value) in partsXY.iter().zip(samples.by_ref()) {
+                let xr = x + (zzww + s2);
+                let yr = y + (zzww + s2);
+                let zr = xyr + zzww;
+                *value = noise4_UnskewedBase(staticData, seed, xr, yr, zr, wr);
+            }
+        }
+    }
 }
 
-impl LatticeVertex4D {
-    pub fn new(xsv: i32, ysv: i32, zsv: i32, wsv: i32) -> Self {
-        let ssv = (xsv + ysv + zsv + wsv) as f32 * UNSKEW_4D;
-        Self {
-            xsvp: (Wrapping(xsv as i64) * Wrapping(PRIME_X)).0,
-            ysvp: (Wrapping(ysv as i64) * Wrapping(PRIME_Y)).0,
-            zsvp: (Wrapping(zsv as i64) * Wrapping(PRIME_Z)).0,
-            wsvp: (Wrapping(wsv as i64) * Wrapping(PRIME_W)).0,
-            dx: -xsv as f32 - ssv,
-            dy: -ysv as f32 - ssv,
-            dz: -zsv as f32 - ssv,
-            dw: -wsv as f32 - ssv,
+/**
+    4D SuperSimplex noise, with XYZ oriented like noise3_ImproveXZ, on a regular grid.
+
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_ImproveXYZ_ImproveXZ_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        let ww = w * 1.118033988749894;
+        for &z in &zs {
+            let partsXZ: Vec<_> = xs
+                .iter()
+                .map(|&x| {
+                    let xz = x + z;
+                    (
+                        x,
+                        xz * -0.21132486540518699998,
+                        xz * -0.57735026918962599998,
+                    )
+                })
+                .collect();
+            for &y in &ys {
+                let yy = y * 0.28867513459481294226;
+                let yyww = yy + ww;
+                let wr = y * -0.866025403784439 + ww;
+                for (&(x, s2, xzr), value) in partsXZ.iter().zip(samples.by_ref()) {
+                    let xr = x + (yyww + s2);
+                    let zr = z + (yyww + s2);
+                    let yr = xzr + yyww;
+                    *value = noise4_UnskewedBase(staticData, seed, xr, yr, zr, wr);
+                }
+            }
         }
     }
 }
 
-struct StaticData {
-    gradients2D: Vec<f32>,
-    gradients3D: Vec<f32>,
-    gradients4D: Vec<f32>,
+/**
+    4D SuperSimplex noise, with XYZ oriented like noise3_Fallback, on a regular grid.
 
-    lookup4DA: Vec<usize>,
-    lookup4DB: Vec<LatticeVertex4D>,
+    # Panics
+
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_ImproveXYZ_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        let ww = w * 1.118033988749894;
+        for &z in &zs {
+            for &y in &ys {
+                for (&x, value) in xs.iter().zip(samples.by_ref()) {
+                    let xyz = x + y + z;
+                    let s2 = xyz * -0.16666666666666666 + ww;
+                    let ws = -0.5 * xyz + ww;
+                    *value = noise4_UnskewedBase(staticData, seed, x + s2, y + s2, z + s2, ws);
+                }
+            }
+        }
+    }
 }
 
-static mut STATIC_DATA: (Once, Option<StaticData>) = (Once::new(), None);
+/**
+    4D SuperSimplex noise, with XY and ZW forming orthogonal triangular-based planes,
+    on a regular grid.
+
+    # Panics
 
-fn getStaticData() -> &'static StaticData {
-    unsafe {
-        STATIC_DATA.0.call_once(|| {
-            STATIC_DATA.1 = Some(initStaticData());
-        });
-        STATIC_DATA.1.as_ref().unwrap()
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_ImproveXY_ImproveZW_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut partsXY = Vec::with_capacity(xs.len() * ys.len());
+    for &y in &ys {
+        for &x in &xs {
+            let xy = x + y;
+            partsXY.push((
+                x,
+                y,
+                xy * -0.28522513987434876941,
+                xy * -0.48214856493302476942,
+            ));
+        }
+    }
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        for &z in &zs {
+            let zw = z + w;
+            let s2ZW = zw * 0.83897065470611435718;
+            let t2ZW = zw * 0.21939749883706435719;
+            for (&(x, y, s2XY, t2XY), value) in partsXY.iter().zip(samples.by_ref()) {
+                let s2 = s2XY + s2ZW;
+                let t2 = t2ZW + t2XY;
+                *value = noise4_UnskewedBase(staticData, seed, x + s2, y + s2, z + t2, w + t2);
+            }
+        }
     }
 }
 
-fn initStaticData() -> StaticData {
-    let gradients2D: Vec<_> = GRAD2_SRC
-        .into_iter()
-        .map(|v| (v / NORMALIZER_2D) as f32)
-        .collect::<Vec<_>>() // cache divisions
-        .into_iter()
-        .cycle()
-        .take((N_GRADS_2D * 2) as usize)
-        .collect();
+/**
+    4D SuperSimplex noise, fallback lattice orientation, on a regular grid.
 
-    let gradients3D: Vec<_> = GRAD3_SRC
-        .into_iter()
-        .map(|v| (v / NORMALIZER_3D) as f32)
-        .collect::<Vec<_>>() // cache divisions
-        .into_iter()
-        .cycle()
-        .take((N_GRADS_3D * 4) as usize)
-        .collect();
+    # Panics
 
-    let gradients4D: Vec<_> = GRAD4_SRC
-        .into_iter()
+    If `out.len()` isn't the product of `size`.
+*/
+pub fn noise4_Fallback_Grid(
+    seed: i64,
+    origin: [f64; 4],
+    step: [f64; 4],
+    size: [usize; 4],
+    out: &mut [f32],
+) {
+    checkGridLength(size.iter().product(), out);
+    let staticData = getStaticData();
+    let xs = gridAxis(origin[0], step[0], size[0]);
+    let ys = gridAxis(origin[1], step[1], size[1]);
+    let zs = gridAxis(origin[2], step[2], size[2]);
+    let ws = gridAxis(origin[3], step[3], size[3]);
+
+    let mut samples = out.iter_mut();
+    for &w in &ws {
+        for &z in &zs {
+            for &y in &ys {
+                for (&x, value) in xs.iter().zip(samples.by_ref()) {
+                    let s = SKEW_4D as f64 * (x + y + z + w);
+                    *value = noise4_UnskewedBase(staticData, seed, x + s, y + s, z + s, w + s);
+                }
+            }
+        }
+    }
+}
+
+/*
+    Lane Evaluators
+
+    Each function evaluates N independent points at once, in stages that each loop
+    over the lanes: lattice offsets and falloffs for every vertex, then the vertex
+    hashes, then the gradient reads and the falloff sums. The scalar branches are
+    selects, so the offset and falloff stages compile to packed SSE2 arithmetic on
+    baseline x86-64; the 64-bit hash multiply and the gradient reads stay one lane
+    at a time.
+
+    `cargo bench --bench lanes` times 8 lanes against the scalar loop over the same
+    points. On one x86-64 core, noise2_Lanes ran about 40% faster. There are no 3D lane
+    functions: every lane has to hash all eight vertices the scalar code might use, and
+    that ran 10-30% slower than calling noise3_ImproveXY/noise3_ImproveXZ/noise3_Fallback
+    in a loop, so use those (or the *_Grid functions) for 3D batches.
+    The scalar functions stay the reference implementation and the fallback for
+    batches that don't fill a whole set of lanes. Lane results match them to within
+    float rounding.
+*/
+
+/**
+    2D OpenSimplex2S/SuperSimplex noise, standard lattice orientation, for N points at once.
+*/
+pub fn noise2_Lanes<const N: usize>(seed: i64, x: [f64; N], y: [f64; N]) -> [f32; N] {
+    let mut xs = [0.0; N];
+    let mut ys = [0.0; N];
+    for l in 0..N {
+        let s = SKEW_2D * (x[l] + y[l]);
+        xs[l] = x[l] + s;
+        ys[l] = y[l] + s;
+    }
+
+    noise2_UnskewedBase_Lanes(getStaticData(), seed, xs, ys)
+}
+
+/**
+    2D OpenSimplex2S/SuperSimplex noise, with Y pointing down the main diagonal,
+    for N points at once.
+*/
+pub fn noise2_ImproveX_Lanes<const N: usize>(seed: i64, x: [f64; N], y: [f64; N]) -> [f32; N] {
+    let mut xs = [0.0; N];
+    let mut ys = [0.0; N];
+    for l in 0..N {
+        let xx = x[l] * ROOT2OVER2;
+        let yy = y[l] * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D));
+        xs[l] = yy + xx;
+        ys[l] = yy - xx;
+    }
+
+    noise2_UnskewedBase_Lanes(getStaticData(), seed, xs, ys)
+}
+
+/**
+    2D OpenSimplex2S/SuperSimplex noise base, N lanes.
+*/
+fn noise2_UnskewedBase_Lanes<const N: usize>(
+    staticData: &StaticData,
+    seed: i64,
+    xs: [f64; N],
+    ys: [f64; N],
+) -> [f32; N] {
+    let seed = Wrapping(seed);
+
+    // Get base points and offsets.
+    let mut xi = [0.0; N];
+    let mut yi = [0.0; N];
+    let mut xsbp = [Wrapping(0); N];
+    let mut ysbp = [Wrapping(0); N];
+    let mut t = [0.0; N];
+    let mut dx0 = [0.0; N];
+    let mut dy0 = [0.0; N];
+    for l in 0..N {
+        let xsb = floorLane(xs[l]);
+        let ysb = floorLane(ys[l]);
+        xi[l] = (xs[l] - xsb) as f32;
+        yi[l] = (ys[l] - ysb) as f32;
+
+        // Prime pre-multiplication for hash.
+        xsbp[l] = Wrapping(xsb as i32 as i64) * Wrapping(PRIME_X);
+        ysbp[l] = Wrapping(ysb as i32 as i64) * Wrapping(PRIME_Y);
+
+        // Unskew.
+        t[l] = (xi[l] + yi[l]) * UNSKEW_2D as f32;
+        dx0[l] = xi[l] + t[l];
+        dy0[l] = yi[l] + t[l];
+    }
+
+    // First vertex.
+    let mut value = [0.0; N];
+    let mut a0 = [0.0; N];
+    for l in 0..N {
+        a0[l] = RSQUARED_2D - dx0[l] * dx0[l] - dy0[l] * dy0[l];
+    }
+    grad2_Lanes(staticData, seed, xsbp, ysbp, dx0, dy0, a0, &mut value);
+
+    // Second vertex.
+    let mut a1 = [0.0; N];
+    let mut dx1 = [0.0; N];
+    let mut dy1 = [0.0; N];
+    let mut xp1 = [Wrapping(0); N];
+    let mut yp1 = [Wrapping(0); N];
+    for l in 0..N {
+        a1[l] = (2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 / UNSKEW_2D + 2.0)) as f32 * t[l]
+            + ((-2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 + 2.0 * UNSKEW_2D)) as f32 + a0[l]);
+        dx1[l] = dx0[l] - (1.0 + 2.0 * UNSKEW_2D) as f32;
+        dy1[l] = dy0[l] - (1.0 + 2.0 * UNSKEW_2D) as f32;
+        xp1[l] = xsbp[l] + Wrapping(PRIME_X);
+        yp1[l] = ysbp[l] + Wrapping(PRIME_Y);
+    }
+    grad2_Lanes(staticData, seed, xp1, yp1, dx1, dy1, a1, &mut value);
+
+    // Third and fourth vertices: each picks one of two candidates,
+    // depending on which half of the cell we're in.
+    let mut a2 = [0.0; N];
+    let mut dx2 = [0.0; N];
+    let mut dy2 = [0.0; N];
+    let mut xp2 = [Wrapping(0); N];
+    let mut yp2 = [Wrapping(0); N];
+    let mut a3 = [0.0; N];
+    let mut dx3 = [0.0; N];
+    let mut dy3 = [0.0; N];
+    let mut xp3 = [Wrapping(0); N];
+    let mut yp3 = [Wrapping(0); N];
+    for l in 0..N {
+        let xmyi = xi[l] - yi[l];
+        let lower = t[l] < UNSKEW_2D as f32;
+
+        let far2 = select(lower, xi[l] + xmyi > 1.0, xi[l] + xmyi < 0.0);
+        dx2[l] = dx0[l]
+            - select(
+                lower,
+                select(far2, (3.0 * UNSKEW_2D + 2.0) as f32, UNSKEW_2D as f32),
+                select(far2, -((1.0 + UNSKEW_2D) as f32), (UNSKEW_2D + 1.0) as f32),
+            );
+        dy2[l] = dy0[l]
+            - select(
+                lower,
+                select(
+                    far2,
+                    (3.0 * UNSKEW_2D + 1.0) as f32,
+                    (UNSKEW_2D + 1.0) as f32,
+                ),
+                select(far2, -(UNSKEW_2D as f32), UNSKEW_2D as f32),
+            );
+        xp2[l] = xsbp[l]
+            + select(
+                lower,
+                select(far2, Wrapping(PRIME_X << 1), Wrapping(0)),
+                select(far2, -Wrapping(PRIME_X), Wrapping(PRIME_X)),
+            );
+        yp2[l] = ysbp[l] + select(lower, Wrapping(PRIME_Y), Wrapping(0));
+        a2[l] = RSQUARED_2D - dx2[l] * dx2[l] - dy2[l] * dy2[l];
+
+        let far3 = select(lower, yi[l] - xmyi > 1.0, yi[l] < xmyi);
+        dx3[l] = dx0[l]
+            - select(
+                lower,
+                select(
+                    far3,
+                    (3.0 * UNSKEW_2D + 1.0) as f32,
+                    (UNSKEW_2D + 1.0) as f32,
+                ),
+                select(far3, -(UNSKEW_2D as f32), UNSKEW_2D as f32),
+            );
+        dy3[l] = dy0[l]
+            - select(
+                lower,
+                select(far3, (3.0 * UNSKEW_2D + 2.0) as f32, UNSKEW_2D as f32),
+                select(far3, -((UNSKEW_2D + 1.0) as f32), (UNSKEW_2D + 1.0) as f32),
+            );
+        xp3[l] = xsbp[l] + select(lower, Wrapping(PRIME_X), Wrapping(0));
+        yp3[l] = ysbp[l]
+            + select(
+                lower,
+                select(far3, Wrapping(PRIME_Y << 1), Wrapping(0)),
+                select(far3, -Wrapping(PRIME_Y), Wrapping(PRIME_Y)),
+            );
+        a3[l] = RSQUARED_2D - dx3[l] * dx3[l] - dy3[l] * dy3[l];
+    }
+    grad2_Lanes(staticData, seed, xp2, yp2, dx2, dy2, a2, &mut value);
+    grad2_Lanes(staticData, seed, xp3, yp3, dx3, dy3, a3, &mut value);
+
+    value
+}
+
+/**
+    Adds one vertex's contribution to every lane, one stage at a time: hashes, gradient reads,
+    then falloffs.
+*/
+fn grad2_Lanes<const N: usize>(
+    staticData: &StaticData,
+    seed: Wrapping<i64>,
+    xsvp: [Wrapping<i64>; N],
+    ysvp: [Wrapping<i64>; N],
+    dx: [f32; N],
+    dy: [f32; N],
+    a: [f32; N],
+    value: &mut [f32; N],
+) {
+    let mut gi = [0; N];
+    for l in 0..N {
+        let mut hash = seed ^ xsvp[l] ^ ysvp[l];
+        hash *= HASH_MULTIPLIER;
+        let shifted = ((hash.0 >> 32) as i32) >> (32 - N_GRADS_2D_EXPONENT + 1);
+        gi[l] = ((hash.0 as i32 ^ shifted) & ((N_GRADS_2D - 1) << 1)) as usize;
+    }
+
+    let grads = &staticData.gradients2D[..N_GRADS_2D as usize * 2];
+    let mut g = [[0.0; 2]; N];
+    for l in 0..N {
+        g[l] = [grads[gi[l] | 0], grads[gi[l] | 1]];
+    }
+
+    for l in 0..N {
+        let a = a[l].max(0.0);
+        value[l] += (a * a) * (a * a) * (g[l][0] * dx[l] + g[l][1] * dy[l]);
+    }
+}
+
+/**
+    `fastFloor` as a float, with the branch as a select.
+*/
+fn floorLane(x: f64) -> f64 {
+    let xi = (x as i32) as f64;
+    select(x < xi, xi - 1.0, xi)
+}
+
+/*
+    Derivative Evaluators
+
+    Each function returns the same value as its scalar counterpart, together with
+    the analytic gradient of the noise with respect to the input coordinates.
+    The per-vertex terms are differentiated in lattice space, then mapped back
+    through the transpose of the function's skew or rotation.
+*/
+
+/**
+    2D OpenSimplex2S/SuperSimplex noise, standard lattice orientation, with derivatives.
+*/
+pub fn noise2_with_derivatives(seed: i64, x: f64, y: f64) -> (f32, [f32; 2]) {
+    let s = SKEW_2D * (x + y);
+    let xs = x + s;
+    let ys = y + s;
+
+    let (value, [dxs, dys]) = noise2_UnskewedBase_Derivatives(getStaticData(), seed, xs, ys);
+    let s = (dxs + dys) as f64 * SKEW_2D;
+    (value, [(dxs as f64 + s) as f32, (dys as f64 + s) as f32])
+}
+
+/**
+    2D OpenSimplex2S/SuperSimplex noise, with Y pointing down the main diagonal, with derivatives.
+*/
+pub fn noise2_ImproveX_with_derivatives(seed: i64, x: f64, y: f64) -> (f32, [f32; 2]) {
+    let xx = x * ROOT2OVER2;
+    let yy = y * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D));
+
+    let (value, [dxs, dys]) =
+        noise2_UnskewedBase_Derivatives(getStaticData(), seed, yy + xx, yy - xx);
+    let dx = (dxs - dys) as f64 * ROOT2OVER2;
+    let dy = (dxs + dys) as f64 * (ROOT2OVER2 * (1.0 + 2.0 * SKEW_2D));
+    (value, [dx as f32, dy as f32])
+}
+
+/**
+    3D OpenSimplex2S/SuperSimplex noise, with better visual isotropy in (X, Y), with derivatives.
+*/
+pub fn noise3_ImproveXY_with_derivatives(seed: i64, x: f64, y: f64, z: f64) -> (f32, [f32; 3]) {
+    let xy = x + y;
+    let s2 = xy * ROTATE3_ORTHOGONALIZER;
+    let zz = z * ROOT3OVER3;
+    let xr = x + s2 + zz;
+    let yr = y + s2 + zz;
+    let zr = xy * -ROOT3OVER3 + zz;
+
+    let (value, [dxr, dyr, dzr]) =
+        noise3_UnrotatedBase_Derivatives(getStaticData(), seed, xr, yr, zr);
+    let (dxr, dyr, dzr) = (dxr as f64, dyr as f64, dzr as f64);
+    let s2 = (dxr + dyr) * ROTATE3_ORTHOGONALIZER - dzr * ROOT3OVER3;
+    let dz = (dxr + dyr + dzr) * ROOT3OVER3;
+    (value, [(dxr + s2) as f32, (dyr + s2) as f32, dz as f32])
+}
+
+/**
+    3D OpenSimplex2S/SuperSimplex noise, with better visual isotropy in (X, Z), with derivatives.
+*/
+pub fn noise3_ImproveXZ_with_derivatives(seed: i64, x: f64, y: f64, z: f64) -> (f32, [f32; 3]) {
+    let xz = x + z;
+    let s2 = xz * -0.211324865405187;
+    let yy = y * ROOT3OVER3;
+    let xr = x + s2 + yy;
+    let zr = z + s2 + yy;
+    let yr = xz * -ROOT3OVER3 + yy;
+
+    let (value, [dxr, dyr, dzr]) =
+        noise3_UnrotatedBase_Derivatives(getStaticData(), seed, xr, yr, zr);
+    let (dxr, dyr, dzr) = (dxr as f64, dyr as f64, dzr as f64);
+    let s2 = (dxr + dzr) * -0.211324865405187 - dyr * ROOT3OVER3;
+    let dy = (dxr + dyr + dzr) * ROOT3OVER3;
+    (value, [(dxr + s2) as f32, dy as f32, (dzr + s2) as f32])
+}
+
+/**
+    3D OpenSimplex2S/SuperSimplex noise, fallback rotation option, with derivatives.
+*/
+pub fn noise3_Fallback_with_derivatives(seed: i64, x: f64, y: f64, z: f64) -> (f32, [f32; 3]) {
+    let r = FALLBACK_ROTATE3 * (x + y + z);
+    let xr = r - x;
+    let yr = r - y;
+    let zr = r - z;
+
+    let (value, [dxr, dyr, dzr]) =
+        noise3_UnrotatedBase_Derivatives(getStaticData(), seed, xr, yr, zr);
+    let (dxr, dyr, dzr) = (dxr as f64, dyr as f64, dzr as f64);
+    let r = FALLBACK_ROTATE3 * (dxr + dyr + dzr);
+    (
+        value,
+        [(r - dxr) as f32, (r - dyr) as f32, (r - dzr) as f32],
+    )
+}
+
+/**
+    4D SuperSimplex noise, with XYZ oriented like noise3_ImproveXY, with derivatives.
+*/
+pub fn noise4_ImproveXYZ_ImproveXY_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let xy = x + y;
+    let s2 = xy * -0.21132486540518699998;
+    let zz = z * 0.28867513459481294226;
+    let ww = w * 1.118033988749894;
+    let xr = x + (zz + ww + s2);
+    let yr = y + (zz + ww + s2);
+    let zr = xy * -0.57735026918962599998 + (zz + ww);
+    let wr = z * -0.866025403784439 + ww;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getStaticData(), seed, xr, yr, zr, wr);
+    let [dxr, dyr, dzr, dwr] = derivatives.map(|d| d as f64);
+    let s2 = (dxr + dyr) * -0.21132486540518699998 + dzr * -0.57735026918962599998;
+    let dz = (dxr + dyr + dzr) * 0.28867513459481294226 + dwr * -0.866025403784439;
+    let dw = (dxr + dyr + dzr + dwr) * 1.118033988749894;
+    (
+        value,
+        [(dxr + s2) as f32, (dyr + s2) as f32, dz as f32, dw as f32],
+    )
+}
+
+/**
+    4D SuperSimplex noise, with XYZ oriented like noise3_ImproveXZ, with derivatives.
+*/
+pub fn noise4_ImproveXYZ_ImproveXZ_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let xz = x + z;
+    let s2 = xz * -0.21132486540518699998;
+    let yy = y * 0.28867513459481294226;
+    let ww = w * 1.118033988749894;
+    let xr = x + (yy + ww + s2);
+    let zr = z + (yy + ww + s2);
+    let yr = xz * -0.57735026918962599998 + (yy + ww);
+    let wr = y * -0.866025403784439 + ww;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getStaticData(), seed, xr, yr, zr, wr);
+    let [dxr, dyr, dzr, dwr] = derivatives.map(|d| d as f64);
+    let s2 = (dxr + dzr) * -0.21132486540518699998 + dyr * -0.57735026918962599998;
+    let dy = (dxr + dyr + dzr) * 0.28867513459481294226 + dwr * -0.866025403784439;
+    let dw = (dxr + dyr + dzr + dwr) * 1.118033988749894;
+    (
+        value,
+        [(dxr + s2) as f32, dy as f32, (dzr + s2) as f32, dw as f32],
+    )
+}
+
+/**
+    4D SuperSimplex noise, with XYZ oriented like noise3_Fallback, with derivatives.
+*/
+pub fn noise4_ImproveXYZ_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let xyz = x + y + z;
+    let ww = w * 1.118033988749894;
+    let s2 = xyz * -0.16666666666666666 + ww;
+    let xs = x + s2;
+    let ys = y + s2;
+    let zs = z + s2;
+    let ws = -0.5 * xyz + ww;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getStaticData(), seed, xs, ys, zs, ws);
+    let [dxs, dys, dzs, dws] = derivatives.map(|d| d as f64);
+    let s2 = (dxs + dys + dzs) * -0.16666666666666666 - 0.5 * dws;
+    let dw = (dxs + dys + dzs + dws) * 1.118033988749894;
+    (
+        value,
+        [
+            (dxs + s2) as f32,
+            (dys + s2) as f32,
+            (dzs + s2) as f32,
+            dw as f32,
+        ],
+    )
+}
+
+/**
+    4D SuperSimplex noise, with XY and ZW forming orthogonal triangular-based planes, with derivatives.
+*/
+pub fn noise4_ImproveXY_ImproveZW_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let s2 = (x + y) * -0.28522513987434876941 + (z + w) * 0.83897065470611435718;
+    let t2 = (z + w) * 0.21939749883706435719 + (x + y) * -0.48214856493302476942;
+    let xs = x + s2;
+    let ys = y + s2;
+    let zs = z + t2;
+    let ws = w + t2;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getStaticData(), seed, xs, ys, zs, ws);
+    let [dxs, dys, dzs, dws] = derivatives.map(|d| d as f64);
+    let s2 = (dxs + dys) * -0.28522513987434876941 + (dzs + dws) * -0.48214856493302476942;
+    let t2 = (dzs + dws) * 0.21939749883706435719 + (dxs + dys) * 0.83897065470611435718;
+    (
+        value,
+        [
+            (dxs + s2) as f32,
+            (dys + s2) as f32,
+            (dzs + t2) as f32,
+            (dws + t2) as f32,
+        ],
+    )
+}
+
+/**
+    4D SuperSimplex noise, fallback lattice orientation, with derivatives.
+*/
+pub fn noise4_Fallback_with_derivatives(
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> (f32, [f32; 4]) {
+    let s = SKEW_4D as f64 * (x + y + z + w);
+    let xs = x + s;
+    let ys = y + s;
+    let zs = z + s;
+    let ws = w + s;
+
+    let (value, derivatives) =
+        noise4_UnskewedBase_Derivatives(getStaticData(), seed, xs, ys, zs, ws);
+    let [dxs, dys, dzs, dws] = derivatives.map(|d| d as f64);
+    let s = SKEW_4D as f64 * (dxs + dys + dzs + dws);
+    (
+        value,
+        [
+            (dxs + s) as f32,
+            (dys + s) as f32,
+            (dzs + s) as f32,
+            (dws + s) as f32,
+        ],
+    )
+}
+
+/**
+    2D OpenSimplex2S/SuperSimplex noise base, with derivatives with respect to (xs, ys).
+*/
+fn noise2_UnskewedBase_Derivatives(
+    staticData: &StaticData,
+    seed: i64,
+    xs: f64,
+    ys: f64,
+) -> (f32, [f32; 2]) {
+    let seed = Wrapping(seed);
+
+    // Get base points and offsets.
+    let xsb = fastFloor(xs);
+    let ysb = fastFloor(ys);
+    let xi = (xs - xsb as f64) as f32;
+    let yi = (ys - ysb as f64) as f32;
+
+    // Prime pre-multiplication for hash.
+    let xsbp = Wrapping(xsb as i64) * Wrapping(PRIME_X);
+    let ysbp = Wrapping(ysb as i64) * Wrapping(PRIME_Y);
+
+    // Unskew.
+    let t = (xi + yi) * UNSKEW_2D as f32;
+    let dx0 = xi + t;
+    let dy0 = yi + t;
+
+    // First vertex.
+    let a0 = RSQUARED_2D - dx0 * dx0 - dy0 * dy0;
+    let mut derivatives = [0.0; 2];
+    let mut value = contribution2(staticData, &mut derivatives, a0, seed, xsbp, ysbp, dx0, dy0);
+
+    // Second vertex.
+    let a1 = (2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 / UNSKEW_2D + 2.0)) as f32 * t
+        + ((-2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 + 2.0 * UNSKEW_2D)) as f32 + a0);
+    let dx1 = dx0 - (1.0 + 2.0 * UNSKEW_2D) as f32;
+    let dy1 = dy0 - (1.0 + 2.0 * UNSKEW_2D) as f32;
+    value += contribution2(
+        staticData,
+        &mut derivatives,
+        a1,
+        seed,
+        xsbp + Wrapping(PRIME_X),
+        ysbp + Wrapping(PRIME_Y),
+        dx1,
+        dy1,
+    );
+
+    // Third and fourth vertices.
+    // Nested conditionals were faster than compact bit logic/arithmetic.
+    let xmyi = xi - yi;
+    if t < UNSKEW_2D as f32 {
+        if xi + xmyi > 1.0 {
+            let dx2 = dx0 - (3.0 * UNSKEW_2D + 2.0) as f32;
+            let dy2 = dy0 - (3.0 * UNSKEW_2D + 1.0) as f32;
+            let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+            if a2 > 0.0 {
+                value += contribution2(
+                    staticData,
+                    &mut derivatives,
+                    a2,
+                    seed,
+                    xsbp + Wrapping(PRIME_X << 1),
+                    ysbp + Wrapping(PRIME_Y),
+                    dx2,
+                    dy2,
+                );
+            }
+        } else {
+            let dx2 = dx0 - UNSKEW_2D as f32;
+            let dy2 = dy0 - (UNSKEW_2D + 1.0) as f32;
+            let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+            if a2 > 0.0 {
+                value += contribution2(
+                    staticData,
+                    &mut derivatives,
+                    a2,
+                    seed,
+                    xsbp,
+                    ysbp + Wrapping(PRIME_Y),
+                    dx2,
+                    dy2,
+                );
+            }
+        }
+
+        if yi - xmyi > 1.0 {
+            let dx3 = dx0 - (3.0 * UNSKEW_2D + 1.0) as f32;
+            let dy3 = dy0 - (3.0 * UNSKEW_2D + 2.0) as f32;
+            let a3 = RSQUARED_2D - dx3 * dx3 - dy3 * dy3;
+            if a3 > 0.0 {
+                value += contribution2(
+                    staticData,
+                    &mut derivatives,
+                    a3,
+                    seed,
+                    xsbp + Wrapping(PRIME_X),
+                    ysbp + Wrapping(PRIME_Y << 1),
+                    dx3,
+                    dy3,
+                );
+            }
+        } else {
+            let dx3 = dx0 - (UNSKEW_2D + 1.0) as f32;
+            let dy3 = dy0 - UNSKEW_2D as f32;
+            let a3 = RSQUARED_2D - dx3 * dx3 - dy3 * dy3;
+            if a3 > 0.0 {
+                value += contribution2(
+                    staticData,
+                    &mut derivatives,
+                    a3,
+                    seed,
+                    xsbp + Wrapping(PRIME_X),
+                    ysbp,
+                    dx3,
+                    dy3,
+                );
+            }
+        }
+    } else {
+        if xi + xmyi < 0.0 {
+            let dx2 = dx0 + (1.0 + UNSKEW_2D) as f32;
+            let dy2 = dy0 + UNSKEW_2D as f32;
+            let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+            if a2 > 0.0 {
+                value += contribution2(
+                    staticData,
+                    &mut derivatives,
+                    a2,
+                    seed,
+                    xsbp - Wrapping(PRIME_X),
+                    ysbp,
+                    dx2,
+                    dy2,
+                );
+            }
+        } else {
+            let dx2 = dx0 - (UNSKEW_2D + 1.0) as f32;
+            let dy2 = dy0 - UNSKEW_2D as f32;
+            let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+            if a2 > 0.0 {
+                value += contribution2(
+                    staticData,
+                    &mut derivatives,
+                    a2,
+                    seed,
+                    xsbp + Wrapping(PRIME_X),
+                    ysbp,
+                    dx2,
+                    dy2,
+                );
+            }
+        }
+
+        if yi < xmyi {
+            let dx2 = dx0 + UNSKEW_2D as f32;
+            let dy2 = dy0 + (UNSKEW_2D + 1.0) as f32;
+            let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+            if a2 > 0.0 {
+                value += contribution2(
+                    staticData,
+                    &mut derivatives,
+                    a2,
+                    seed,
+                    xsbp,
+                    ysbp - Wrapping(PRIME_Y),
+                    dx2,
+                    dy2,
+                );
+            }
+        } else {
+            let dx2 = dx0 - UNSKEW_2D as f32;
+            let dy2 = dy0 - (UNSKEW_2D + 1.0) as f32;
+            let a2 = RSQUARED_2D - dx2 * dx2 - dy2 * dy2;
+            if a2 > 0.0 {
+                value += contribution2(
+                    staticData,
+                    &mut derivatives,
+                    a2,
+                    seed,
+                    xsbp,
+                    ysbp + Wrapping(PRIME_Y),
+                    dx2,
+                    dy2,
+                );
+            }
+        }
+    }
+
+    // Every offset is the unskewed base offset plus a constant.
+    let unskew = (derivatives[0] + derivatives[1]) * UNSKEW_2D as f32;
+    (value, [derivatives[0] + unskew, derivatives[1] + unskew])
+}
+
+/**
+    Generate overlapping cubic lattices for 3D Re-oriented BCC noise,
+    with derivatives with respect to (xr, yr, zr).
+*/
+fn noise3_UnrotatedBase_Derivatives(
+    staticData: &StaticData,
+    seed: i64,
+    xr: f64,
+    yr: f64,
+    zr: f64,
+) -> (f32, [f32; 3]) {
+    let seed = Wrapping(seed);
+
+    // Get base points and offsets.
+    let xrb = fastFloor(xr);
+    let yrb = fastFloor(yr);
+    let zrb = fastFloor(zr);
+    let xi = (xr - xrb as f64) as f32;
+    let yi = (yr - yrb as f64) as f32;
+    let zi = (zr - zrb as f64) as f32;
+
+    // Prime pre-multiplication for hash. Also flip seed for second lattice copy.
+    let xrbp = Wrapping(xrb as i64) * Wrapping(PRIME_X);
+    let yrbp = Wrapping(yrb as i64) * Wrapping(PRIME_Y);
+    let zrbp = Wrapping(zrb as i64) * Wrapping(PRIME_Z);
+    let seed2 = seed ^ Wrapping(SEED_FLIP_3D);
+
+    // -1 if positive, 0 if negative.
+    let xNMask = (-0.5 - xi) as i32;
+    let yNMask = (-0.5 - yi) as i32;
+    let zNMask = (-0.5 - zi) as i32;
+
+    // First vertex.
+    let x0 = xi + xNMask as f32;
+    let y0 = yi + yNMask as f32;
+    let z0 = zi + zNMask as f32;
+    let a0 = RSQUARED_3D - x0 * x0 - y0 * y0 - z0 * z0;
+    let mut derivatives = [0.0; 3];
+    let mut value = contribution3(
+        staticData,
+        &mut derivatives,
+        a0,
+        seed,
+        xrbp + (Wrapping(xNMask as i64) & Wrapping(PRIME_X)),
+        yrbp + (Wrapping(yNMask as i64) & Wrapping(PRIME_Y)),
+        zrbp + (Wrapping(zNMask as i64) & Wrapping(PRIME_Z)),
+        x0,
+        y0,
+        z0,
+    );
+
+    // Second vertex.
+    let x1 = xi - 0.5;
+    let y1 = yi - 0.5;
+    let z1 = zi - 0.5;
+    let a1 = RSQUARED_3D - x1 * x1 - y1 * y1 - z1 * z1;
+    value += contribution3(
+        staticData,
+        &mut derivatives,
+        a1,
+        seed2,
+        xrbp + Wrapping(PRIME_X),
+        yrbp + Wrapping(PRIME_Y),
+        zrbp + Wrapping(PRIME_Z),
+        x1,
+        y1,
+        z1,
+    );
+
+    // Shortcuts for building the remaining falloffs.
+    // Derived by subtracting the polynomials with the offsets plugged in.
+    let xAFlipMask0 = ((xNMask | 1) << 1) as f32 * x1;
+    let yAFlipMask0 = ((yNMask | 1) << 1) as f32 * y1;
+    let zAFlipMask0 = ((zNMask | 1) << 1) as f32 * z1;
+    let xAFlipMask1 = (-2 - (xNMask << 2)) as f32 * x1 - 1.0;
+    let yAFlipMask1 = (-2 - (yNMask << 2)) as f32 * y1 - 1.0;
+    let zAFlipMask1 = (-2 - (zNMask << 2)) as f32 * z1 - 1.0;
+
+    let mut skip5 = false;
+    let a2 = xAFlipMask0 + a0;
+    if a2 > 0.0 {
+        let x2 = x0 - (xNMask | 1) as f32;
+        let y2 = y0;
+        let z2 = z0;
+        value += contribution3(
+            staticData,
+            &mut derivatives,
+            a2,
+            seed,
+            xrbp + (Wrapping(!xNMask as i64) & Wrapping(PRIME_X)),
+            yrbp + (Wrapping(yNMask as i64) & Wrapping(PRIME_Y)),
+            zrbp + (Wrapping(zNMask as i64) & Wrapping(PRIME_Z)),
+            x2,
+            y2,
+            z2,
+        );
+    } else {
+        let a3 = yAFlipMask0 + zAFlipMask0 + a0;
+        if a3 > 0.0 {
+            let x3 = x0;
+            let y3 = y0 - (yNMask | 1) as f32;
+            let z3 = z0 - (zNMask | 1) as f32;
+            value += contribution3(
+                staticData,
+                &mut derivatives,
+                a3,
+                seed,
+                xrbp + (Wrapping(xNMask as i64) & Wrapping(PRIME_X)),
+                yrbp + (Wrapping(!yNMask as i64) & Wrapping(PRIME_Y)),
+                zrbp + (Wrapping(!zNMask as i64) & Wrapping(PRIME_Z)),
+                x3,
+                y3,
+                z3,
+            );
+        }
+
+        let a4 = xAFlipMask1 + a1;
+        if a4 > 0.0 {
+            let x4 = (xNMask | 1) as f32 + x1;
+            let y4 = y1;
+            let z4 = z1;
+            value += contribution3(
+                staticData,
+                &mut derivatives,
+                a4,
+                seed2,
+                xrbp + (Wrapping(xNMask as i64) & (Wrapping(PRIME_X) << 1)),
+                yrbp + Wrapping(PRIME_Y),
+                zrbp + Wrapping(PRIME_Z),
+                x4,
+                y4,
+                z4,
+            );
+            skip5 = true;
+        }
+    }
+
+    let mut skip9 = false;
+    let a6 = yAFlipMask0 + a0;
+    if a6 > 0.0 {
+        let x6 = x0;
+        let y6 = y0 - (yNMask | 1) as f32;
+        let z6 = z0;
+        value += contribution3(
+            staticData,
+            &mut derivatives,
+            a6,
+            seed,
+            xrbp + (Wrapping(xNMask as i64) & Wrapping(PRIME_X)),
+            yrbp + (Wrapping(!yNMask as i64) & Wrapping(PRIME_Y)),
+            zrbp + (Wrapping(zNMask as i64) & Wrapping(PRIME_Z)),
+            x6,
+            y6,
+            z6,
+        );
+    } else {
+        let a7 = xAFlipMask0 + zAFlipMask0 + a0;
+        if a7 > 0.0 {
+            let x7 = x0 - (xNMask | 1) as f32;
+            let y7 = y0;
+            let z7 = z0 - (zNMask | 1) as f32;
+            value += contribution3(
+                staticData,
+                &mut derivatives,
+                a7,
+                seed,
+                xrbp + (Wrapping(!xNMask as i64) & Wrapping(PRIME_X)),
+                yrbp + (Wrapping(yNMask as i64) & Wrapping(PRIME_Y)),
+                zrbp + (Wrapping(!zNMask as i64) & Wrapping(PRIME_Z)),
+                x7,
+                y7,
+                z7,
+            );
+        }
+
+        let a8 = yAFlipMask1 + a1;
+        if a8 > 0.0 {
+            let x8 = x1;
+            let y8 = (yNMask | 1) as f32 + y1;
+            let z8 = z1;
+            value += contribution3(
+                staticData,
+                &mut derivatives,
+                a8,
+                seed2,
+                xrbp + Wrapping(PRIME_X),
+                yrbp + (Wrapping(yNMask as i64) & (Wrapping(PRIME_Y) << 1)),
+                zrbp + Wrapping(PRIME_Z),
+                x8,
+                y8,
+                z8,
+            );
+            skip9 = true;
+        }
+    }
+
+    let mut skipD = false;
+    let aA = zAFlipMask0 + a0;
+    if aA > 0.0 {
+        let xA = x0;
+        let yA = y0;
+        let zA = z0 - (zNMask | 1) as f32;
+        value += contribution3(
+            staticData,
+            &mut derivatives,
+            aA,
+            seed,
+            xrbp + (Wrapping(xNMask as i64) & Wrapping(PRIME_X)),
+            yrbp + (Wrapping(yNMask as i64) & Wrapping(PRIME_Y)),
+            zrbp + (Wrapping(!zNMask as i64) & Wrapping(PRIME_Z)),
+            xA,
+            yA,
+            zA,
+        );
+    } else {
+        let aB = xAFlipMask0 + yAFlipMask0 + a0;
+        if aB > 0.0 {
+            let xB = x0 - (xNMask | 1) as f32;
+            let yB = y0 - (yNMask | 1) as f32;
+            let zB = z0;
+            value += contribution3(
+                staticData,
+                &mut derivatives,
+                aB,
+                seed,
+                xrbp + (Wrapping(!xNMask as i64) & Wrapping(PRIME_X)),
+                yrbp + (Wrapping(!yNMask as i64) & Wrapping(PRIME_Y)),
+                zrbp + (Wrapping(zNMask as i64) & Wrapping(PRIME_Z)),
+                xB,
+                yB,
+                zB,
+            );
+        }
+
+        let aC = zAFlipMask1 + a1;
+        if aC > 0.0 {
+            let xC = x1;
+            let yC = y1;
+            let zC = (zNMask | 1) as f32 + z1;
+            value += contribution3(
+                staticData,
+                &mut derivatives,
+                aC,
+                seed2,
+                xrbp + Wrapping(PRIME_X),
+                yrbp + Wrapping(PRIME_Y),
+                zrbp + (Wrapping(zNMask as i64) & (Wrapping(PRIME_Z) << 1)),
+                xC,
+                yC,
+                zC,
+            );
+            skipD = true;
+        }
+    }
+
+    if !skip5 {
+        let a5 = yAFlipMask1 + zAFlipMask1 + a1;
+        if a5 > 0.0 {
+            let x5 = x1;
+            let y5 = (yNMask | 1) as f32 + y1;
+            let z5 = (zNMask | 1) as f32 + z1;
+            value += contribution3(
+                staticData,
+                &mut derivatives,
+                a5,
+                seed2,
+                xrbp + Wrapping(PRIME_X),
+                yrbp + (Wrapping(yNMask as i64) & (Wrapping(PRIME_Y) << 1)),
+                zrbp + (Wrapping(zNMask as i64) & (Wrapping(PRIME_Z) << 1)),
+                x5,
+                y5,
+                z5,
+            );
+        }
+    }
+
+    if !skip9 {
+        let a9 = xAFlipMask1 + zAFlipMask1 + a1;
+        if a9 > 0.0 {
+            let x9 = (xNMask | 1) as f32 + x1;
+            let y9 = y1;
+            let z9 = (zNMask | 1) as f32 + z1;
+            value += contribution3(
+                staticData,
+                &mut derivatives,
+                a9,
+                seed2,
+                xrbp + (Wrapping(xNMask as i64) & (Wrapping(PRIME_X) << 1)),
+                yrbp + Wrapping(PRIME_Y),
+                zrbp + (Wrapping(zNMask as i64) & (Wrapping(PRIME_Z) << 1)),
+                x9,
+                y9,
+                z9,
+            );
+        }
+    }
+
+    if !skipD {
+        let aD = xAFlipMask1 + yAFlipMask1 + a1;
+        if aD > 0.0 {
+            let xD = (xNMask | 1) as f32 + x1;
+            let yD = (yNMask | 1) as f32 + y1;
+            let zD = z1;
+            value += contribution3(
+                staticData,
+                &mut derivatives,
+                aD,
+                seed2,
+                xrbp + (Wrapping(xNMask as i64) & (Wrapping(PRIME_X) << 1)),
+                yrbp + (Wrapping(yNMask as i64) & (Wrapping(PRIME_Y) << 1)),
+                zrbp + Wrapping(PRIME_Z),
+                xD,
+                yD,
+                zD,
+            );
+        }
+    }
+
+    (value, derivatives)
+}
+
+/**
+    4D SuperSimplex noise base, with derivatives with respect to (xs, ys, zs, ws).
+*/
+fn noise4_UnskewedBase_Derivatives(
+    staticData: &StaticData,
+    seed: i64,
+    xs: f64,
+    ys: f64,
+    zs: f64,
+    ws: f64,
+) -> (f32, [f32; 4]) {
+    let seed = Wrapping(seed);
+
+    // Get base points and offsets
+    let xsb = fastFloor(xs);
+    let ysb = fastFloor(ys);
+    let zsb = fastFloor(zs);
+    let wsb = fastFloor(ws);
+    let xsi = (xs - xsb as f64) as f32;
+    let ysi = (ys - ysb as f64) as f32;
+    let zsi = (zs - zsb as f64) as f32;
+    let wsi = (ws - wsb as f64) as f32;
+
+    // Unskewed offsets
+    let ssi = (xsi + ysi + zsi + wsi) * UNSKEW_4D;
+    let xi = xsi + ssi;
+    let yi = ysi + ssi;
+    let zi = zsi + ssi;
+    let wi = wsi + ssi;
+
+    // Prime pre-multiplication for hash.
+    let xsvp = Wrapping(xsb as i64) * Wrapping(PRIME_X);
+    let ysvp = Wrapping(ysb as i64) * Wrapping(PRIME_Y);
+    let zsvp = Wrapping(zsb as i64) * Wrapping(PRIME_Z);
+    let wsvp = Wrapping(wsb as i64) * Wrapping(PRIME_W);
+
+    // Index into initial table.
+    let index = ((fastFloor(xs * 4.0) & 3) << 0)
+        | ((fastFloor(ys * 4.0) & 3) << 2)
+        | ((fastFloor(zs * 4.0) & 3) << 4)
+        | ((fastFloor(ws * 4.0) & 3) << 6);
+
+    // Point contributions
+    let mut derivatives = [0.0; 4];
+    let mut value = 0.0;
+    let secondaryIndexStartAndStop = staticData.lookup4DA[index as usize];
+    let secondaryIndexStart = secondaryIndexStartAndStop & 0xFFFF;
+    let secondaryIndexStop = secondaryIndexStartAndStop >> 16;
+    for i in secondaryIndexStart..secondaryIndexStop {
+        let c = &staticData.lookup4DB[i];
+        let dx = xi + c.dx;
+        let dy = yi + c.dy;
+        let dz = zi + c.dz;
+        let dw = wi + c.dw;
+        let a = (dx * dx + dy * dy) + (dz * dz + dw * dw);
+        if a < RSQUARED_4D {
+            value += contribution4(
+                staticData,
+                &mut derivatives,
+                RSQUARED_4D - a,
+                seed,
+                xsvp + Wrapping(c.xsvp),
+                ysvp + Wrapping(c.ysvp),
+                zsvp + Wrapping(c.zsvp),
+                wsvp + Wrapping(c.wsvp),
+                dx,
+                dy,
+                dz,
+                dw,
+            );
+        }
+    }
+    // Every offset is the unskewed base offset plus a constant.
+    let unskew =
+        ((derivatives[0] + derivatives[1]) + (derivatives[2] + derivatives[3])) * UNSKEW_4D;
+    (
+        value,
+        [
+            derivatives[0] + unskew,
+            derivatives[1] + unskew,
+            derivatives[2] + unskew,
+            derivatives[3] + unskew,
+        ],
+    )
+}
+
+/*
+    Periodic Evaluators
+
+    Tileable noise with an integer period per axis, so that
+    noise(x + period[0], y, ...) == noise(x, y, ...) and likewise for the other axes.
+*/
+
+/**
+    2D noise that repeats every period[0] units in X and every period[1] units in Y.
+    Each axis is wrapped onto a circle with that circumference, and the resulting
+    torus is sampled with noise4_ImproveXY_ImproveZW.
+*/
+pub fn noise2_Periodic(seed: i64, x: f64, y: f64, period: [u32; 2]) -> f32 {
+    checkPeriod(&period);
+    let [xc, xs] = torusCoordinates(x, period[0]);
+    let [yc, ys] = torusCoordinates(y, period[1]);
+
+    noise4_ImproveXY_ImproveZW(seed, xc, xs, yc, ys)
+}
+
+/**
+    3D OpenSimplex2S/SuperSimplex noise that repeats every period[i] units along each axis.
+    Both cubic lattices are hashed with their coordinates wrapped to the period.
+    The lattices can't be re-oriented without breaking the period, so this has
+    more axis-aligned bias than noise3_ImproveXY or noise3_ImproveXZ.
+*/
+pub fn noise3_Periodic(seed: i64, x: f64, y: f64, z: f64, period: [u32; 3]) -> f32 {
+    checkPeriod(&period);
+
+    noise3_PeriodicBase(getStaticData(), seed, x, y, z, period)
+}
+
+/**
+    Generate overlapping cubic lattices with wrapped hashes.
+    Every vertex of the cell around the point is checked on both lattice copies.
+*/
+fn noise3_PeriodicBase(
+    staticData: &StaticData,
+    seed: i64,
+    x: f64,
+    y: f64,
+    z: f64,
+    period: [u32; 3],
+) -> f32 {
+    let mut seed = Wrapping(seed);
+    let mut value = 0.0;
+
+    // The second copy is offset by half a unit; its vertex at i - 0.5 is hashed as i.
+    for offset in [0.0, 0.5] {
+        let xb = fastFloor(x + offset);
+        let yb = fastFloor(y + offset);
+        let zb = fastFloor(z + offset);
+        let xi = (x + offset - xb as f64) as f32;
+        let yi = (y + offset - yb as f64) as f32;
+        let zi = (z + offset - zb as f64) as f32;
+
+        for vertex in 0..8 {
+            let (cx, cy, cz) = (vertex & 1, (vertex >> 1) & 1, vertex >> 2);
+            let dx = xi - cx as f32;
+            let dy = yi - cy as f32;
+            let dz = zi - cz as f32;
+            let a = RSQUARED_3D - dx * dx - dy * dy - dz * dz;
+            if a > 0.0 {
+                value += (a * a)
+                    * (a * a)
+                    * grad3(
+                        staticData,
+                        seed,
+                        wrappedPrime(xb + cx, period[0], PRIME_X),
+                        wrappedPrime(yb + cy, period[1], PRIME_Y),
+                        wrappedPrime(zb + cz, period[2], PRIME_Z),
+                        dx,
+                        dy,
+                        dz,
+                    );
+            }
+        }
+
+        seed ^= SEED_FLIP_3D;
+    }
+
+    value
+}
+
+/*
+    Utility
+*/
+
+fn grad2Cached(
+    cache: &mut impl LatticeCache<2>,
+    slot: usize,
+    staticData: &StaticData,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+) -> f32 {
+    let g = cache.gradient(slot, || gradVector2(staticData, seed, xsvp, ysvp));
+    g[0] * dx + g[1] * dy
+}
+
+fn grad3Cached(
+    cache: &mut impl LatticeCache<3>,
+    slot: usize,
+    staticData: &StaticData,
+    seed: Wrapping<i64>,
+    xrvp: Wrapping<i64>,
+    yrvp: Wrapping<i64>,
+    zrvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+) -> f32 {
+    let g = cache.gradient(slot, || gradVector3(staticData, seed, xrvp, yrvp, zrvp));
+    g[0] * dx + g[1] * dy + g[2] * dz
+}
+
+fn grad3(
+    staticData: &StaticData,
+    seed: Wrapping<i64>,
+    xrvp: Wrapping<i64>,
+    yrvp: Wrapping<i64>,
+    zrvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+) -> f32 {
+    let g = gradVector3(staticData, seed, xrvp, yrvp, zrvp);
+    g[0] * dx + g[1] * dy + g[2] * dz
+}
+
+fn grad4(
+    staticData: &StaticData,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    zsvp: Wrapping<i64>,
+    wsvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+    dw: f32,
+) -> f32 {
+    let g = gradVector4(staticData, seed, xsvp, ysvp, zsvp, wsvp);
+    (g[0] * dx + g[1] * dy) + (g[2] * dz + g[3] * dw)
+}
+
+fn gradVector2(
+    staticData: &StaticData,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+) -> [f32; 2] {
+    let mut hash = seed ^ xsvp ^ ysvp;
+    hash *= HASH_MULTIPLIER;
+    hash ^= hash.0 >> (64 - N_GRADS_2D_EXPONENT + 1);
+    let gi = (hash.0 as i32 & ((N_GRADS_2D - 1) << 1)) as usize;
+    let grads = &staticData.gradients2D;
+    [grads[gi | 0], grads[gi | 1]]
+}
+
+fn gradVector3(
+    staticData: &StaticData,
+    seed: Wrapping<i64>,
+    xrvp: Wrapping<i64>,
+    yrvp: Wrapping<i64>,
+    zrvp: Wrapping<i64>,
+) -> [f32; 3] {
+    let mut hash = (seed ^ xrvp) ^ (yrvp ^ zrvp);
+    hash *= HASH_MULTIPLIER;
+    hash ^= hash.0 >> (64 - N_GRADS_3D_EXPONENT + 2);
+    let gi = (hash.0 as i32 & ((N_GRADS_3D - 1) << 2)) as usize;
+    let grads = &staticData.gradients3D;
+    [grads[gi | 0], grads[gi | 1], grads[gi | 2]]
+}
+
+fn gradVector4(
+    staticData: &StaticData,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    zsvp: Wrapping<i64>,
+    wsvp: Wrapping<i64>,
+) -> [f32; 4] {
+    let mut hash = seed ^ (xsvp ^ ysvp) ^ (zsvp ^ wsvp);
+    hash *= HASH_MULTIPLIER;
+    hash ^= hash.0 >> (64 - N_GRADS_4D_EXPONENT + 2);
+    let gi = (hash.0 as i32 & ((N_GRADS_4D - 1) << 2)) as usize;
+    let grads = &staticData.gradients4D;
+    [grads[gi | 0], grads[gi | 1], grads[gi | 2], grads[gi | 3]]
+}
+
+/**
+    Vertex contribution a^4 * <g, d>. Adds its derivative with respect to the
+    offset d, a^4 * g - 8 * a^3 * <g, d> * d, to `derivatives`.
+*/
+fn contribution2(
+    staticData: &StaticData,
+    derivatives: &mut [f32; 2],
+    a: f32,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+) -> f32 {
+    let g = gradVector2(staticData, seed, xsvp, ysvp);
+    let dot = g[0] * dx + g[1] * dy;
+    falloffDerivatives(derivatives, a, dot, g, [dx, dy])
+}
+
+fn contribution3(
+    staticData: &StaticData,
+    derivatives: &mut [f32; 3],
+    a: f32,
+    seed: Wrapping<i64>,
+    xrvp: Wrapping<i64>,
+    yrvp: Wrapping<i64>,
+    zrvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+) -> f32 {
+    let g = gradVector3(staticData, seed, xrvp, yrvp, zrvp);
+    let dot = g[0] * dx + g[1] * dy + g[2] * dz;
+    falloffDerivatives(derivatives, a, dot, g, [dx, dy, dz])
+}
+
+fn contribution4(
+    staticData: &StaticData,
+    derivatives: &mut [f32; 4],
+    a: f32,
+    seed: Wrapping<i64>,
+    xsvp: Wrapping<i64>,
+    ysvp: Wrapping<i64>,
+    zsvp: Wrapping<i64>,
+    wsvp: Wrapping<i64>,
+    dx: f32,
+    dy: f32,
+    dz: f32,
+    dw: f32,
+) -> f32 {
+    let g = gradVector4(staticData, seed, xsvp, ysvp, zsvp, wsvp);
+    let dot = (g[0] * dx + g[1] * dy) + (g[2] * dz + g[3] * dw);
+    falloffDerivatives(derivatives, a, dot, g, [dx, dy, dz, dw])
+}
+
+fn falloffDerivatives<const D: usize>(
+    derivatives: &mut [f32; D],
+    a: f32,
+    dot: f32,
+    g: [f32; D],
+    d: [f32; D],
+) -> f32 {
+    let aa = a * a;
+    let slope = -8.0 * aa * a * dot;
+    for ((derivative, g), d) in derivatives.iter_mut().zip(g).zip(d) {
+        *derivative += (aa * aa) * g + slope * d;
+    }
+    (aa * aa) * dot
+}
+
+fn select<T>(condition: bool, a: T, b: T) -> T {
+    if condition {
+        a
+    } else {
+        b
+    }
+}
+
+fn checkPeriod(period: &[u32]) {
+    assert!(period.iter().all(|&p| p > 0), "period must be positive");
+}
+
+/**
+    Point on a circle with circumference `period`, so that the distance
+    travelled along the circle matches the distance travelled along the axis.
+*/
+fn torusCoordinates(t: f64, period: u32) -> [f64; 2] {
+    let radius = period as f64 / std::f64::consts::TAU;
+    let angle = t / radius;
+    [radius * angle.cos(), radius * angle.sin()]
+}
+
+fn wrappedPrime(i: i32, period: u32, prime: i64) -> Wrapping<i64> {
+    Wrapping((i as i64).rem_euclid(period as i64)) * Wrapping(prime)
+}
+
+/**
+    Where the 2D and 3D bases get their vertex gradients. A base calls `enter` with its lattice
+    cell once per sample, then asks for each vertex by a slot number that names the same vertex
+    for every sample in that cell.
+*/
+trait LatticeCache<const D: usize> {
+    fn enter(&mut self, cell: [i32; 4]);
+    fn gradient(&mut self, slot: usize, hash: impl FnOnce() -> [f32; D]) -> [f32; D];
+}
+
+/**
+    Hashes every vertex, for the single-point functions.
+*/
+struct NoCache;
+
+impl<const D: usize> LatticeCache<D> for NoCache {
+    fn enter(&mut self, _cell: [i32; 4]) {}
+
+    fn gradient(&mut self, _slot: usize, hash: impl FnOnce() -> [f32; D]) -> [f32; D] {
+        hash()
+    }
+}
+
+/**
+    Keeps the gradients of the current cell, so grid samples that land in the same cell as the
+    previous one only hash the vertices it didn't use.
+*/
+struct CellCache<const D: usize> {
+    cell: [i32; 4],
+    filled: u32,
+    gradients: [[f32; D]; 16],
+}
+
+impl<const D: usize> CellCache<D> {
+    fn new() -> Self {
+        CellCache {
+            cell: [0; 4],
+            filled: 0,
+            gradients: [[0.0; D]; 16],
+        }
+    }
+}
+
+impl<const D: usize> LatticeCache<D> for CellCache<D> {
+    fn enter(&mut self, cell: [i32; 4]) {
+        if cell != self.cell {
+            self.cell = cell;
+            self.filled = 0;
+        }
+    }
+
+    fn gradient(&mut self, slot: usize, hash: impl FnOnce() -> [f32; D]) -> [f32; D] {
+        if self.filled & (1 << slot) == 0 {
+            self.gradients[slot] = hash();
+            self.filled |= 1 << slot;
+        }
+        self.gradients[slot]
+    }
+}
+
+fn gridAxis(origin: f64, step: f64, count: usize) -> Vec<f64> {
+    (0..count).map(|i| origin + i as f64 * step).collect()
+}
+
+fn checkGridLength(count: usize, out: &[f32]) {
+    assert_eq!(
+        out.len(),
+        count,
+        "output buffer length must match the grid size"
+    );
+}
+
+fn fastFloor(x: f64) -> i32 {
+    let xi = x as i32;
+    if x < xi as f64 {
+        xi - 1
+    } else {
+        xi
+    }
+}
+
+/*
+    Lookup Tables & Gradients
+*/
+
+#[derive(Clone, Default)]
+struct LatticeVertex4D {
+    pub dx: f32,
+    pub dy: f32,
+    pub dz: f32,
+    pub dw: f32,
+    pub xsvp: i64,
+    pub ysvp: i64,
+    pub zsvp: i64,
+    pub wsvp: i64,
+}
+
+impl LatticeVertex4D {
+    pub fn new(xsv: i32, ysv: i32, zsv: i32, wsv: i32) -> Self {
+        let ssv = (xsv + ysv + zsv + wsv) as f32 * UNSKEW_4D;
+        Self {
+            xsvp: (Wrapping(xsv as i64) * Wrapping(PRIME_X)).0,
+            ysvp: (Wrapping(ysv as i64) * Wrapping(PRIME_Y)).0,
+            zsvp: (Wrapping(zsv as i64) * Wrapping(PRIME_Z)).0,
+            wsvp: (Wrapping(wsv as i64) * Wrapping(PRIME_W)).0,
+            dx: -xsv as f32 - ssv,
+            dy: -ysv as f32 - ssv,
+            dz: -zsv as f32 - ssv,
+            dw: -wsv as f32 - ssv,
+        }
+    }
+}
+
+struct StaticData {
+    gradients2D: Vec<f32>,
+    gradients3D: Vec<f32>,
+    gradients4D: Vec<f32>,
+
+    lookup4DA: Vec<usize>,
+    lookup4DB: Vec<LatticeVertex4D>,
+}
+
+static STATIC_DATA: OnceLock<StaticData> = OnceLock::new();
+
+fn getStaticData() -> &'static StaticData {
+    STATIC_DATA.get_or_init(initStaticData)
+}
+
+/**
+    Builds the gradient and 4D lattice lookup tables now, so the first noise call doesn't pay for it.
+    Calling it again, or from several threads at once, is harmless.
+*/
+pub fn warm_up() {
+    getStaticData();
+}
+
+fn initStaticData() -> StaticData {
+    let gradients2D: Vec<_> = GRAD2_SRC
+        .iter()
+        .map(|v| (v / NORMALIZER_2D) as f32)
+        .collect::<Vec<_>>() // cache divisions
+        .into_iter()
+        .cycle()
+        .take((N_GRADS_2D * 2) as usize)
+        .collect();
+
+    let gradients3D: Vec<_> = GRAD3_SRC
+        .iter()
+        .map(|v| (v / NORMALIZER_3D) as f32)
+        .collect::<Vec<_>>() // cache divisions
+        .into_iter()
+        .cycle()
+        .take((N_GRADS_3D * 4) as usize)
+        .collect();
+
+    let gradients4D: Vec<_> = GRAD4_SRC
+        .iter()
         .map(|v| (v / NORMALIZER_4D) as f32)
         .collect::<Vec<_>>() // cache divisions
         .into_iter()
@@ -1,11 +1,14 @@
 use crate::configuration::ConfigurationMessage;
+use crate::terrain_worker::TerrainStatus;
 use crate::routes::{
     camera_change_far_z, camera_change_field_view_y, camera_change_position_x,
     camera_change_position_y, camera_change_position_z, camera_change_target_x,
     camera_change_target_y, camera_change_target_z, camera_change_up_x, camera_change_up_y,
-    camera_change_up_z, health_check, terrain_change_color, terrain_change_depth,
-    terrain_change_failoff, terrain_change_fractal_amplitude, terrain_change_fractal_frequency, terrain_change_fractal_octaves,
-    terrain_change_max_height, terrain_change_cube_size, terrain_change_seed, terrain_change_width, terrain_change_z,
+    camera_change_up_z, health_check, light_change_ambient_intensity, light_change_color,
+    light_change_direction_x, light_change_direction_y, light_change_direction_z,
+    light_change_intensity, terrain_change_color, terrain_change_color_ramp, terrain_change_depth,
+    terrain_change_failoff, terrain_change_fractal_amplitude, terrain_change_fractal_frequency, terrain_change_fractal_octaves, terrain_change_fractal_type, terrain_change_graph,
+    terrain_change_max_height, terrain_change_mode, terrain_change_noise_algorithm, terrain_change_noise_orientation, terrain_change_cube_size, terrain_change_seed, terrain_change_skirts, terrain_change_threads, terrain_change_volume_cave_frequency, terrain_change_volume_threshold, terrain_change_volume_vertical_bias, terrain_change_warp_frequency, terrain_change_water_color, terrain_change_water_level, terrain_change_water_opacity, terrain_change_water_seabed_color, terrain_stats, terrain_status, terrain_change_warp_levels, terrain_change_warp_seed, terrain_change_warp_strength, terrain_change_width, terrain_change_z,
 };
 use actix_cors::Cors;
 use actix_web::dev::Server;
@@ -13,33 +16,45 @@ use actix_web::web::ServiceConfig;
 use actix_web::{web, App, HttpServer};
 use std::net::TcpListener;
 use std::sync::mpsc::Sender;
+use std::sync::Arc;
 
 const PORT: i32 = 8090;
 
-pub async fn start_server(tx: Sender<ConfigurationMessage>) -> std::io::Result<()> {
+pub async fn start_server(
+    tx: Sender<ConfigurationMessage>,
+    status: Arc<TerrainStatus>,
+) -> std::io::Result<()> {
     println!("Tokio running on port {}", PORT);
     let address = format!("0.0.0.0:{}", PORT);
     let listener = TcpListener::bind(address).expect("Failed to bind port");
-    run(listener, tx)?.await
+    run(listener, tx, status)?.await
 }
 
-fn run(listener: TcpListener, tx: Sender<ConfigurationMessage>) -> Result<Server, std::io::Error> {
+fn run(
+    listener: TcpListener,
+    tx: Sender<ConfigurationMessage>,
+    status: Arc<TerrainStatus>,
+) -> Result<Server, std::io::Error> {
     let server = HttpServer::new(move || {
         let cors = Cors::default()
             .allow_any_origin()
             .allow_any_method()
             .allow_any_header()
             .max_age(3600);
-        App::new().wrap(cors).configure(config_app(tx.clone()))
+        App::new().wrap(cors).configure(config_app(tx.clone(), status.clone()))
     })
     .listen(listener)?
     .run();
     Ok(server)
 }
 
-fn config_app(tx: Sender<ConfigurationMessage>) -> Box<dyn Fn(&mut ServiceConfig)> {
+fn config_app(
+    tx: Sender<ConfigurationMessage>,
+    status: Arc<TerrainStatus>,
+) -> Box<dyn Fn(&mut ServiceConfig)> {
     Box::new(move |cfg: &mut ServiceConfig| {
         cfg.app_data(web::Data::new(tx.clone()))
+            .app_data(web::Data::from(status.clone()))
             .service(web::resource("/api/health_check").route(web::get().to(health_check)))
             .service(
                 web::resource("/api/terrain/width")
@@ -80,6 +95,78 @@ fn config_app(tx: Sender<ConfigurationMessage>) -> Box<dyn Fn(&mut ServiceConfig
                 web::resource("/api/terrain/fractal/frequency")
                     .route(web::put().to(terrain_change_fractal_frequency)),
             )
+            .service(
+                web::resource("/api/terrain/fractal/type")
+                    .route(web::put().to(terrain_change_fractal_type)),
+            )
+            .service(
+                web::resource("/api/terrain/warp/levels")
+                    .route(web::put().to(terrain_change_warp_levels)),
+            )
+            .service(
+                web::resource("/api/terrain/warp/seed")
+                    .route(web::put().to(terrain_change_warp_seed)),
+            )
+            .service(
+                web::resource("/api/terrain/warp/strength")
+                    .route(web::put().to(terrain_change_warp_strength)),
+            )
+            .service(
+                web::resource("/api/terrain/warp/frequency")
+                    .route(web::put().to(terrain_change_warp_frequency)),
+            )
+            .service(
+                web::resource("/api/terrain/noise/algorithm")
+                    .route(web::put().to(terrain_change_noise_algorithm)),
+            )
+            .service(
+                web::resource("/api/terrain/noise/orientation")
+                    .route(web::put().to(terrain_change_noise_orientation)),
+            )
+            .service(
+                web::resource("/api/terrain/graph").route(web::put().to(terrain_change_graph)),
+            )
+            .service(
+                web::resource("/api/terrain/threads").route(web::put().to(terrain_change_threads)),
+            )
+            .service(web::resource("/api/terrain/status").route(web::get().to(terrain_status)))
+            .service(web::resource("/api/terrain/stats").route(web::get().to(terrain_stats)))
+            .service(web::resource("/api/terrain/mode").route(web::put().to(terrain_change_mode)))
+            .service(
+                web::resource("/api/terrain/skirts").route(web::put().to(terrain_change_skirts)),
+            )
+            .service(
+                web::resource("/api/terrain/colorramp")
+                    .route(web::put().to(terrain_change_color_ramp)),
+            )
+            .service(
+                web::resource("/api/terrain/volume/threshold")
+                    .route(web::put().to(terrain_change_volume_threshold)),
+            )
+            .service(
+                web::resource("/api/terrain/volume/cavefrequency")
+                    .route(web::put().to(terrain_change_volume_cave_frequency)),
+            )
+            .service(
+                web::resource("/api/terrain/volume/verticalbias")
+                    .route(web::put().to(terrain_change_volume_vertical_bias)),
+            )
+            .service(
+                web::resource("/api/terrain/water/level")
+                    .route(web::put().to(terrain_change_water_level)),
+            )
+            .service(
+                web::resource("/api/terrain/water/color")
+                    .route(web::put().to(terrain_change_water_color)),
+            )
+            .service(
+                web::resource("/api/terrain/water/opacity")
+                    .route(web::put().to(terrain_change_water_opacity)),
+            )
+            .service(
+                web::resource("/api/terrain/water/seabedcolor")
+                    .route(web::put().to(terrain_change_water_seabed_color)),
+            )
             .service(
                 web::resource("/api/camera/position/x")
                     .route(web::put().to(camera_change_position_x)),
@@ -115,6 +202,26 @@ fn config_app(tx: Sender<ConfigurationMessage>) -> Box<dyn Fn(&mut ServiceConfig
             .service(web::resource("/api/camera/up/y").route(web::put().to(camera_change_up_y)))
             .service(
                 web::resource("/api/camera/up/z").route(web::put().to(camera_change_up_z)),
+            )
+            .service(
+                web::resource("/api/light/direction/x")
+                    .route(web::put().to(light_change_direction_x)),
+            )
+            .service(
+                web::resource("/api/light/direction/y")
+                    .route(web::put().to(light_change_direction_y)),
+            )
+            .service(
+                web::resource("/api/light/direction/z")
+                    .route(web::put().to(light_change_direction_z)),
+            )
+            .service(web::resource("/api/light/color").route(web::put().to(light_change_color)))
+            .service(
+                web::resource("/api/light/intensity").route(web::put().to(light_change_intensity)),
+            )
+            .service(
+                web::resource("/api/light/ambient/intensity")
+                    .route(web::put().to(light_change_ambient_intensity)),
             );
     })
 }
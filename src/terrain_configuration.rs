@@ -1,12 +1,27 @@
+use crate::color_ramp::{ColorRamp, Palette};
 use crate::configuration::ConfigurationMessage;
-use crate::smooth::noise3_ImproveXZ;
+use crate::domain_warp::DomainWarp;
+use crate::fractal::{fractal, FractalType};
+use crate::heightmap::{column_count, positions, Heightmap};
+use crate::noise_graph::NoiseModule;
+use crate::noise_source::{Algorithm, NoiseSource};
+use crate::opensimplex2::OpenSimplex2;
+use crate::progress::Progress;
+use crate::heightfield_mesh::{self, heightfield_mesh};
+use crate::mesh_mode::MeshMode;
+use crate::volume::{DensityGrid, Volume};
+use crate::volume_mesh::{self, volume_mesh};
+use crate::voxel_mesh::{self, column_top, greedy_voxel_mesh};
+use crate::water::{water_mesh, TerrainStats, Water};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::Arc;
 use three_d::*;
 
 #[derive(Debug, Clone)]
 pub struct TerrainConfiguration {
     tot_width: f32,
     tot_depth: f32,
-    seed: i64,
     cube_size: f32,
     color: String,
     max_height: f32,
@@ -15,9 +30,35 @@ pub struct TerrainConfiguration {
     fractal_octaves: i32,
     fractal_amplitude: f32,
     fractal_frequency: f64,
+    fractal_type: FractalType,
+    warp: DomainWarp,
+    // The terrain layer, seeded with the configured seed. The warp and graph leaves sample
+    // copies of it under their own seeds
+    noise: OpenSimplex2,
+    graph: Option<NoiseModule>,
+    // Worker threads for generation and meshing, built when the thread count changes and shared
+    // by clones. None for rayon's global pool, one thread per core
+    pool: Option<Arc<ThreadPool>>,
+    mode: MeshMode,
+    // Heightfield mode only: walls from the border down to the ground
+    skirts: bool,
+    // Volume mode only: density carving caves and overhangs out of the heightmap
+    volume: Volume,
+    // Replaces the shaded base color when set
+    color_ramp: Option<ColorRamp>,
+    water: Water,
 }
 
 impl TerrainConfiguration {
+    pub const MAX_THREADS: usize = 256;
+    // Limits of check_terrain_limits. Columns along each axis stay well below where f32
+    // accumulation of the cube size stops advancing
+    pub const MAX_COLUMNS: f32 = 65536.0;
+    pub const MAX_CELLS: f32 = 16777216.0;
+    pub const MAX_LEVELS: f32 = 4096.0;
+    pub const MAX_OCTAVES: i32 = 64;
+
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         tot_width: f32,
         tot_depth: f32,
@@ -34,7 +75,6 @@ impl TerrainConfiguration {
         Self {
             tot_width,
             tot_depth,
-            seed,
             cube_size,
             color,
             max_height,
@@ -43,33 +83,36 @@ impl TerrainConfiguration {
             fractal_octaves,
             fractal_amplitude,
             fractal_frequency,
+            fractal_type: FractalType::default(),
+            warp: DomainWarp::default(),
+            noise: OpenSimplex2::new(Algorithm::Smooth, seed),
+            graph: None,
+            pool: None,
+            mode: MeshMode::default(),
+            skirts: true,
+            volume: Volume::default(),
+            color_ramp: None,
+            water: Water::default(),
         }
     }
 }
 
-fn fractal_noise(
+fn fractal_noise<N: NoiseSource>(
+    noise: &N,
     terrain_configuration: &TerrainConfiguration,
-    width: f32,
-    depth: f32
+    width: f64,
+    depth: f64
 ) -> f32 {
-    let mut height: f32 = 0.0;
-    let mut amplitude: f32 = 1.0;
-    let mut frequency: f64 = 1.0;
-    let octaves: i32 = terrain_configuration.fractal_octaves;
-    for _i in 0..octaves {
-        height += noise3_ImproveXZ(
-            terrain_configuration.seed,
-            f64::from(width) * frequency,
-            f64::from(depth) * frequency,
-            terrain_configuration.z,
-        ) * amplitude;
-        amplitude *= terrain_configuration.fractal_amplitude;
-        frequency *= terrain_configuration.fractal_frequency;
-    }
-    let max_height = 2.0 * (1.0 - 0.5_f32.powi(octaves));
-
-    // Normalize to [0,1]
-    (height / max_height + 1.0) * 0.5
+    // Normalized to [0,1] for every fractal type
+    fractal(
+        noise,
+        terrain_configuration.fractal_type,
+        [width, depth, terrain_configuration.z],
+        terrain_configuration.fractal_octaves,
+        terrain_configuration.fractal_amplitude,
+        terrain_configuration.fractal_frequency,
+        false,
+    )
 }
 
 const CURVE: &[(f32, f32)] = &[
@@ -83,8 +126,8 @@ const CURVE: &[(f32, f32)] = &[
     (1.0, 1.0),
 ];
 
-fn piecewise_linear(x: f32) -> f32 {
-    for w in CURVE.windows(2) {
+pub(crate) fn piecewise_linear(curve: &[(f32, f32)], x: f32) -> f32 {
+    for w in curve.windows(2) {
         let (x0, y0) = w[0];
         let (x1, y1) = w[1];
 
@@ -92,7 +135,7 @@ fn piecewise_linear(x: f32) -> f32 {
             return y0 + (x - x0) * (y1 - y0) / (x1 - x0);
         }
     }
-    CURVE.last().unwrap().1
+    curve.last().unwrap().1
 }
 
 fn adjust_height(terrain_configuration: &TerrainConfiguration, height: f32) -> f32 {
@@ -102,183 +145,487 @@ fn adjust_height(terrain_configuration: &TerrainConfiguration, height: f32) -> f
 pub fn configure_terrain(
     context: &Context,
     terrain_configuration: &TerrainConfiguration,
-) -> Gm<Mesh, ColorMaterial> {
-    let mut terrain: Vec<Vec<Cube>> = Vec::new();
-
-    let mut width: f32 = 0.0;
-    let mut depth: f32;
-
-    while width < terrain_configuration.tot_width {
-        let mut terrain_layer: Vec<Cube> = Vec::new();
-        depth = 0.0;
-        while depth < terrain_configuration.tot_depth {
-            let value = fractal_noise(terrain_configuration, width, depth);
-            let value_piecewise = piecewise_linear(value);
-            let stretch_value = adjust_height(terrain_configuration, value_piecewise);
-            let dist = (width * width + depth * depth).sqrt();
-            let falloff = (1.0 - (dist / terrain_configuration.failoff)).max(0.0);
-            let cube = Cube {
-                x: width,
-                y: depth,
-                z: stretch_value * falloff + terrain_configuration.cube_size,
-            };
-            terrain_layer.push(cube);
-            depth += terrain_configuration.cube_size;
-        }
-        terrain.push(terrain_layer);
-        width += terrain_configuration.cube_size;
-    }
+) -> Gm<Mesh, PhysicalMaterial> {
+    configure_terrain_with_noise(context, terrain_configuration, &terrain_configuration.noise)
+}
 
-    let cpu_mesh = cubes_to_voxel_mesh(&terrain, terrain_configuration);
+pub fn configure_terrain_with_noise<N: NoiseSource + Sync>(
+    context: &Context,
+    terrain_configuration: &TerrainConfiguration,
+    noise: &N,
+) -> Gm<Mesh, PhysicalMaterial> {
+    let heightmap = generate_heightmap_with_noise(terrain_configuration, noise);
+    let (cpu_mesh, _) = mesh_rows(&heightmap, terrain_configuration, noise, &Progress::new())
+        .expect("never cancelled");
+
+    upload_terrain(context, &cpu_mesh)
+}
 
+/**
+    Uploads a mesh built by `terrain_mesh` or `build_terrain`. The material is a rough,
+    non-metallic white lit with Cook-Torrance, so the vertex colors are the terrain's albedo.
+*/
+pub fn upload_terrain(context: &Context, cpu_mesh: &CpuMesh) -> Gm<Mesh, PhysicalMaterial> {
     Gm::new(
-        Mesh::new(&context, &cpu_mesh),
-        ColorMaterial {
+        Mesh::new(context, cpu_mesh),
+        PhysicalMaterial {
+            metallic: 0.0,
+            roughness: 0.9,
+            lighting_model: LightingModel::Cook(
+                NormalDistributionFunction::TrowbridgeReitzGGX,
+                GeometryFunction::SmithSchlickGGX,
+            ),
             ..Default::default()
         },
     )
 }
 
-#[derive(Debug)]
-struct Cube {
-    x: f32,
-    y: f32,
-    z: f32,
+/**
+    The configured water surface over the terrain, or `None` when there is no water. Drawn
+    translucent and after the terrain, so the seabed shows through it.
+*/
+pub fn configure_water(
+    context: &Context,
+    terrain_configuration: &TerrainConfiguration,
+) -> Option<Gm<Mesh, PhysicalMaterial>> {
+    let cpu_mesh = water_surface(terrain_configuration)?;
+    let [color_r, color_g, color_b] = rgb(&terrain_configuration.water.color);
+    let alpha = (terrain_configuration.water.opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    Some(Gm::new(
+        Mesh::new(context, &cpu_mesh),
+        PhysicalMaterial::new_transparent(
+            context,
+            &CpuMaterial {
+                albedo: Srgba::new(color_r, color_g, color_b, alpha),
+                metallic: 0.0,
+                roughness: 0.1,
+                lighting_model: LightingModel::Cook(
+                    NormalDistributionFunction::TrowbridgeReitzGGX,
+                    GeometryFunction::SmithSchlickGGX,
+                ),
+                ..Default::default()
+            },
+        ),
+    ))
 }
 
-fn add_cube(positions: &mut Vec<Vec3>, indices: &mut Vec<u32>, base: Vec3, size: f32, height: f32) {
-    let start = positions.len() as u32;
-
-    let p0 = base;
-    let p1 = base + vec3(size, 0.0, 0.0);
-    let p2 = base + vec3(size, height, 0.0);
-    let p3 = base + vec3(0.0, height, 0.0);
-
-    let p4 = base + vec3(0.0, 0.0, size);
-    let p5 = base + vec3(size, 0.0, size);
-    let p6 = base + vec3(size, height, size);
-    let p7 = base + vec3(0.0, height, size);
-
-    // 8 cube corners
-    positions.extend([
-        p0, p1, p2, p3, // front
-        p4, p5, p6, p7, // back
-    ]);
-
-    // 12 triangles (2 per face)
-    indices.extend([
-        // Front
-        start,
-        start + 1,
-        start + 2,
-        start,
-        start + 2,
-        start + 3,
-        // Back
-        start + 4,
-        start + 6,
-        start + 5,
-        start + 4,
-        start + 7,
-        start + 6,
-        // Left
-        start + 4,
-        start,
-        start + 3,
-        start + 4,
-        start + 3,
-        start + 7,
-        // Right
-        start + 1,
-        start + 5,
-        start + 6,
-        start + 1,
-        start + 6,
-        start + 2,
-        // Top
-        start + 3,
-        start + 2,
-        start + 6,
-        start + 3,
-        start + 6,
-        start + 7,
-        // Bottom
-        start + 4,
-        start + 5,
-        start + 1,
-        start + 4,
-        start + 1,
-        start,
-    ]);
+/**
+    Water surface at the configured sea level, covering the terrain mesh of the configured mode,
+    or `None` when there is no water.
+*/
+pub fn water_surface(terrain_configuration: &TerrainConfiguration) -> Option<CpuMesh> {
+    if !terrain_configuration.water.is_visible() {
+        return None;
+    }
+    let (width, depth) = terrain_size(terrain_configuration);
+    let cube_size = terrain_configuration.cube_size;
+    // Cubes cover their whole column, the smooth surfaces end at the last column origin
+    let extent = |columns: usize| match terrain_configuration.mode {
+        MeshMode::Voxels => columns as f32 * cube_size,
+        MeshMode::Heightfield | MeshMode::Volume => columns.saturating_sub(1) as f32 * cube_size,
+    };
+    Some(water_mesh(extent(width), extent(depth), terrain_configuration.water.level))
 }
 
-fn cubes_to_voxel_mesh(
-    cubes: &Vec<Vec<Cube>>,
+/**
+    Land share and coastline of `heightmap`, a terrain generated from the same configuration,
+    at the configured sea level, for the terrain as drawn in the configured mode.
+*/
+pub fn terrain_stats(
+    heightmap: &Heightmap,
     terrain_configuration: &TerrainConfiguration,
-) -> CpuMesh {
-    let mut positions = Vec::new();
-    let mut indices = Vec::new();
-    let mut colors: Vec<Srgba> = Vec::new();
-
-    let color_r = u8::from_str_radix(&terrain_configuration.color[0..2], 16).unwrap();
-    let color_g = u8::from_str_radix(&terrain_configuration.color[2..4], 16).unwrap();
-    let color_b = u8::from_str_radix(&terrain_configuration.color[4..6], 16).unwrap();
-
-    let base_color_red = color_r;
-    let base_color_green = color_g;
-    let base_color_blue = color_b;
-
-    for row in cubes {
-        for cube in row {
-            let height_trunc = cube.z / terrain_configuration.cube_size;
-            let fractional_part = cube.z % terrain_configuration.cube_size;
-
-            let top_level = height_trunc.floor() as i32;
-
-            // stack from ground (0) up to cube.z
-            let mut height: f32 = 0.0;
-            for level in 1..top_level {
-                let base = vec3(cube.x, height, cube.y);
-
-                add_cube(&mut positions, &mut indices, base, terrain_configuration.cube_size, terrain_configuration.cube_size);
-
-                // darker color at bottom, lighter at top
-                for _ in 0..8 {
-                    let t = cube.z - ((top_level as f32 - level as f32 + 2.0) as f32 / top_level as f32 * 0.5);
-                    let red = (base_color_red as f32 + 0.25 + (0.45 * t) * 50.0) as u8;
-                    let green = (base_color_green as f32 + 0.25 + (0.45 * t) * 50.0) as u8;
-                    let blue = (base_color_blue as f32 + 0.25 + (0.45 * t) * 50.0) as u8;
-                    colors.push(Srgba::new(red, green, blue, 255));
-                }
-                height += terrain_configuration.cube_size;
-            }
+) -> TerrainStats {
+    TerrainStats::new(
+        &terrain_surface(heightmap, terrain_configuration),
+        terrain_configuration.water.level,
+    )
+}
+
+/**
+    Height of the drawn terrain at every column of `heightmap`, a terrain generated from the
+    same configuration: the top of the cube stack, the heightfield itself, or where the volume
+    last turns from solid to empty going up.
+*/
+pub fn terrain_surface(
+    heightmap: &Heightmap,
+    terrain_configuration: &TerrainConfiguration,
+) -> Heightmap {
+    surface_rows(heightmap, terrain_configuration, None)
+}
+
+// `grid` is the density of the volume, sampled again when not given
+fn surface_rows(
+    heightmap: &Heightmap,
+    terrain_configuration: &TerrainConfiguration,
+    grid: Option<&DensityGrid>,
+) -> Heightmap {
+    let (width, depth) = (heightmap.width, heightmap.depth);
+    let heights = match (terrain_configuration.mode, grid) {
+        (MeshMode::Voxels, _) => heightmap
+            .heights
+            .iter()
+            .map(|&height| column_top(height, heightmap.cell_size))
+            .collect(),
+        (MeshMode::Heightfield, _) => heightmap.heights.clone(),
+        (MeshMode::Volume, Some(grid)) => (0..depth)
+            .flat_map(|z| (0..width).map(move |x| grid.surface(x, z)))
+            .collect(),
+        (MeshMode::Volume, None) => {
+            let grid = generate_density(heightmap, terrain_configuration);
+            return surface_rows(heightmap, terrain_configuration, Some(&grid));
+        }
+    };
+    Heightmap::new(width, depth, heightmap.cell_size, heights, heightmap.seed)
+}
+
+/**
+    Heightmap and mesh of the configured terrain, counting finished rows in `progress`.
+    Returns `None` if `progress` is cancelled before the build completes.
+*/
+pub fn build_terrain(
+    terrain_configuration: &TerrainConfiguration,
+    progress: &Progress,
+) -> Option<(Heightmap, CpuMesh)> {
+    build_terrain_with_stats(terrain_configuration, progress)
+        .map(|(heightmap, cpu_mesh, _)| (heightmap, cpu_mesh))
+}
+
+/**
+    `build_terrain` and the `terrain_stats` of the result, taken from the density the volume
+    was meshed from rather than sampled again.
+*/
+pub(crate) fn build_terrain_with_stats(
+    terrain_configuration: &TerrainConfiguration,
+    progress: &Progress,
+) -> Option<(Heightmap, CpuMesh, TerrainStats)> {
+    let (width, depth) = terrain_size(terrain_configuration);
+    let mesh_steps = match terrain_configuration.mode {
+        MeshMode::Voxels => voxel_mesh::mesh_steps(width, depth),
+        MeshMode::Heightfield => heightfield_mesh::mesh_steps(width, depth),
+        // Densities are sampled one row of columns at a time before meshing
+        MeshMode::Volume => depth + volume_mesh::mesh_steps(volume_size(terrain_configuration)),
+    };
+    progress.set_total(depth + mesh_steps);
+
+    let noise = &terrain_configuration.noise;
+    in_pool(terrain_configuration, || {
+        let heightmap = heightmap_rows(terrain_configuration, noise, progress)?;
+        let (cpu_mesh, surface) = mesh_rows(&heightmap, terrain_configuration, noise, progress)?;
+        let stats = TerrainStats::new(&surface, terrain_configuration.water.level);
+        Some((heightmap, cpu_mesh, stats))
+    })
+}
+
+/**
+    Column heights from the configured noise. Needs no rendering context.
+*/
+pub fn generate_heightmap(terrain_configuration: &TerrainConfiguration) -> Heightmap {
+    generate_heightmap_with_noise(terrain_configuration, &terrain_configuration.noise)
+}
+
+/**
+    Column heights from `noise` in place of the configured noise. The heightmap records the
+    seed of `noise`.
+*/
+pub fn generate_heightmap_with_noise<N: NoiseSource + Sync>(
+    terrain_configuration: &TerrainConfiguration,
+    noise: &N,
+) -> Heightmap {
+    in_pool(terrain_configuration, || {
+        heightmap_rows(terrain_configuration, noise, &Progress::new())
+    })
+    .expect("never cancelled")
+}
 
-            let base = vec3(cube.x, height, cube.y);
-
-            add_cube(
-                &mut positions,
-                &mut indices,
-                base,
-                terrain_configuration.cube_size,
-                fractional_part,
-            );
-
-            for _ in 0..8 {
-                let t = cube.z - (2.0 as f32 / top_level as f32 * 0.5);
-                let red = (base_color_red as f32 + 0.25 + (0.45 * t) * 50.0) as u8;
-                let green = (base_color_green as f32 + 0.25 + (0.45 * t) * 50.0) as u8;
-                let blue = (base_color_blue as f32 + 0.25 + (0.45 * t) * 50.0) as u8;
-                colors.push(Srgba::new(red, green, blue, 255));
+fn heightmap_rows<N: NoiseSource + Sync>(
+    terrain_configuration: &TerrainConfiguration,
+    noise: &N,
+    progress: &Progress,
+) -> Option<Heightmap> {
+    let (width, depth) = terrain_size(terrain_configuration);
+    let xs = positions(width, terrain_configuration.cube_size);
+    let zs = positions(depth, terrain_configuration.cube_size);
+
+    // Every column is independent, so rows can be filled in any order without changing the result
+    let mut heights = vec![0.0; width * depth];
+    heights
+        .par_chunks_mut(width.max(1))
+        .zip(zs.par_iter())
+        .for_each(|(row, &depth)| {
+            if progress.is_cancelled() {
+                return;
+            }
+            for (height, &width) in row.iter_mut().zip(&xs) {
+                *height = column_height(terrain_configuration, noise, width, depth);
             }
+            progress.advance();
+        });
+    if progress.is_cancelled() {
+        return None;
+    }
+
+    Some(Heightmap::new(
+        width,
+        depth,
+        terrain_configuration.cube_size,
+        heights,
+        noise.seed(),
+    ))
+}
+
+fn column_height<N: NoiseSource>(
+    terrain_configuration: &TerrainConfiguration,
+    noise: &N,
+    width: f32,
+    depth: f32,
+) -> f32 {
+    let (warped_width, warped_depth) = terrain_configuration.warp.apply(
+        noise,
+        f64::from(width),
+        f64::from(depth),
+        terrain_configuration.z,
+    );
+    // A module graph replaces the fractal and curve, and is expected to output roughly [0,1]
+    let value_piecewise = match &terrain_configuration.graph {
+        Some(graph) => graph.get(
+            noise,
+            warped_width,
+            warped_depth,
+            terrain_configuration.z,
+        ),
+        None => {
+            let value = fractal_noise(noise, terrain_configuration, warped_width, warped_depth);
+            piecewise_linear(CURVE, value)
         }
+    };
+    let stretch_value = adjust_height(terrain_configuration, value_piecewise);
+    let dist = (width * width + depth * depth).sqrt();
+    let falloff = (1.0 - (dist / terrain_configuration.failoff)).max(0.0);
+    stretch_value * falloff + terrain_configuration.cube_size
+}
+
+/**
+    Checks that the terrain generates and meshes in bounded time and memory, describing the
+    first problem found: every setting finite, a positive cube size and falloff, at most
+    `MAX_COLUMNS` columns along each axis and `MAX_CELLS` in all, `max_height` at most
+    `MAX_LEVELS` cubes, and at most `MAX_OCTAVES` octaves. Run it before `terrain_size`, which
+    counts columns one by one.
+*/
+pub fn check_terrain_limits(terrain_configuration: &TerrainConfiguration) -> Result<(), String> {
+    let floats = [
+        ("tot_width", terrain_configuration.tot_width),
+        ("tot_depth", terrain_configuration.tot_depth),
+        ("cube_size", terrain_configuration.cube_size),
+        ("max_height", terrain_configuration.max_height),
+        ("failoff", terrain_configuration.failoff),
+        ("fractal_amplitude", terrain_configuration.fractal_amplitude),
+    ];
+    let doubles = [
+        ("z", terrain_configuration.z),
+        ("fractal_frequency", terrain_configuration.fractal_frequency),
+        ("warp_strength", terrain_configuration.warp.strength),
+        ("warp_frequency", terrain_configuration.warp.frequency),
+    ];
+    let values = floats.map(|(name, value)| (name, f64::from(value)));
+    if let Some((name, _)) = values.iter().chain(&doubles).find(|(_, value)| !value.is_finite()) {
+        return Err(format!("{} must be finite", name));
+    }
+    let cube_size = terrain_configuration.cube_size;
+    if cube_size <= 0.0 || terrain_configuration.failoff <= 0.0 {
+        return Err("cube_size and failoff must be positive".to_string());
     }
 
-    CpuMesh {
-        positions: Positions::F32(positions),
-        indices: Indices::U32(indices),
-        colors: Option::Some(colors),
-        ..Default::default()
+    let columns = [terrain_configuration.tot_width, terrain_configuration.tot_depth]
+        .map(|total| (total / cube_size).ceil().max(0.0));
+    if columns.iter().any(|&count| count > TerrainConfiguration::MAX_COLUMNS) {
+        return Err(format!(
+            "the terrain must be at most {} columns along each axis",
+            TerrainConfiguration::MAX_COLUMNS
+        ));
+    }
+    if columns[0] * columns[1] > TerrainConfiguration::MAX_CELLS {
+        return Err(format!(
+            "the terrain must be at most {} columns in all",
+            TerrainConfiguration::MAX_CELLS
+        ));
     }
+    if terrain_configuration.max_height.abs() / cube_size > TerrainConfiguration::MAX_LEVELS {
+        return Err(format!(
+            "max_height must be at most {} cubes",
+            TerrainConfiguration::MAX_LEVELS
+        ));
+    }
+    if terrain_configuration.fractal_octaves > TerrainConfiguration::MAX_OCTAVES {
+        return Err(format!(
+            "fractal_octaves must be at most {}",
+            TerrainConfiguration::MAX_OCTAVES
+        ));
+    }
+    Ok(())
+}
+
+/**
+    Number of columns along the width and depth.
+*/
+pub fn terrain_size(terrain_configuration: &TerrainConfiguration) -> (usize, usize) {
+    (
+        column_count(terrain_configuration.tot_width, terrain_configuration.cube_size),
+        column_count(terrain_configuration.tot_depth, terrain_configuration.cube_size),
+    )
+}
+
+/**
+    Number of density samples along the width, height and depth in volume mode. The top sample
+    is at least one cube above the highest possible column.
+*/
+pub fn volume_size(terrain_configuration: &TerrainConfiguration) -> [usize; 3] {
+    let (width, depth) = terrain_size(terrain_configuration);
+    let cube_size = terrain_configuration.cube_size;
+    let height = column_count(terrain_configuration.max_height + 2.0 * cube_size, cube_size) + 1;
+    [width, height, depth]
+}
+
+/**
+    Density of the configured volume around `heightmap`, a terrain generated from the same
+    configuration.
+*/
+pub fn generate_density(
+    heightmap: &Heightmap,
+    terrain_configuration: &TerrainConfiguration,
+) -> DensityGrid {
+    let noise = &terrain_configuration.noise;
+    in_pool(terrain_configuration, || {
+        density_rows(heightmap, terrain_configuration, noise, &Progress::new())
+    })
+    .expect("never cancelled")
+}
+
+fn density_rows<N: NoiseSource + Sync>(
+    heightmap: &Heightmap,
+    terrain_configuration: &TerrainConfiguration,
+    noise: &N,
+    progress: &Progress,
+) -> Option<DensityGrid> {
+    let [width, height, depth] = volume_size(terrain_configuration);
+    let step = terrain_configuration.cube_size;
+    let xs = positions(width, step);
+    let ys = positions(height, step);
+    let zs = positions(depth, step);
+
+    let mut values = vec![0.0; width * height * depth];
+    values
+        .par_chunks_mut((width * height).max(1))
+        .zip(zs.par_iter())
+        .enumerate()
+        .for_each(|(k, (slice, &z))| {
+            if progress.is_cancelled() {
+                return;
+            }
+            for (j, &y) in ys.iter().enumerate() {
+                for (i, &x) in xs.iter().enumerate() {
+                    slice[i + width * j] = terrain_configuration.volume.density(
+                        noise,
+                        heightmap.get(i, k),
+                        [x, y, z],
+                        terrain_configuration.z,
+                    );
+                }
+            }
+            progress.advance();
+        });
+    if progress.is_cancelled() {
+        return None;
+    }
+
+    Some(DensityGrid::new([width, height, depth], step, values))
+}
+
+/**
+    A pool of `threads` workers, or `None` for rayon's global pool (one per core) for 0.
+*/
+fn thread_pool(threads: usize) -> Option<Arc<ThreadPool>> {
+    if threads == 0 {
+        return None;
+    }
+    // No threads on this platform, the global pool runs jobs on the caller
+    ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .ok()
+        .map(Arc::new)
+}
+
+/**
+    Runs `job` on the configured pool. Installed once by each public entry point, never by the
+    row functions they share.
+*/
+fn in_pool<T: Send>(
+    terrain_configuration: &TerrainConfiguration,
+    job: impl FnOnce() -> T + Send,
+) -> T {
+    match &terrain_configuration.pool {
+        Some(pool) => pool.install(job),
+        None => job(),
+    }
+}
+
+/**
+    The mesh drawn by the viewer in the configured mode: stacked cubes with hidden faces culled
+    and coplanar faces merged, a continuous heightfield surface, or the surface of the volume
+    with its caves and overhangs.
+*/
+pub fn terrain_mesh(heightmap: &Heightmap, terrain_configuration: &TerrainConfiguration) -> CpuMesh {
+    in_pool(terrain_configuration, || {
+        mesh_rows(heightmap, terrain_configuration, &terrain_configuration.noise, &Progress::new())
+    })
+    .expect("never cancelled")
+    .0
+}
+
+fn mesh_rows<N: NoiseSource + Sync>(
+    heightmap: &Heightmap,
+    terrain_configuration: &TerrainConfiguration,
+    noise: &N,
+    progress: &Progress,
+) -> Option<(CpuMesh, Heightmap)> {
+    let color = rgb(&terrain_configuration.color);
+    // Ramps are validated by the API; an invalid one set from code falls back to the base color
+    let top = terrain_configuration.max_height + terrain_configuration.cube_size;
+    let palette = match &terrain_configuration.color_ramp {
+        Some(color_ramp) => Palette::ramp(color_ramp, top).unwrap_or(Palette::shaded(color)),
+        None => Palette::shaded(color),
+    };
+    // Without water the level is at or below the ground, so nothing is seabed
+    let palette = palette.with_seabed(
+        terrain_configuration.water.level,
+        rgb(&terrain_configuration.water.seabed_color),
+    );
+    // Also the drawn height of every column, for the land statistics
+    match terrain_configuration.mode {
+        MeshMode::Voxels => Some((
+            greedy_voxel_mesh(heightmap, &palette, progress)?,
+            surface_rows(heightmap, terrain_configuration, None),
+        )),
+        MeshMode::Heightfield => Some((
+            heightfield_mesh(heightmap, &palette, terrain_configuration.skirts, progress)?,
+            heightmap.clone(),
+        )),
+        MeshMode::Volume => {
+            let grid = density_rows(heightmap, terrain_configuration, noise, progress)?;
+            Some((
+                volume_mesh(&grid, &palette, progress)?,
+                surface_rows(heightmap, terrain_configuration, Some(&grid)),
+            ))
+        }
+    }
+}
+
+fn rgb(color: &str) -> [u8; 3] {
+    let color_r = u8::from_str_radix(&color[0..2], 16).unwrap();
+    let color_g = u8::from_str_radix(&color[2..4], 16).unwrap();
+    let color_b = u8::from_str_radix(&color[4..6], 16).unwrap();
+    [color_r, color_g, color_b]
 }
 
 pub fn update_configuration(
@@ -295,7 +642,10 @@ pub fn update_configuration(
             ..terrain_configuration
         },
         Some(ConfigurationMessage::TerrainSeed(value)) => TerrainConfiguration {
-            seed: value,
+            noise: OpenSimplex2 {
+                seed: value,
+                ..terrain_configuration.noise
+            },
             ..terrain_configuration
         },
         Some(ConfigurationMessage::TerrainCubeSize(value)) => TerrainConfiguration {
@@ -330,6 +680,121 @@ pub fn update_configuration(
             fractal_frequency: value,
             ..terrain_configuration
         },
+        Some(ConfigurationMessage::TerrainFractalType(value)) => TerrainConfiguration {
+            fractal_type: value,
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainWarpLevels(value)) => TerrainConfiguration {
+            warp: DomainWarp {
+                levels: value,
+                ..terrain_configuration.warp
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainWarpSeed(value)) => TerrainConfiguration {
+            warp: DomainWarp {
+                seed: value,
+                ..terrain_configuration.warp
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainWarpStrength(value)) => TerrainConfiguration {
+            warp: DomainWarp {
+                strength: value,
+                ..terrain_configuration.warp
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainWarpFrequency(value)) => TerrainConfiguration {
+            warp: DomainWarp {
+                frequency: value,
+                ..terrain_configuration.warp
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainNoiseAlgorithm(value)) => TerrainConfiguration {
+            noise: OpenSimplex2 {
+                algorithm: value,
+                ..terrain_configuration.noise
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainNoiseOrientation(value)) => TerrainConfiguration {
+            noise: OpenSimplex2 {
+                orientation: value,
+                ..terrain_configuration.noise
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainGraph(value)) => TerrainConfiguration {
+            graph: value,
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainThreads(value)) => TerrainConfiguration {
+            pool: thread_pool(value),
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainMode(value)) => TerrainConfiguration {
+            mode: value,
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainSkirts(value)) => TerrainConfiguration {
+            skirts: value,
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainColorRamp(value)) => TerrainConfiguration {
+            color_ramp: value,
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainVolumeThreshold(value)) => TerrainConfiguration {
+            volume: Volume {
+                threshold: value,
+                ..terrain_configuration.volume
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainVolumeCaveFrequency(value)) => TerrainConfiguration {
+            volume: Volume {
+                cave_frequency: value,
+                ..terrain_configuration.volume
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainVolumeVerticalBias(value)) => TerrainConfiguration {
+            volume: Volume {
+                vertical_bias: value,
+                ..terrain_configuration.volume
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainWaterLevel(value)) => TerrainConfiguration {
+            water: Water {
+                level: value,
+                ..terrain_configuration.water
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainWaterColor(value)) => TerrainConfiguration {
+            water: Water {
+                color: value,
+                ..terrain_configuration.water
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainWaterOpacity(value)) => TerrainConfiguration {
+            water: Water {
+                opacity: value,
+                ..terrain_configuration.water
+            },
+            ..terrain_configuration
+        },
+        Some(ConfigurationMessage::TerrainWaterSeabedColor(value)) => TerrainConfiguration {
+            water: Water {
+                seabed_color: value,
+                ..terrain_configuration.water
+            },
+            ..terrain_configuration
+        },
         None => terrain_configuration.clone(),
         _ => terrain_configuration.clone(),
     }
@@ -0,0 +1,251 @@
+/*!
+    Builds terrain meshes on a background thread, so the viewer keeps drawing the previous mesh
+    while a new one is generated.
+
+    Only the newest configuration matters: submitting one cancels the build in progress and
+    replaces any configuration still waiting for the thread.
+*/
+
+use crate::progress::Progress;
+use crate::terrain_configuration::{build_terrain_with_stats, TerrainConfiguration};
+use crate::water::TerrainStats;
+use serde::Serialize;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use three_d::CpuMesh;
+
+/**
+    Build state shared with the REST API. Builds are numbered from 1 in submission order.
+*/
+#[derive(Debug, Default)]
+pub struct TerrainStatus {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    requested: u64,
+    ready: u64,
+    building: Option<(u64, Arc<Progress>)>,
+    stats: Option<TerrainStats>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct StatusReport {
+    /**
+        Newest build submitted, 0 before the first.
+    */
+    pub requested: u64,
+    /**
+        Newest build whose mesh is ready, 0 before the first.
+    */
+    pub ready: u64,
+    /**
+        Share of the running build completed, `None` when no build is running.
+    */
+    pub progress: Option<f32>,
+}
+
+impl TerrainStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn report(&self) -> StatusReport {
+        let state = self.state.lock().unwrap();
+        StatusReport {
+            requested: state.requested,
+            ready: state.ready,
+            progress: state
+                .building
+                .as_ref()
+                .map(|(_, progress)| progress.fraction()),
+        }
+    }
+
+    /**
+        Land and coastline of the newest ready build, `None` before the first.
+    */
+    pub fn stats(&self) -> Option<TerrainStats> {
+        self.state.lock().unwrap().stats
+    }
+
+    /**
+        Numbers a new build and cancels the running one, which is stale from now on.
+    */
+    fn submit(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.requested += 1;
+        if let Some((_, progress)) = &state.building {
+            progress.cancel();
+        }
+        state.requested
+    }
+
+    /**
+        False if a newer build was submitted in the meantime.
+    */
+    fn start(&self, generation: u64, progress: Arc<Progress>) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.requested != generation {
+            return false;
+        }
+        state.building = Some((generation, progress));
+        true
+    }
+
+    /**
+        Passes the mesh on only if the build is still the newest one. Sent under the lock, so
+        the mesh is waiting in `meshes` by the time the report shows it ready.
+    */
+    fn finish(
+        &self,
+        generation: u64,
+        built: Option<(TerrainStats, CpuMesh)>,
+        meshes: &Sender<CpuMesh>,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        if matches!(state.building, Some((building, _)) if building == generation) {
+            state.building = None;
+        }
+        if let Some((stats, cpu_mesh)) = built {
+            if state.requested == generation {
+                state.ready = generation;
+                state.stats = Some(stats);
+                let _ = meshes.send(cpu_mesh);
+            }
+        }
+    }
+
+    fn cancel(&self) {
+        if let Some((_, progress)) = &self.state.lock().unwrap().building {
+            progress.cancel();
+        }
+    }
+}
+
+/**
+    Owns the build thread, which stops when the worker is dropped.
+
+    Where threads are unavailable (the browser build) `submit` builds on the calling thread
+    instead, so the viewer still works, only without staying responsive during builds.
+*/
+pub struct TerrainWorker {
+    shared: Arc<Shared>,
+    meshes: Sender<CpuMesh>,
+    built: Receiver<CpuMesh>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    status: Arc<TerrainStatus>,
+    queue: Mutex<Queue>,
+    wake: Condvar,
+}
+
+#[derive(Default)]
+struct Queue {
+    next: Option<(u64, TerrainConfiguration)>,
+    stop: bool,
+}
+
+impl TerrainWorker {
+    pub fn new(status: Arc<TerrainStatus>) -> Self {
+        let shared = Arc::new(Shared {
+            status,
+            queue: Mutex::new(Queue::default()),
+            wake: Condvar::new(),
+        });
+        let (meshes, built) = mpsc::channel();
+
+        let thread = {
+            let shared = shared.clone();
+            let meshes = meshes.clone();
+            thread::Builder::new()
+                .name("terrain".to_string())
+                .spawn(move || work(&shared, &meshes))
+                .ok()
+        };
+
+        Self {
+            shared,
+            meshes,
+            built,
+            thread,
+        }
+    }
+
+    pub fn status(&self) -> &Arc<TerrainStatus> {
+        &self.shared.status
+    }
+
+    /**
+        Queues a build of `terrain_configuration`, cancelling older ones.
+    */
+    pub fn submit(&self, terrain_configuration: TerrainConfiguration) {
+        let generation = self.shared.status.submit();
+        if self.thread.is_none() {
+            build(
+                &self.shared,
+                &self.meshes,
+                generation,
+                &terrain_configuration,
+            );
+            return;
+        }
+        self.shared.queue.lock().unwrap().next = Some((generation, terrain_configuration));
+        self.shared.wake.notify_one();
+    }
+
+    /**
+        The newest mesh finished since the last call, if any.
+    */
+    pub fn try_mesh(&self) -> Option<CpuMesh> {
+        self.built.try_iter().last()
+    }
+}
+
+impl Drop for TerrainWorker {
+    fn drop(&mut self) {
+        self.shared.queue.lock().unwrap().stop = true;
+        self.shared.status.cancel();
+        self.shared.wake.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn work(shared: &Shared, meshes: &Sender<CpuMesh>) {
+    loop {
+        let (generation, terrain_configuration) = {
+            let mut queue = shared.queue.lock().unwrap();
+            loop {
+                if queue.stop {
+                    return;
+                }
+                if let Some(next) = queue.next.take() {
+                    break next;
+                }
+                queue = shared.wake.wait(queue).unwrap();
+            }
+        };
+        build(shared, meshes, generation, &terrain_configuration);
+    }
+}
+
+fn build(
+    shared: &Shared,
+    meshes: &Sender<CpuMesh>,
+    generation: u64,
+    terrain_configuration: &TerrainConfiguration,
+) {
+    let progress = Arc::new(Progress::new());
+    if !shared.status.start(generation, progress.clone()) {
+        return;
+    }
+    let built = build_terrain_with_stats(terrain_configuration, &progress)
+        .map(|(_, cpu_mesh, stats)| (stats, cpu_mesh));
+    shared.status.finish(generation, built, meshes);
+}
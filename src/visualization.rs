@@ -1,11 +1,14 @@
 use crate::camera_configuration::{configure_camera, CameraConfiguration};
 use crate::configuration::ConfigurationMessage;
-use crate::terrain_configuration::{configure_terrain, TerrainConfiguration};
+use crate::light_configuration::{configure_lights, LightConfiguration};
+use crate::terrain_configuration::{configure_water, upload_terrain, TerrainConfiguration};
+use crate::terrain_worker::{TerrainStatus, TerrainWorker};
 use std::sync::mpsc::Receiver;
+use std::sync::Arc;
 use three_d::WindowedContext;
 use three_d::*;
 
-pub fn window(rx: Receiver<ConfigurationMessage>) {
+pub fn window(rx: Receiver<ConfigurationMessage>, status: Arc<TerrainStatus>) {
     let event_loop = winit::event_loop::EventLoop::new();
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -42,6 +45,11 @@ pub fn window(rx: Receiver<ConfigurationMessage>) {
     let mut camera = configure_camera(&camera_configuration);
     let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
 
+    // Sun from above, slightly behind the default camera
+    let mut light_configuration =
+        LightConfiguration::new(-1.0, -2.0, -0.5, "fff2d9".to_string(), 2.5, 0.4);
+    let (mut sun, mut ambient) = configure_lights(&context, &light_configuration);
+
     let mut terrain_configuration = TerrainConfiguration::new(
         20.0,
         20.0,
@@ -56,7 +64,11 @@ pub fn window(rx: Receiver<ConfigurationMessage>) {
         1.0,
     );
 
-    let mut model = configure_terrain(&context, &terrain_configuration);
+    // Nothing is drawn until the first build is ready, then the previous mesh stays up during rebuilds
+    let worker = TerrainWorker::new(status);
+    worker.submit(terrain_configuration.clone());
+    let mut model = None;
+    let mut water = configure_water(&context, &terrain_configuration);
 
     let mut frame_input_generator = FrameInputGenerator::from_winit_window(&window);
 
@@ -72,7 +84,7 @@ pub fn window(rx: Receiver<ConfigurationMessage>) {
             frame_input
                 .screen()
                 .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
-                .render(&camera, &model, &[]);
+                .render(&camera, model.iter().chain(&water), &[&sun, &ambient]);
 
             context.swap_buffers().unwrap();
             control_flow.set_poll();
@@ -94,14 +106,28 @@ pub fn window(rx: Receiver<ConfigurationMessage>) {
             }
         }
         _ => {
+            // Pending messages are applied together, so a burst of changes costs one rebuild
+            let mut terrain_changed = false;
             while let Ok(msg) = rx.try_recv() {
-                (terrain_configuration, camera_configuration) = update_configuration(
-                    terrain_configuration.clone(),
-                    camera_configuration.clone(),
-                    Some(msg),
-                );
-                model = configure_terrain(&context, &terrain_configuration);
+                terrain_changed |= msg.changes_terrain();
+                (terrain_configuration, camera_configuration, light_configuration) =
+                    update_configuration(
+                        terrain_configuration.clone(),
+                        camera_configuration,
+                        light_configuration.clone(),
+                        Some(msg),
+                    );
                 camera = configure_camera(&camera_configuration);
+                (sun, ambient) = configure_lights(&context, &light_configuration);
+                // The surface moves at once, the seabed follows with the rebuilt terrain
+                water = configure_water(&context, &terrain_configuration);
+                window.request_redraw();
+            }
+            if terrain_changed {
+                worker.submit(terrain_configuration.clone());
+            }
+            if let Some(cpu_mesh) = worker.try_mesh() {
+                model = Some(upload_terrain(&context, &cpu_mesh));
                 window.request_redraw();
             }
         }
@@ -111,10 +137,12 @@ pub fn window(rx: Receiver<ConfigurationMessage>) {
 fn update_configuration(
     terrain_configuration: TerrainConfiguration,
     camera_configuration: CameraConfiguration,
+    light_configuration: LightConfiguration,
     msg: Option<ConfigurationMessage>,
-) -> (TerrainConfiguration, CameraConfiguration) {
+) -> (TerrainConfiguration, CameraConfiguration, LightConfiguration) {
     (
         crate::terrain_configuration::update_configuration(terrain_configuration, msg.clone()),
         crate::camera_configuration::update_configuration(camera_configuration, msg.clone()),
+        crate::light_configuration::update_configuration(light_configuration, msg.clone()),
     )
 }
@@ -0,0 +1,114 @@
+/*!
+    Volumetric terrain: a 3D density that is solid below the heightmap surface and carved or
+    extended by 3D noise, so the surface can have caves, arches and overhangs.
+*/
+
+use crate::heightmap::positions;
+use crate::noise_source::NoiseSource;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Volume {
+    /**
+        Density above which space is solid. Raising it carves more caves.
+    */
+    pub threshold: f32,
+    /**
+        Frequency of the cave noise, in cycles per terrain unit.
+    */
+    pub cave_frequency: f64,
+    /**
+        Density gained per terrain unit below the heightmap surface. High values follow the
+        heightmap closely, low values let the noise reach deeper and float higher.
+    */
+    pub vertical_bias: f32,
+}
+
+impl Volume {
+    pub fn new(threshold: f32, cave_frequency: f64, vertical_bias: f32) -> Self {
+        Self {
+            threshold,
+            cave_frequency,
+            vertical_bias,
+        }
+    }
+
+    /**
+        Density at `[x, y, z]` minus the threshold, positive inside the terrain. `surface` is
+        the heightmap height of the column and `w` selects the noise slice.
+    */
+    pub fn density<N: NoiseSource + ?Sized>(
+        &self,
+        noise: &N,
+        surface: f32,
+        [x, y, z]: [f32; 3],
+        w: f64,
+    ) -> f32 {
+        let frequency = self.cave_frequency;
+        let caves = noise.sample4(
+            f64::from(x) * frequency,
+            f64::from(y) * frequency,
+            f64::from(z) * frequency,
+            w,
+        );
+        self.vertical_bias * (surface - y) + caves - self.threshold
+    }
+}
+
+impl Default for Volume {
+    /**
+        Caves reaching about one terrain unit below the surface.
+    */
+    fn default() -> Self {
+        Self::new(0.0, 3.0, 1.0)
+    }
+}
+
+/**
+    Density samples on a regular grid from the origin, `step` apart, stored x fastest, then y,
+    then z. Positive values are solid; everything outside the grid is empty.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityGrid {
+    pub size: [usize; 3],
+    pub step: f32,
+    pub values: Vec<f32>,
+}
+
+impl DensityGrid {
+    pub fn new(size: [usize; 3], step: f32, values: Vec<f32>) -> Self {
+        assert_eq!(
+            values.len(),
+            size[0] * size[1] * size[2],
+            "density grid needs one value per sample"
+        );
+        Self { size, step, values }
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> f32 {
+        self.values[x + self.size[0] * (y + self.size[1] * z)]
+    }
+
+    /**
+        Height where the column of samples at `x`, `z` last turns from solid to empty going up,
+        interpolated between samples as the surface is. 0 if the column is empty, and the top
+        sample if it is solid to the top.
+    */
+    pub fn surface(&self, x: usize, z: usize) -> f32 {
+        let ys = self.positions(1);
+        let Some(j) = (0..self.size[1]).rev().find(|&j| self.get(x, j, z) > 0.0) else {
+            return 0.0;
+        };
+        if j + 1 == self.size[1] {
+            return ys[j];
+        }
+        let (v0, v1) = (self.get(x, j, z), self.get(x, j + 1, z));
+        ys[j] + (ys[j + 1] - ys[j]) * (v0 / (v0 - v1))
+    }
+
+    /**
+        Sample coordinates along `axis`, 0 for x, 1 for y and 2 for z.
+    */
+    pub fn positions(&self, axis: usize) -> Vec<f32> {
+        positions(self.size[axis], self.step)
+    }
+}
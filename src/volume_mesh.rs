@@ -0,0 +1,239 @@
+/*!
+    Surface of a density grid, extracted with surface nets (dual contouring without the error
+    minimisation).
+
+    Every grid cell the surface passes through gets one vertex, at the mean of the points where
+    the density crosses zero along the cell's edges. Every grid edge with a crossing becomes a
+    quad joining the vertices of the four cells around it. Unlike marching cubes this needs no
+    case tables, and the surface is closed and consistently wound by construction.
+*/
+
+use crate::color_ramp::{slope, Palette};
+use crate::progress::Progress;
+use crate::volume::DensityGrid;
+use rayon::prelude::*;
+use three_d::{vec3, CpuMesh, Indices, InnerSpace, Positions, Srgba, Vec3};
+
+// Density of the empty layer wrapped around the grid, which closes the surface at its sides
+const OUTSIDE: f32 = -1.0;
+
+/**
+    Number of `progress` steps `volume_mesh` takes for a grid of `size` samples.
+*/
+pub fn mesh_steps(size: [usize; 3]) -> usize {
+    2 * size[2] + 3
+}
+
+/**
+    Surface around the positive samples of `grid`, colored by `palette`. Triangles wind
+    counter-clockwise seen from outside. Vertices are kept inside the sampled box, so where
+    the terrain reaches the grid's sides and floor it is cut off flat. Returns `None` if
+    `progress` is cancelled.
+*/
+pub fn volume_mesh(grid: &DensityGrid, palette: &Palette, progress: &Progress) -> Option<CpuMesh> {
+    if grid.size.contains(&0) {
+        return Some(Mesh::default().into_mesh());
+    }
+    let padded = Padded::new(grid);
+    let cells = padded.size.map(|n| n - 1);
+    let cell_index = |[x, y, z]: [usize; 3]| x + cells[0] * (y + cells[1] * z);
+
+    let slabs: Option<Vec<Vec<(usize, Vec3, Vec3)>>> = (0..cells[2])
+        .into_par_iter()
+        .map(|z| {
+            if progress.is_cancelled() {
+                return None;
+            }
+            let mut slab = Vec::new();
+            for y in 0..cells[1] {
+                for x in 0..cells[0] {
+                    if let Some((position, normal)) = padded.cell_vertex([x, y, z]) {
+                        slab.push((cell_index([x, y, z]), position, normal));
+                    }
+                }
+            }
+            progress.advance();
+            Some(slab)
+        })
+        .collect();
+
+    let mut mesh = Mesh::default();
+    let mut vertices = vec![u32::MAX; cells[0] * cells[1] * cells[2]];
+    for (cell, position, normal) in slabs?.into_iter().flatten() {
+        vertices[cell] = mesh.positions.len() as u32;
+        mesh.positions.push(position);
+        mesh.normals.push(normal);
+        mesh.colors
+            .push(palette.color(position.y, position.y, slope(normal)));
+    }
+
+    let slabs: Option<Vec<Vec<u32>>> = (0..padded.size[2])
+        .into_par_iter()
+        .map(|z| {
+            if progress.is_cancelled() {
+                return None;
+            }
+            let mut indices = Vec::new();
+            for y in 0..padded.size[1] {
+                for x in 0..padded.size[0] {
+                    for axis in 0..3 {
+                        if let Some(quad) = padded.edge_quad([x, y, z], axis) {
+                            let [a, b, c, d] = quad.map(|cell| vertices[cell_index(cell)]);
+                            indices.extend([a, b, c, a, c, d]);
+                        }
+                    }
+                }
+            }
+            progress.advance();
+            Some(indices)
+        })
+        .collect();
+    for indices in slabs? {
+        mesh.indices.extend(indices);
+    }
+    Some(mesh.into_mesh())
+}
+
+/**
+    The grid wrapped in one layer of empty samples on every side.
+*/
+struct Padded<'a> {
+    grid: &'a DensityGrid,
+    size: [usize; 3],
+    coordinates: [Vec<f32>; 3],
+    bounds: [f32; 3],
+}
+
+impl<'a> Padded<'a> {
+    fn new(grid: &'a DensityGrid) -> Self {
+        let coordinates = [0, 1, 2].map(|axis| {
+            let inner = grid.positions(axis);
+            let last = *inner.last().unwrap();
+            let mut padded = vec![-grid.step];
+            padded.extend(inner);
+            padded.push(last + grid.step);
+            padded
+        });
+        Self {
+            grid,
+            size: grid.size.map(|n| n + 2),
+            bounds: [0, 1, 2].map(|axis| coordinates[axis][grid.size[axis]]),
+            coordinates,
+        }
+    }
+
+    fn value(&self, [x, y, z]: [usize; 3]) -> f32 {
+        let inside = |p: usize, axis: usize| p >= 1 && p <= self.grid.size[axis];
+        if inside(x, 0) && inside(y, 1) && inside(z, 2) {
+            self.grid.get(x - 1, y - 1, z - 1)
+        } else {
+            OUTSIDE
+        }
+    }
+
+    fn position(&self, sample: [usize; 3]) -> Vec3 {
+        let [x, y, z] = [0, 1, 2].map(|axis| self.coordinates[axis][sample[axis]]);
+        vec3(x, y, z)
+    }
+
+    /**
+        Vertex and normal of the cell whose lowest corner is `cell`, if the surface crosses it.
+    */
+    fn cell_vertex(&self, cell: [usize; 3]) -> Option<(Vec3, Vec3)> {
+        let corner = |offset: [usize; 3]| {
+            let sample = [0, 1, 2].map(|axis| cell[axis] + offset[axis]);
+            (self.position(sample), self.value(sample))
+        };
+
+        let mut crossings = Vec3::new(0.0, 0.0, 0.0);
+        let mut count = 0;
+        let mut gradient = [0.0f32; 3];
+        for axis in 0..3 {
+            for (u, v) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let mut offset = [0; 3];
+                offset[(axis + 1) % 3] = u;
+                offset[(axis + 2) % 3] = v;
+                let (p0, v0) = corner(offset);
+                offset[axis] = 1;
+                let (p1, v1) = corner(offset);
+
+                gradient[axis] += (v1 - v0) / (p1[axis] - p0[axis]) / 4.0;
+                if (v0 > 0.0) != (v1 > 0.0) {
+                    crossings += p0 + (p1 - p0) * (v0 / (v0 - v1));
+                    count += 1;
+                }
+            }
+        }
+        if count == 0 {
+            return None;
+        }
+
+        let mean = crossings / count as f32;
+        let [x, y, z] = [0, 1, 2].map(|axis| mean[axis].clamp(0.0, self.bounds[axis]));
+        // Density grows inwards, so the outward normal points down the gradient
+        let normal = -vec3(gradient[0], gradient[1], gradient[2]);
+        let normal = if normal.magnitude2() > 0.0 {
+            normal.normalize()
+        } else {
+            vec3(0.0, 1.0, 0.0)
+        };
+        Some((vec3(x, y, z), normal))
+    }
+
+    /**
+        Cells around the edge from `sample` one step along `axis`, counter-clockwise seen from
+        outside, if the surface crosses the edge.
+    */
+    fn edge_quad(&self, sample: [usize; 3], axis: usize) -> Option<[[usize; 3]; 4]> {
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        if sample[axis] + 1 >= self.size[axis]
+            || sample[u] == 0
+            || sample[v] == 0
+            || sample[u] + 1 >= self.size[u]
+            || sample[v] + 1 >= self.size[v]
+        {
+            return None;
+        }
+        let mut next = sample;
+        next[axis] += 1;
+        let solid = self.value(sample) > 0.0;
+        if solid == (self.value(next) > 0.0) {
+            return None;
+        }
+
+        let cell = |du: usize, dv: usize| {
+            let mut cell = sample;
+            cell[u] = sample[u] - 1 + du;
+            cell[v] = sample[v] - 1 + dv;
+            cell
+        };
+        // Counter-clockwise in the (u, v) plane faces along +axis, which is outwards when the
+        // solid side is at `sample`
+        let quad = [cell(0, 0), cell(1, 0), cell(1, 1), cell(0, 1)];
+        Some(if solid {
+            quad
+        } else {
+            [quad[0], quad[3], quad[2], quad[1]]
+        })
+    }
+}
+
+#[derive(Default)]
+struct Mesh {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    colors: Vec<Srgba>,
+    indices: Vec<u32>,
+}
+
+impl Mesh {
+    fn into_mesh(self) -> CpuMesh {
+        CpuMesh {
+            positions: Positions::F32(self.positions),
+            indices: Indices::U32(self.indices),
+            normals: Some(self.normals),
+            colors: Some(self.colors),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,372 @@
+/*!
+    Voxel surface of a heightmap, built with greedy meshing.
+
+    Every column is the stack of cubes the viewer has always drawn: whole cubes from the ground
+    up, topped by one partial cube, each a flat color that gets lighter towards the top or
+    follows the color ramp by the height of its top. Only
+    faces between a cube and empty space are kept, and coplanar faces of the same color are
+    merged into larger quads, so the surface looks the same with far fewer triangles. Merged
+    quads do not share vertices with the smaller faces along their edges (T-junctions), so
+    rasterizers may show single-pixel seams there.
+*/
+
+use crate::color_ramp::Palette;
+use crate::heightmap::{positions, Heightmap};
+use crate::progress::Progress;
+use rayon::prelude::*;
+use three_d::{vec3, CpuMesh, Indices, Positions, Srgba, Vec3};
+
+/**
+    Number of `progress` steps `greedy_voxel_mesh` takes for a `width` by `depth` heightmap.
+*/
+pub fn mesh_steps(width: usize, depth: usize) -> usize {
+    width + depth + 1
+}
+
+/**
+    Surface of the column stacks in `heightmap`, each cube colored by `palette` as flat ground.
+    Triangles wind counter-clockwise seen from outside. Returns `None` if `progress` is
+    cancelled.
+*/
+pub fn greedy_voxel_mesh(
+    heightmap: &Heightmap,
+    palette: &Palette,
+    progress: &Progress,
+) -> Option<CpuMesh> {
+    let (width, depth) = (heightmap.width, heightmap.depth);
+    let cell_size = heightmap.cell_size;
+    // Column edges, so a face between two columns uses the exact coordinate both share
+    let xs = positions(width + 1, cell_size);
+    let zs = positions(depth + 1, cell_size);
+
+    let columns: Vec<Column> = heightmap
+        .heights
+        .par_iter()
+        .map(|&height| Column::new(height, cell_size, palette))
+        .collect();
+    let column = |i: usize, j: usize| &columns[i + width * j];
+    let height = |i: Option<usize>, j: Option<usize>| match (i, j) {
+        (Some(i), Some(j)) if i < width && j < depth => column(i, j).height,
+        _ => 0.0,
+    };
+
+    // Jobs are independent and joined in order, so the mesh does not depend on the thread count
+    let jobs = (0..width)
+        .map(Job::Row)
+        .chain((0..depth).map(Job::Line))
+        .chain([Job::Caps]);
+    let parts: Option<Vec<Quads>> = jobs
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|job| {
+            if progress.is_cancelled() {
+                return None;
+            }
+            let mut quads = Quads::default();
+            match job {
+                Job::Row(i) => {
+                    // Faces towards -x and +x, merged along the depth
+                    let lower = |j: usize| column(i, j).exposed(height(i.checked_sub(1), Some(j)));
+                    let upper = |j: usize| column(i, j).exposed(height(Some(i + 1), Some(j)));
+                    for (j0, j1, (y0, y1, color)) in runs(depth, lower) {
+                        quads.push(Face::NegX(xs[i], [y0, y1], [zs[j0], zs[j1]]), color);
+                    }
+                    for (j0, j1, (y0, y1, color)) in runs(depth, upper) {
+                        quads.push(Face::PosX(xs[i + 1], [y0, y1], [zs[j0], zs[j1]]), color);
+                    }
+                }
+                Job::Line(j) => {
+                    // Faces towards -z and +z, merged along the width
+                    let lower = |i: usize| column(i, j).exposed(height(Some(i), j.checked_sub(1)));
+                    let upper = |i: usize| column(i, j).exposed(height(Some(i), Some(j + 1)));
+                    for (i0, i1, (y0, y1, color)) in runs(width, lower) {
+                        quads.push(Face::NegZ(zs[j], [xs[i0], xs[i1]], [y0, y1]), color);
+                    }
+                    for (i0, i1, (y0, y1, color)) in runs(width, upper) {
+                        quads.push(Face::PosZ(zs[j + 1], [xs[i0], xs[i1]], [y0, y1]), color);
+                    }
+                }
+                Job::Caps => {
+                    let top = |i: usize, j: usize| column(i, j).top();
+                    for (i0, i1, j0, j1, (y, color)) in rectangles(width, depth, top) {
+                        let rectangle = [[xs[i0], xs[i1]], [zs[j0], zs[j1]]];
+                        quads.push(Face::PosY(y, rectangle), color);
+                    }
+                    let bottom = |i: usize, j: usize| column(i, j).bottom();
+                    for (i0, i1, j0, j1, color) in rectangles(width, depth, bottom) {
+                        let rectangle = [[xs[i0], xs[i1]], [zs[j0], zs[j1]]];
+                        quads.push(Face::NegY(0.0, rectangle), color);
+                    }
+                }
+            }
+            progress.advance();
+            Some(quads)
+        })
+        .collect();
+
+    let mut quads = Quads::default();
+    for part in parts? {
+        quads.append(part);
+    }
+    Some(quads.into_mesh())
+}
+
+enum Job {
+    Row(usize),
+    Line(usize),
+    Caps,
+}
+
+/**
+    Top of the cube stack drawn for a column `height` high. The whole cubes stop one level
+    below `height`, so the top is lower than `height` by a cube, or down to the ground.
+*/
+pub fn column_top(height: f32, cell_size: f32) -> f32 {
+    let top_level = (height / cell_size).floor() as i32;
+    let fractional_part = height % cell_size;
+    let levels = (top_level - 1).max(0) as usize;
+    let bases = positions(levels + 1, cell_size);
+    if fractional_part > 0.0 {
+        bases[levels] + fractional_part
+    } else if levels > 0 {
+        bases[levels - 1] + cell_size
+    } else {
+        0.0
+    }
+}
+
+/**
+    Solid from the ground to `height`, as runs of cubes sharing a color, bottom first.
+*/
+struct Column {
+    height: f32,
+    segments: Vec<(f32, f32, Srgba)>,
+}
+
+impl Column {
+    fn new(height: f32, cell_size: f32, palette: &Palette) -> Self {
+        let top_level = (height / cell_size).floor() as i32;
+        let fractional_part = height % cell_size;
+
+        // Whole cubes below the partial one, shaded darker towards the ground
+        let levels = (top_level - 1).max(0) as usize;
+        let bases = positions(levels + 1, cell_size);
+        let mut cubes: Vec<(f32, f32, Srgba)> = (0..levels)
+            .map(|k| {
+                let level = k + 1;
+                let shading = (top_level as f32 - level as f32 + 2.0) / top_level as f32 * 0.5;
+                let t = height - shading;
+                let top = bases[k] + cell_size;
+                (bases[k], top, palette.color(t, top, 0.0))
+            })
+            .collect();
+        if fractional_part > 0.0 {
+            let t = height - (2.0 / top_level as f32 * 0.5);
+            let top = bases[levels] + fractional_part;
+            cubes.push((bases[levels], top, palette.color(t, top, 0.0)));
+        }
+
+        let mut segments: Vec<(f32, f32, Srgba)> = Vec::new();
+        for (y0, y1, color) in cubes {
+            match segments.last_mut() {
+                Some(last) if last.2 == color => last.1 = y1,
+                _ => segments.push((y0, y1, color)),
+            }
+        }
+        Self {
+            height: segments.last().map_or(0.0, |segment| segment.1),
+            segments,
+        }
+    }
+
+    /**
+        Parts of one side above a neighbour `neighbour` high.
+    */
+    fn exposed(&self, neighbour: f32) -> Vec<(f32, f32, Srgba)> {
+        self.segments
+            .iter()
+            .filter(|segment| segment.1 > neighbour)
+            .map(|&(y0, y1, color)| (y0.max(neighbour), y1, color))
+            .collect()
+    }
+
+    fn top(&self) -> Option<(f32, Srgba)> {
+        self.segments.last().map(|&(_, y1, color)| (y1, color))
+    }
+
+    fn bottom(&self) -> Option<Srgba> {
+        self.segments.first().map(|&(_, _, color)| color)
+    }
+}
+
+/**
+    Merges equal faces of consecutive cells along a line: returns the first cell, one past the
+    last cell, and the face.
+*/
+fn runs<T: PartialEq>(len: usize, faces: impl Fn(usize) -> Vec<T>) -> Vec<(usize, usize, T)> {
+    let mut closed = Vec::new();
+    let mut open: Vec<(usize, T)> = Vec::new();
+    for cell in 0..len {
+        let mut next = Vec::new();
+        for face in faces(cell) {
+            match open.iter().position(|(_, f)| *f == face) {
+                Some(index) => next.push(open.swap_remove(index)),
+                None => next.push((cell, face)),
+            }
+        }
+        closed.extend(open.into_iter().map(|(start, face)| (start, cell, face)));
+        open = next;
+    }
+    closed.extend(open.into_iter().map(|(start, face)| (start, len, face)));
+    closed
+}
+
+/**
+    Greedy rectangles of equal faces on a grid: grows each rectangle along the width, then
+    along the depth while the whole span matches. Bounds are exclusive at the far end.
+*/
+fn rectangles<T: PartialEq>(
+    width: usize,
+    depth: usize,
+    face: impl Fn(usize, usize) -> Option<T>,
+) -> Vec<(usize, usize, usize, usize, T)> {
+    let mut done = vec![false; width * depth];
+    let mut rectangles = Vec::new();
+    for j0 in 0..depth {
+        for i0 in 0..width {
+            if done[i0 + width * j0] {
+                continue;
+            }
+            let Some(current) = face(i0, j0) else {
+                continue;
+            };
+            let same =
+                |i: usize, j: usize| !done[i + width * j] && face(i, j).as_ref() == Some(&current);
+            let mut i1 = i0 + 1;
+            while i1 < width && same(i1, j0) {
+                i1 += 1;
+            }
+            let mut j1 = j0 + 1;
+            while j1 < depth && (i0..i1).all(|i| same(i, j1)) {
+                j1 += 1;
+            }
+            for j in j0..j1 {
+                for i in i0..i1 {
+                    done[i + width * j] = true;
+                }
+            }
+            rectangles.push((i0, i1, j0, j1, current));
+        }
+    }
+    rectangles
+}
+
+/**
+    An axis-aligned quad: the plane coordinate, then the two ranges spanned, in x, y, z order.
+*/
+enum Face {
+    PosX(f32, [f32; 2], [f32; 2]),
+    NegX(f32, [f32; 2], [f32; 2]),
+    PosY(f32, [[f32; 2]; 2]),
+    NegY(f32, [[f32; 2]; 2]),
+    PosZ(f32, [f32; 2], [f32; 2]),
+    NegZ(f32, [f32; 2], [f32; 2]),
+}
+
+impl Face {
+    /**
+        Corners counter-clockwise seen from outside, each built from the exact coordinates.
+    */
+    fn corners(&self) -> [Vec3; 4] {
+        match *self {
+            Face::PosX(x, [y0, y1], [z0, z1]) => [
+                vec3(x, y0, z0),
+                vec3(x, y1, z0),
+                vec3(x, y1, z1),
+                vec3(x, y0, z1),
+            ],
+            Face::NegX(x, [y0, y1], [z0, z1]) => [
+                vec3(x, y0, z0),
+                vec3(x, y0, z1),
+                vec3(x, y1, z1),
+                vec3(x, y1, z0),
+            ],
+            Face::PosY(y, [[x0, x1], [z0, z1]]) => [
+                vec3(x0, y, z0),
+                vec3(x0, y, z1),
+                vec3(x1, y, z1),
+                vec3(x1, y, z0),
+            ],
+            Face::NegY(y, [[x0, x1], [z0, z1]]) => [
+                vec3(x0, y, z0),
+                vec3(x1, y, z0),
+                vec3(x1, y, z1),
+                vec3(x0, y, z1),
+            ],
+            Face::PosZ(z, [x0, x1], [y0, y1]) => [
+                vec3(x0, y0, z),
+                vec3(x1, y0, z),
+                vec3(x1, y1, z),
+                vec3(x0, y1, z),
+            ],
+            Face::NegZ(z, [x0, x1], [y0, y1]) => [
+                vec3(x0, y0, z),
+                vec3(x0, y1, z),
+                vec3(x1, y1, z),
+                vec3(x1, y0, z),
+            ],
+        }
+    }
+
+    fn normal(&self) -> Vec3 {
+        match self {
+            Face::PosX(..) => vec3(1.0, 0.0, 0.0),
+            Face::NegX(..) => vec3(-1.0, 0.0, 0.0),
+            Face::PosY(..) => vec3(0.0, 1.0, 0.0),
+            Face::NegY(..) => vec3(0.0, -1.0, 0.0),
+            Face::PosZ(..) => vec3(0.0, 0.0, 1.0),
+            Face::NegZ(..) => vec3(0.0, 0.0, -1.0),
+        }
+    }
+}
+
+/**
+    Quads with their own four vertices, since every quad is a flat color facing one way.
+*/
+#[derive(Default)]
+struct Quads {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    colors: Vec<Srgba>,
+}
+
+impl Quads {
+    fn push(&mut self, face: Face, color: Srgba) {
+        self.positions.extend(face.corners());
+        self.normals.extend([face.normal(); 4]);
+        self.colors.extend([color; 4]);
+    }
+
+    fn append(&mut self, other: Quads) {
+        self.positions.extend(other.positions);
+        self.normals.extend(other.normals);
+        self.colors.extend(other.colors);
+    }
+
+    fn into_mesh(self) -> CpuMesh {
+        let quads = self.positions.len() as u32 / 4;
+        let indices = (0..quads)
+            .flat_map(|quad| {
+                let start = 4 * quad;
+                [start, start + 1, start + 2, start, start + 2, start + 3]
+            })
+            .collect();
+        CpuMesh {
+            positions: Positions::F32(self.positions),
+            indices: Indices::U32(indices),
+            normals: Some(self.normals),
+            colors: Some(self.colors),
+            ..Default::default()
+        }
+    }
+}
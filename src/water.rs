@@ -0,0 +1,124 @@
+/*!
+    Sea level for island terrains: a translucent water surface over the terrain, seabed colors
+    below it, and the share of land and length of coastline it leaves.
+*/
+
+use crate::heightmap::Heightmap;
+use serde::Serialize;
+use three_d::{vec3, CpuMesh, Indices, Positions};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Water {
+    /**
+        Height of the water surface. The ground is at 0, so at 0 or below there is no water.
+    */
+    pub level: f32,
+    /**
+        6 hex digits, "rrggbb".
+    */
+    pub color: String,
+    /**
+        From 0 for clear water to 1 for opaque.
+    */
+    pub opacity: f32,
+    /**
+        Color of the terrain below the water surface, 6 hex digits.
+    */
+    pub seabed_color: String,
+}
+
+impl Water {
+    pub fn new(level: f32, color: String, opacity: f32, seabed_color: String) -> Self {
+        Self {
+            level,
+            color,
+            opacity,
+            seabed_color,
+        }
+    }
+
+    /**
+        Whether any terrain can be under water.
+    */
+    pub fn is_visible(&self) -> bool {
+        self.level > 0.0
+    }
+}
+
+impl Default for Water {
+    /**
+        No water, so terrains look as they did before sea levels existed.
+    */
+    fn default() -> Self {
+        Self::new(0.0, "2f6f9f".to_string(), 0.6, "8c7a5b".to_string())
+    }
+}
+
+/**
+    Horizontal water surface at `level` covering `[0, width]` by `[0, depth]`, facing up.
+*/
+pub fn water_mesh(width: f32, depth: f32, level: f32) -> CpuMesh {
+    CpuMesh {
+        positions: Positions::F32(vec![
+            vec3(0.0, level, 0.0),
+            vec3(0.0, level, depth),
+            vec3(width, level, depth),
+            vec3(width, level, 0.0),
+        ]),
+        // Counter-clockwise seen from above
+        indices: Indices::U32(vec![0, 1, 2, 0, 2, 3]),
+        normals: Some(vec![vec3(0.0, 1.0, 0.0); 4]),
+        ..Default::default()
+    }
+}
+
+/**
+    What a sea level leaves of a terrain. Columns whose drawn top is not below the sea level
+    are land, the complement of the columns colored as seabed.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TerrainStats {
+    pub sea_level: f32,
+    /**
+        Share of land columns, in [0, 1]. 0 for an empty terrain.
+    */
+    pub land_share: f32,
+    /**
+        Length of the borders between land and water columns, in terrain units. The edges of
+        the terrain are not coastline.
+    */
+    pub coastline_length: f32,
+}
+
+impl TerrainStats {
+    /**
+        `surface` holds the drawn top of every column, see
+        `terrain_configuration::terrain_surface`.
+    */
+    pub fn new(surface: &Heightmap, sea_level: f32) -> Self {
+        let land = |x: usize, z: usize| surface.get(x, z) >= sea_level;
+
+        let land_columns = surface.heights.iter().filter(|&&h| h >= sea_level).count();
+        let mut coast_edges = 0;
+        for z in 0..surface.depth {
+            for x in 0..surface.width {
+                if x + 1 < surface.width && land(x, z) != land(x + 1, z) {
+                    coast_edges += 1;
+                }
+                if z + 1 < surface.depth && land(x, z) != land(x, z + 1) {
+                    coast_edges += 1;
+                }
+            }
+        }
+
+        Self {
+            sea_level,
+            land_share: if surface.heights.is_empty() {
+                0.0
+            } else {
+                land_columns as f32 / surface.heights.len() as f32
+            },
+            coastline_length: coast_edges as f32 * surface.cell_size,
+        }
+    }
+}
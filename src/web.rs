@@ -0,0 +1,67 @@
+/*!
+    WebAssembly entry points. The page calls `run` once to start the viewer on its first
+    canvas, then forwards control panel updates with `send`, and reads build progress with
+    `status` and land statistics with `stats`, instead of HTTP requests.
+*/
+
+use crate::bridge;
+use crate::configuration::ConfigurationMessage;
+use crate::terrain_worker::TerrainStatus;
+use crate::visualization::window;
+use std::cell::RefCell;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+
+thread_local! {
+    static SENDER: RefCell<Option<Sender<ConfigurationMessage>>> = const { RefCell::new(None) };
+    static STATUS: Arc<TerrainStatus> = Arc::new(TerrainStatus::new());
+}
+
+/**
+    Starts the viewer. Never returns: the browser drives the event loop from here on.
+*/
+#[wasm_bindgen]
+pub fn run() {
+    window(connect(), STATUS.with(Arc::clone));
+}
+
+/**
+    Same endpoints and JSON values as the REST API, e.g.
+    `send("/api/terrain/seed", "42")`. Throws when the value is rejected.
+*/
+#[wasm_bindgen]
+pub fn send(endpoint: &str, value: &str) -> Result<(), JsValue> {
+    let message = bridge::message(endpoint, value).map_err(|e| JsValue::from_str(&e))?;
+    SENDER.with(|sender| match sender.borrow().as_ref() {
+        Some(tx) => tx
+            .send(message)
+            .map_err(|_| JsValue::from_str("the viewer has stopped")),
+        None => Err(JsValue::from_str("the viewer is not running")),
+    })
+}
+
+/**
+    Same JSON as `GET /api/terrain/status`.
+*/
+#[wasm_bindgen]
+pub fn status() -> String {
+    STATUS.with(|status| serde_json::to_string(&status.report()).unwrap_or_default())
+}
+
+/**
+    Same JSON as `GET /api/terrain/stats`.
+*/
+#[wasm_bindgen]
+pub fn stats() -> String {
+    STATUS.with(|status| serde_json::to_string(&status.stats()).unwrap_or_default())
+}
+
+/**
+    Routes `send` to a new receiver, replacing any earlier one.
+*/
+pub fn connect() -> Receiver<ConfigurationMessage> {
+    let (tx, rx) = mpsc::channel::<ConfigurationMessage>();
+    SENDER.with(|sender| *sender.borrow_mut() = Some(tx));
+    rx
+}
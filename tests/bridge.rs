@@ -0,0 +1,126 @@
+use terrainopensimplex2::bridge::message;
+use terrainopensimplex2::configuration::ConfigurationMessage;
+use terrainopensimplex2::fractal::FractalType;
+use terrainopensimplex2::mesh_mode::MeshMode;
+
+#[test]
+fn panel_urls_map_to_messages() {
+    assert!(matches!(
+        message("http://localhost:8090/api/terrain/width", "15.75"),
+        Ok(ConfigurationMessage::TerrainWidth(width)) if width == 15.75
+    ));
+    assert!(matches!(
+        message("/api/terrain/seed", "42"),
+        Ok(ConfigurationMessage::TerrainSeed(42))
+    ));
+    assert!(matches!(
+        message("/api/terrain/color", "\"4955ff\""),
+        Ok(ConfigurationMessage::TerrainColor(color)) if color == "4955ff"
+    ));
+    assert!(matches!(
+        message("/api/terrain/fractal/type", "\"ridged\""),
+        Ok(ConfigurationMessage::TerrainFractalType(
+            FractalType::Ridged
+        ))
+    ));
+    assert!(matches!(
+        message("/api/terrain/graph", "null"),
+        Ok(ConfigurationMessage::TerrainGraph(None))
+    ));
+    assert!(matches!(
+        message("/api/terrain/threads", "4"),
+        Ok(ConfigurationMessage::TerrainThreads(4))
+    ));
+    assert!(matches!(
+        message("/api/terrain/mode", "\"heightfield\""),
+        Ok(ConfigurationMessage::TerrainMode(MeshMode::Heightfield))
+    ));
+    assert!(matches!(
+        message("/api/terrain/skirts", "false"),
+        Ok(ConfigurationMessage::TerrainSkirts(false))
+    ));
+    assert!(matches!(
+        message("/api/terrain/volume/threshold", "0.25"),
+        Ok(ConfigurationMessage::TerrainVolumeThreshold(v)) if v == 0.25
+    ));
+    assert!(matches!(
+        message("/api/terrain/volume/cavefrequency", "4"),
+        Ok(ConfigurationMessage::TerrainVolumeCaveFrequency(v)) if v == 4.0
+    ));
+    assert!(matches!(
+        message("/api/terrain/volume/verticalbias", "2.5"),
+        Ok(ConfigurationMessage::TerrainVolumeVerticalBias(v)) if v == 2.5
+    ));
+    assert!(matches!(
+        message("/api/light/direction/y", "-2"),
+        Ok(ConfigurationMessage::LightDirectionY(v)) if v == -2.0
+    ));
+    assert!(matches!(
+        message("/api/light/color", "\"fff2d9\""),
+        Ok(ConfigurationMessage::LightColor(ref v)) if v == "fff2d9"
+    ));
+    assert!(matches!(
+        message("/api/light/intensity", "2.5"),
+        Ok(ConfigurationMessage::LightIntensity(v)) if v == 2.5
+    ));
+    assert!(matches!(
+        message("/api/light/ambient/intensity", "0"),
+        Ok(ConfigurationMessage::LightAmbientIntensity(v)) if v == 0.0
+    ));
+    assert!(matches!(
+        message(
+            "/api/terrain/colorramp",
+            r#"{ "stops": [ { "height": 0.0, "color": "c2b280" } ] }"#
+        ),
+        Ok(ConfigurationMessage::TerrainColorRamp(Some(_)))
+    ));
+    assert!(matches!(
+        message("/api/terrain/colorramp", "null"),
+        Ok(ConfigurationMessage::TerrainColorRamp(None))
+    ));
+    assert!(matches!(
+        message("/api/terrain/water/level", "0.5"),
+        Ok(ConfigurationMessage::TerrainWaterLevel(level)) if level == 0.5
+    ));
+    assert!(matches!(
+        message("/api/terrain/water/opacity", "1"),
+        Ok(ConfigurationMessage::TerrainWaterOpacity(opacity)) if opacity == 1.0
+    ));
+    assert!(matches!(
+        message("/api/terrain/water/seabedcolor", "\"8c7a5b\""),
+        Ok(ConfigurationMessage::TerrainWaterSeabedColor(color)) if color == "8c7a5b"
+    ));
+    assert!(matches!(
+        message("/api/camera/up/z", "1"),
+        Ok(ConfigurationMessage::CameraUpZ(z)) if z == 1.0
+    ));
+}
+
+#[test]
+fn values_rejected_by_the_rest_api_are_rejected() {
+    let invalid = [
+        ("/api/terrain/cubesize", "0.0"),
+        ("/api/terrain/color", "\"4955f\""),
+        ("/api/terrain/color", "\"4955fg\""),
+        ("/api/terrain/seed", "\"42\""),
+        ("/api/terrain/warp/levels", "3"),
+        ("/api/terrain/threads", "257"),
+        ("/api/terrain/threads", "-1"),
+        ("/api/terrain/mode", "\"cubes\""),
+        ("/api/terrain/skirts", "1"),
+        ("/api/terrain/volume/threshold", "\"high\""),
+        ("/api/terrain/colorramp", r#"{ "stops": [] }"#),
+        ("/api/terrain/water/opacity", "1.5"),
+        ("/api/terrain/water/color", "\"blue\""),
+        ("/api/terrain/water/seabedcolor", "\"8c7a5\""),
+        ("/api/light/color", "\"fff2d\""),
+        ("/api/light/intensity", "-1"),
+        ("/api/light/ambient/intensity", "-0.5"),
+        ("/api/terrain/noise/algorithm", "\"medium\""),
+        ("/api/terrain/graph", r#"{ "type": "min", "sources": [] }"#),
+        ("/api/terrain/unknown", "1"),
+    ];
+    for (endpoint, value) in invalid {
+        assert!(message(endpoint, value).is_err(), "{} {}", endpoint, value);
+    }
+}
@@ -0,0 +1,183 @@
+/*
+    Exercises the handle API through the generated header, linked against the staticlib.
+    Prints the first failed check and exits with 1, or exits with 0.
+*/
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "opensimplex2.h"
+
+#define CHECK(condition)                                           \
+    do {                                                           \
+        if (!(condition)) {                                        \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #condition); \
+            return 1;                                              \
+        }                                                          \
+    } while (0)
+
+static const long long SEED = 40000345266LL;
+
+static int check_terrain(void) {
+    OpenSimplex2TerrainConfig config = opensimplex2_terrain_default_config();
+    config.tot_width = 2.0f;
+    config.tot_depth = 1.0f;
+    config.cube_size = 0.25f;
+
+    size_t size_x = 0, size_y = 0;
+    CHECK(opensimplex2_terrain_size(&config, &size_x, &size_y) == OPENSIMPLEX2_OK);
+    CHECK(size_x == 8 && size_y == 4);
+
+    float heights[32];
+    CHECK(opensimplex2_terrain_heights(&config, heights, 31) == OPENSIMPLEX2_ERROR_BAD_LENGTH);
+    CHECK(opensimplex2_terrain_heights(&config, heights, 32) == OPENSIMPLEX2_OK);
+    for (int i = 0; i < 32; i++) {
+        /* Every column stands at least one cube above the ground */
+        CHECK(heights[i] >= config.cube_size && heights[i] <= config.cube_size + config.max_height);
+    }
+
+    OpenSimplex2TerrainMesh *mesh = NULL;
+    CHECK(opensimplex2_terrain_mesh_create(&config, &mesh) == OPENSIMPLEX2_OK && mesh != NULL);
+    size_t vertex_count = 0, index_count = 0;
+    CHECK(opensimplex2_terrain_mesh_size(mesh, &vertex_count, &index_count) == OPENSIMPLEX2_OK);
+    /* Two triangles per quad, each quad with its own four vertices */
+    CHECK(vertex_count > 0 && vertex_count % 4 == 0 && index_count == vertex_count / 4 * 6);
+
+    float *positions = malloc(3 * vertex_count * sizeof *positions);
+    unsigned int *indices = malloc(index_count * sizeof *indices);
+    unsigned char *colors = malloc(4 * vertex_count);
+    CHECK(positions != NULL && indices != NULL && colors != NULL);
+    CHECK(opensimplex2_terrain_mesh(mesh, positions, 3 * vertex_count - 1, NULL, 0, NULL, 0) == OPENSIMPLEX2_ERROR_BAD_LENGTH);
+    CHECK(opensimplex2_terrain_mesh(mesh, NULL, 0, indices, index_count, NULL, 0) == OPENSIMPLEX2_OK);
+    CHECK(opensimplex2_terrain_mesh(mesh, positions, 3 * vertex_count, indices, index_count, colors, 4 * vertex_count) == OPENSIMPLEX2_OK);
+    CHECK(opensimplex2_terrain_mesh(NULL, positions, 3 * vertex_count, NULL, 0, NULL, 0) == OPENSIMPLEX2_ERROR_NULL_POINTER);
+    for (size_t i = 0; i < index_count; i++) {
+        CHECK(indices[i] < vertex_count);
+    }
+    for (size_t i = 0; i < vertex_count; i++) {
+        CHECK(positions[3 * i + 1] >= 0.0f && colors[4 * i + 3] == 255);
+    }
+    free(positions);
+    free(indices);
+    free(colors);
+    opensimplex2_terrain_mesh_destroy(mesh);
+
+    /* Every setting reaches the pipeline, and changes the heights */
+    float warped[32];
+    config.fractal_type = 1;
+    config.warp_levels = 2;
+    config.noise_algorithm = 0;
+    CHECK(opensimplex2_terrain_heights(&config, warped, 32) == OPENSIMPLEX2_OK);
+    CHECK(memcmp(warped, heights, sizeof heights) != 0);
+
+    float flat[32];
+    config.graph = "{ \"type\": \"constant\", \"value\": 0.5 }";
+    CHECK(opensimplex2_terrain_heights(&config, flat, 32) == OPENSIMPLEX2_OK);
+    for (int i = 1; i < 32; i++) {
+        CHECK(fabsf(flat[i] - flat[0]) < 0.01f);
+    }
+
+    config.graph = "{ \"type\": \"min\", \"sources\": [] }";
+    CHECK(opensimplex2_terrain_heights(&config, flat, 32) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    config.graph = NULL;
+    config.fractal_type = 6;
+    CHECK(opensimplex2_terrain_size(&config, &size_x, &size_y) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    config.fractal_type = 0;
+    config.warp_levels = 3;
+    CHECK(opensimplex2_terrain_size(&config, &size_x, &size_y) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    config.warp_levels = 0;
+    CHECK(opensimplex2_terrain_mesh_create(&config, NULL) == OPENSIMPLEX2_ERROR_NULL_POINTER);
+
+    config.cube_size = 0.0f;
+    CHECK(opensimplex2_terrain_size(&config, &size_x, &size_y) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    CHECK(opensimplex2_terrain_size(NULL, &size_x, &size_y) == OPENSIMPLEX2_ERROR_NULL_POINTER);
+
+    /* Settings that would never finish or could not be allocated are rejected up front */
+    OpenSimplex2TerrainConfig bad = opensimplex2_terrain_default_config();
+    bad.cube_size = 1e-7f;
+    CHECK(opensimplex2_terrain_size(&bad, &size_x, &size_y) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    bad = opensimplex2_terrain_default_config();
+    bad.tot_width = bad.tot_depth = 6000.0f;
+    bad.cube_size = 1.0f;
+    CHECK(opensimplex2_terrain_size(&bad, &size_x, &size_y) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    bad = opensimplex2_terrain_default_config();
+    bad.max_height = 1e30f;
+    CHECK(opensimplex2_terrain_mesh_create(&bad, &mesh) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    bad = opensimplex2_terrain_default_config();
+    bad.max_height = NAN;
+    CHECK(opensimplex2_terrain_heights(&bad, flat, 32) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    bad = opensimplex2_terrain_default_config();
+    bad.failoff = 0.0f;
+    CHECK(opensimplex2_terrain_size(&bad, &size_x, &size_y) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    bad = opensimplex2_terrain_default_config();
+    bad.z = INFINITY;
+    CHECK(opensimplex2_terrain_size(&bad, &size_x, &size_y) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    bad = opensimplex2_terrain_default_config();
+    bad.fractal_octaves = 1 << 30;
+    CHECK(opensimplex2_terrain_size(&bad, &size_x, &size_y) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+
+    /* A graph can still raise columns past the limit, which only meshing refuses */
+    config = opensimplex2_terrain_default_config();
+    config.tot_width = 2.0f;
+    config.tot_depth = 1.0f;
+    config.cube_size = 0.25f;
+    config.graph = "{ \"type\": \"constant\", \"value\": 1e30 }";
+    CHECK(opensimplex2_terrain_heights(&config, flat, 32) == OPENSIMPLEX2_OK);
+    CHECK(opensimplex2_terrain_mesh_create(&config, &mesh) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    return 0;
+}
+
+int main(void) {
+    float grid[4 * 3 * 2];
+    float sentinel[4];
+
+    CHECK(opensimplex2_create(SEED, 2) == NULL);
+
+    OpenSimplex2 *fast = opensimplex2_create(SEED, 0);
+    OpenSimplex2 *smooth = opensimplex2_create(SEED, 1);
+    CHECK(fast != NULL && smooth != NULL);
+
+    /* 2D grid matches the scalar exports, X varying fastest */
+    CHECK(opensimplex2_fill_grid2(fast, grid, 12, 0, -1.5, 2.0, 0.25, 0.5, 4, 3) == OPENSIMPLEX2_OK);
+    for (int j = 0; j < 3; j++) {
+        for (int i = 0; i < 4; i++) {
+            float expected = opensimplex2_fast_noise2(SEED, -1.5 + i * 0.25, 2.0 + j * 0.5);
+            CHECK(grid[i + 4 * j] == expected);
+        }
+    }
+
+    /* 3D grid in every orientation */
+    CHECK(opensimplex2_fill_grid3(smooth, grid, 24, 1, 0.5, -0.5, 3.0, 0.1, 0.2, 0.3, 4, 3, 2) == OPENSIMPLEX2_OK);
+    CHECK(grid[23] == opensimplex2_smooth_noise3_ImproveXZ(SEED, 0.5 + 3 * 0.1, -0.5 + 2 * 0.2, 3.0 + 1 * 0.3));
+    CHECK(opensimplex2_fill_grid3(smooth, grid, 24, 0, 0.5, -0.5, 3.0, 0.1, 0.2, 0.3, 4, 3, 2) == OPENSIMPLEX2_OK);
+    CHECK(grid[0] == opensimplex2_smooth_noise3_ImproveXY(SEED, 0.5, -0.5, 3.0));
+    CHECK(opensimplex2_fill_grid3(smooth, grid, 24, 2, 0.5, -0.5, 3.0, 0.1, 0.2, 0.3, 4, 3, 2) == OPENSIMPLEX2_OK);
+    CHECK(grid[0] == opensimplex2_smooth_noise3_Fallback(SEED, 0.5, -0.5, 3.0));
+
+    /* Frequency and offset */
+    CHECK(opensimplex2_set_frequency(fast, 0.5) == OPENSIMPLEX2_OK);
+    CHECK(opensimplex2_set_offset(fast, 10.0, -20.0, 0.0, 0.0) == OPENSIMPLEX2_OK);
+    CHECK(opensimplex2_fill_grid2(fast, grid, 1, 1, 3.0, 4.0, 1.0, 1.0, 1, 1) == OPENSIMPLEX2_OK);
+    CHECK(grid[0] == opensimplex2_fast_noise2_ImproveX(SEED, 11.5, -18.0));
+
+    /* Errors leave the buffer untouched */
+    memset(sentinel, 0, sizeof sentinel);
+    CHECK(opensimplex2_fill_grid2(NULL, sentinel, 4, 0, 0.0, 0.0, 1.0, 1.0, 2, 2) == OPENSIMPLEX2_ERROR_NULL_POINTER);
+    CHECK(opensimplex2_fill_grid2(fast, NULL, 4, 0, 0.0, 0.0, 1.0, 1.0, 2, 2) == OPENSIMPLEX2_ERROR_NULL_POINTER);
+    CHECK(opensimplex2_fill_grid2(fast, sentinel, 3, 0, 0.0, 0.0, 1.0, 1.0, 2, 2) == OPENSIMPLEX2_ERROR_BAD_LENGTH);
+    CHECK(opensimplex2_fill_grid3(fast, sentinel, 4, 0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, (size_t)-1, 2, 2) == OPENSIMPLEX2_ERROR_BAD_LENGTH);
+    CHECK(opensimplex2_fill_grid3(fast, sentinel, 4, 3, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1, 2, 2) == OPENSIMPLEX2_ERROR_BAD_ARGUMENT);
+    CHECK(opensimplex2_set_frequency(NULL, 1.0) == OPENSIMPLEX2_ERROR_NULL_POINTER);
+    for (int i = 0; i < 4; i++) {
+        CHECK(sentinel[i] == 0.0f);
+    }
+
+    CHECK(isnan(opensimplex2_cellular_noise2(SEED, 0.0, 0.0, 9, 0)));
+
+    opensimplex2_destroy(fast);
+    opensimplex2_destroy(smooth);
+    opensimplex2_destroy(NULL);
+    return check_terrain();
+}
@@ -0,0 +1,81 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const HEADER: &str = "include/opensimplex2.h";
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn generate_header() -> String {
+    let config = cbindgen::Config::from_file(manifest_dir().join("cbindgen.toml")).unwrap();
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(manifest_dir().join("src/ffi.rs"))
+        .generate()
+        .expect("cbindgen failed on src/ffi.rs")
+        .write(&mut header);
+    String::from_utf8(header).unwrap()
+}
+
+#[test]
+#[ignore]
+fn regenerate() {
+    fs::write(manifest_dir().join(HEADER), generate_header()).unwrap();
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let shipped = fs::read_to_string(manifest_dir().join(HEADER)).unwrap();
+    assert!(
+        shipped == generate_header(),
+        "{} is stale, run `cargo test --test c_api -- --ignored regenerate`",
+        HEADER
+    );
+}
+
+/// `cargo test` builds the staticlib into the same `deps` directory as the test binaries.
+fn staticlib() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    exe.with_file_name("libterrainopensimplex2.a")
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn c_program_runs_against_the_staticlib() {
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("c_api");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir().join("include"))
+        .arg(manifest_dir().join("tests/c/c_api.c"))
+        .arg(staticlib())
+        .args([
+            "-lpthread",
+            "-ldl",
+            "-lm",
+            "-lrt",
+            "-lutil",
+            "-lgcc_s",
+            "-lc",
+        ])
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "compiling tests/c/c_api.c failed");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,286 @@
+use terrainopensimplex2::cellular::{
+    cellular2, cellular3, feature_point2, feature_point3, CellularOutput, DistanceFunction,
+};
+use terrainopensimplex2::ffi::{opensimplex2_cellular_noise2, opensimplex2_cellular_noise3};
+
+const SEEDS: [i64; 3] = [0, 42, -7_340_032_993];
+const DISTANCES: [DistanceFunction; 3] = [
+    DistanceFunction::Euclidean,
+    DistanceFunction::Manhattan,
+    DistanceFunction::Chebyshev,
+];
+const STEP: f64 = 1e-3;
+
+fn points<const D: usize>() -> impl Iterator<Item = [f64; D]> {
+    (0..2000).map(|i| {
+        std::array::from_fn(|axis| (i as f64 * (0.137 + 0.071 * axis as f64)) % 40.0 - 20.0)
+    })
+}
+
+/// A step along one axis has the same length in every metric, so F1 and F2 move by at most that.
+fn assert_lipschitz(name: &str, a: f32, b: f32) {
+    assert!(
+        (a - b).abs() <= (STEP * 1.01) as f32,
+        "{}: {} and {} a step apart",
+        name,
+        a,
+        b
+    );
+}
+
+#[test]
+fn distances_are_ordered_and_continuous2() {
+    for seed in SEEDS {
+        for distance in DISTANCES {
+            for p in points::<2>() {
+                let f1 = cellular2(seed, p[0], p[1], distance, CellularOutput::F1);
+                let f2 = cellular2(seed, p[0], p[1], distance, CellularOutput::F2);
+                let f21 = cellular2(seed, p[0], p[1], distance, CellularOutput::F2MinusF1);
+                assert!(
+                    0.0 <= f1 && f1 <= f2,
+                    "{:?} at {:?}: {} {}",
+                    distance,
+                    p,
+                    f1,
+                    f2
+                );
+                assert!((f21 - (f2 - f1)).abs() < 1e-6);
+
+                // Missing a feature point shows up as a jump when crossing a cell border.
+                for axis in 0..2 {
+                    let mut q = p;
+                    q[axis] += STEP;
+                    for output in [CellularOutput::F1, CellularOutput::F2] {
+                        let a = cellular2(seed, p[0], p[1], distance, output);
+                        let b = cellular2(seed, q[0], q[1], distance, output);
+                        assert_lipschitz(&format!("{:?} {:?} at {:?}", distance, output, p), a, b);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn distances_are_ordered_and_continuous3() {
+    for seed in SEEDS {
+        for distance in DISTANCES {
+            for p in points::<3>().take(500) {
+                let f1 = cellular3(seed, p[0], p[1], p[2], distance, CellularOutput::F1);
+                let f2 = cellular3(seed, p[0], p[1], p[2], distance, CellularOutput::F2);
+                assert!(
+                    0.0 <= f1 && f1 <= f2,
+                    "{:?} at {:?}: {} {}",
+                    distance,
+                    p,
+                    f1,
+                    f2
+                );
+
+                for axis in 0..3 {
+                    let mut q = p;
+                    q[axis] += STEP;
+                    for output in [CellularOutput::F1, CellularOutput::F2] {
+                        let a = cellular3(seed, p[0], p[1], p[2], distance, output);
+                        let b = cellular3(seed, q[0], q[1], q[2], distance, output);
+                        assert_lipschitz(&format!("{:?} {:?} at {:?}", distance, output, p), a, b);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn cell_ids_partition_space() {
+    let mut ids = Vec::new();
+    for p in points::<2>() {
+        let id = cellular2(
+            7,
+            p[0],
+            p[1],
+            DistanceFunction::Euclidean,
+            CellularOutput::CellId,
+        );
+        assert!((-1.0..1.0).contains(&id));
+        ids.push(id);
+    }
+    ids.sort_by(f32::total_cmp);
+    ids.dedup();
+    assert!(ids.len() > 100, "only {} distinct cells", ids.len());
+
+    // Points right next to a feature point share its cell.
+    let a = cellular3(
+        7,
+        0.5,
+        0.5,
+        0.5,
+        DistanceFunction::Euclidean,
+        CellularOutput::CellId,
+    );
+    let b = cellular3(
+        7,
+        0.501,
+        0.5,
+        0.5,
+        DistanceFunction::Euclidean,
+        CellularOutput::CellId,
+    );
+    assert_eq!(a, b);
+}
+
+#[test]
+fn seeds_change_the_pattern() {
+    let differs = points::<2>().any(|p| {
+        cellular2(
+            1,
+            p[0],
+            p[1],
+            DistanceFunction::Euclidean,
+            CellularOutput::F1,
+        ) != cellular2(
+            2,
+            p[0],
+            p[1],
+            DistanceFunction::Euclidean,
+            CellularOutput::F1,
+        )
+    });
+    assert!(differs);
+}
+
+#[test]
+fn ffi_codes_select_the_variant() {
+    assert_eq!(
+        opensimplex2_cellular_noise2(5, 1.25, -3.5, 1, 2),
+        cellular2(
+            5,
+            1.25,
+            -3.5,
+            DistanceFunction::Manhattan,
+            CellularOutput::F2MinusF1
+        )
+    );
+    assert_eq!(
+        opensimplex2_cellular_noise3(5, 1.25, -3.5, 9.0, 2, 3),
+        cellular3(
+            5,
+            1.25,
+            -3.5,
+            9.0,
+            DistanceFunction::Chebyshev,
+            CellularOutput::CellId
+        )
+    );
+    assert!(opensimplex2_cellular_noise2(5, 0.0, 0.0, 3, 0).is_nan());
+    assert!(opensimplex2_cellular_noise3(5, 0.0, 0.0, 0.0, 0, 4).is_nan());
+}
+
+/// Random points in [-1000, 1000) from a fixed linear congruential generator.
+fn random_points<const D: usize>(count: usize) -> Vec<[f64; D]> {
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 11) as f64 / (1u64 << 53) as f64 * 2000.0 - 1000.0
+    };
+    (0..count)
+        .map(|_| std::array::from_fn(|_| next()))
+        .collect()
+}
+
+fn distance_to(distance: DistanceFunction, d: &[f64]) -> f64 {
+    match distance {
+        DistanceFunction::Euclidean => d.iter().map(|v| v * v).sum::<f64>().sqrt(),
+        DistanceFunction::Manhattan => d.iter().map(|v| v.abs()).sum(),
+        DistanceFunction::Chebyshev => d.iter().fold(0.0, |m, v| v.abs().max(m)),
+    }
+}
+
+/// Nearest two distances to the feature points of every cell within `radius` of `point`.
+fn brute_force<const D: usize>(
+    distance: DistanceFunction,
+    point: [f64; D],
+    radius: i64,
+    feature_point: impl Fn([i64; D]) -> [f64; D],
+) -> (f32, f32) {
+    let base: [i64; D] = point.map(|v| v.floor() as i64);
+    let side = (2 * radius + 1) as usize;
+    let mut distances: Vec<f64> = (0..side.pow(D as u32))
+        .map(|index| {
+            let cell = std::array::from_fn(|axis| {
+                base[axis] + (index / side.pow(axis as u32) % side) as i64 - radius
+            });
+            let feature = feature_point(cell);
+            let d: [f64; D] = std::array::from_fn(|axis| feature[axis] - point[axis]);
+            distance_to(distance, &d)
+        })
+        .collect();
+    distances.sort_by(f64::total_cmp);
+    (distances[0] as f32, distances[1] as f32)
+}
+
+fn assert_close(name: &str, a: f32, b: f32) {
+    assert!(
+        (a - b).abs() <= 1e-6 * b.max(1.0),
+        "{}: {} != {}",
+        name,
+        a,
+        b
+    );
+}
+
+#[test]
+fn nearest_points_match_a_brute_force_search2() {
+    for distance in DISTANCES {
+        for point in random_points::<2>(20_000) {
+            let (f1, f2) = brute_force(distance, point, 4, |cell| feature_point2(42, cell));
+            let [x, y] = point;
+            assert_close("F1", cellular2(42, x, y, distance, CellularOutput::F1), f1);
+            assert_close("F2", cellular2(42, x, y, distance, CellularOutput::F2), f2);
+        }
+    }
+}
+
+#[test]
+fn nearest_points_match_a_brute_force_search3() {
+    for distance in DISTANCES {
+        for point in random_points::<3>(2_000) {
+            let (f1, f2) = brute_force(distance, point, 3, |cell| feature_point3(42, cell));
+            let [x, y, z] = point;
+            assert_close(
+                "F1",
+                cellular3(42, x, y, z, distance, CellularOutput::F1),
+                f1,
+            );
+            assert_close(
+                "F2",
+                cellular3(42, x, y, z, distance, CellularOutput::F2),
+                f2,
+            );
+        }
+    }
+}
+
+#[test]
+fn every_input_finishes() {
+    for distance in DISTANCES {
+        for v in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(cellular2(1, v, 0.5, distance, CellularOutput::F1).is_nan());
+            assert!(cellular3(1, 0.5, 0.5, v, distance, CellularOutput::F2).is_nan());
+        }
+        // Far past i64 and past the precision of f64: distances still come from the position
+        // within the cell, so F1 and F2 stay in the usual range.
+        for v in [1e19, -1e19, 1e300, 4.5e15 + 0.25] {
+            for output in [CellularOutput::F1, CellularOutput::F2] {
+                let a = cellular2(1, v, v, distance, output);
+                let b = cellular3(1, v, -v, v, distance, output);
+                assert!((0.0..3.0).contains(&a), "{:?} at {}: {}", distance, v, a);
+                assert!((0.0..3.0).contains(&b), "{:?} at {}: {}", distance, v, b);
+            }
+        }
+    }
+    assert!(opensimplex2_cellular_noise2(1, f64::NAN, 0.0, 0, 0).is_nan());
+    assert!(opensimplex2_cellular_noise3(1, 1e19, 1e19, 1e19, 0, 1) < 3.0);
+}
@@ -0,0 +1,176 @@
+use terrainopensimplex2::color_ramp::{slope, ColorRamp, Palette};
+use terrainopensimplex2::configuration::ConfigurationMessage;
+use terrainopensimplex2::mesh_mode::MeshMode;
+use terrainopensimplex2::terrain_configuration::{
+    generate_heightmap, terrain_mesh, update_configuration, TerrainConfiguration,
+};
+use three_d::{vec3, InnerSpace, Positions, Srgba};
+
+const RAMP: &str = r#"{
+    "stops": [
+        { "height": 0.0, "color": "c2b280" },
+        { "height": 0.1, "color": "4f7a3a" },
+        { "height": 0.5, "color": "7a746b" },
+        { "height": 0.8, "color": "f4f4f4" }
+    ],
+    "slope": { "start": 0.4, "end": 0.6, "color": "000000" }
+}"#;
+
+fn ramp(json: &str) -> ColorRamp {
+    serde_json::from_str(json).unwrap()
+}
+
+fn rgb(color: Srgba) -> [u8; 3] {
+    [color.r, color.g, color.b]
+}
+
+#[test]
+fn stops_blend_by_normalised_height() {
+    let palette = Palette::ramp(&ramp(RAMP), 2.0).unwrap();
+    let flat = |height: f32| rgb(palette.color(height, height, 0.0));
+
+    assert_eq!(flat(0.0), [0xc2, 0xb2, 0x80]);
+    assert_eq!(flat(0.2), [0x4f, 0x7a, 0x3a]);
+    assert_eq!(flat(1.6), [0xf4, 0xf4, 0xf4]);
+    // Clamped outside the ramp
+    assert_eq!(flat(-1.0), [0xc2, 0xb2, 0x80]);
+    assert_eq!(flat(5.0), [0xf4, 0xf4, 0xf4]);
+    // An eighth of the way from rock at 1.0 to snow at 1.6
+    assert_eq!(flat(1.075), [0x89, 0x84, 0x7c]);
+}
+
+#[test]
+fn stops_switch_without_blending() {
+    let mut stepped = ramp(RAMP);
+    stepped.blend = false;
+    let palette = Palette::ramp(&stepped, 2.0).unwrap();
+
+    assert_eq!(rgb(palette.color(1.3, 1.3, 0.0)), [0x7a, 0x74, 0x6b]);
+    assert_eq!(rgb(palette.color(1.59, 1.59, 0.0)), [0x7a, 0x74, 0x6b]);
+    assert_eq!(rgb(palette.color(1.6, 1.6, 0.0)), [0xf4, 0xf4, 0xf4]);
+}
+
+#[test]
+fn steep_ground_takes_the_slope_color() {
+    let palette = Palette::ramp(&ramp(RAMP), 2.0).unwrap();
+    let at = |slope: f32| rgb(palette.color(1.6, 1.6, slope));
+
+    assert_eq!(at(0.4), [0xf4, 0xf4, 0xf4]);
+    assert_eq!(at(0.5), [0x7a, 0x7a, 0x7a]);
+    assert_eq!(at(0.6), [0, 0, 0]);
+    assert_eq!(at(1.0), [0, 0, 0]);
+
+    assert_eq!(slope(vec3(0.0, 1.0, 0.0)), 0.0);
+    assert!((slope(vec3(1.0, 1.0, 0.0).normalize()) - 0.5).abs() < 1e-6);
+    assert_eq!(slope(vec3(0.0, 0.0, 1.0)), 1.0);
+    assert_eq!(slope(vec3(0.0, -1.0, 0.0)), 1.0);
+}
+
+#[test]
+fn defaults_and_round_trip() {
+    let plain = ramp(r#"{ "stops": [ { "height": 0.5, "color": "102030" } ] }"#);
+    assert!(plain.blend);
+    assert!(plain.slope.is_none());
+
+    let full = ramp(RAMP);
+    let json = serde_json::to_string(&full).unwrap();
+    assert_eq!(ramp(&json), full);
+}
+
+#[test]
+fn invalid_ramps_are_rejected() {
+    let invalid = [
+        r#"{ "stops": [] }"#,
+        r#"{ "stops": [ { "height": 0.5, "color": "10203" } ] }"#,
+        r#"{ "stops": [ { "height": 0.5, "color": "10203g" } ] }"#,
+        r#"{ "stops": [ { "height": 1.5, "color": "102030" } ] }"#,
+        r#"{ "stops": [ { "height": 0.5, "color": "102030" }, { "height": 0.2, "color": "102030" } ] }"#,
+        r#"{ "stops": [ { "height": 0.5, "color": "102030" } ], "slope": { "start": 0.6, "end": 0.4, "color": "000000" } }"#,
+        r#"{ "stops": [ { "height": 0.5, "color": "102030" } ], "slope": { "start": 0.4, "end": 0.6, "color": "black" } }"#,
+    ];
+    for json in invalid {
+        assert!(ramp(json).validate().is_err(), "{}", json);
+        assert!(Palette::ramp(&ramp(json), 1.0).is_err(), "{}", json);
+    }
+    assert!(ramp(RAMP).validate().is_ok());
+}
+
+#[test]
+fn configured_ramp_colors_the_terrain() {
+    let terrain_configuration = TerrainConfiguration::new(
+        2.0,
+        2.0,
+        40000345266,
+        0.1,
+        "304630".to_string(),
+        2.0,
+        200.0,
+        21.0,
+        8,
+        0.4,
+        1.0,
+    );
+    let heightmap = generate_heightmap(&terrain_configuration);
+    let single = ramp(r#"{ "stops": [ { "height": 0.0, "color": "102030" } ] }"#);
+
+    for mode in [MeshMode::Voxels, MeshMode::Heightfield, MeshMode::Volume] {
+        let in_mode = update_configuration(
+            terrain_configuration.clone(),
+            Some(ConfigurationMessage::TerrainMode(mode)),
+        );
+        let ramped = update_configuration(
+            in_mode.clone(),
+            Some(ConfigurationMessage::TerrainColorRamp(Some(single.clone()))),
+        );
+        let colors = terrain_mesh(&heightmap, &ramped).colors.unwrap();
+        assert!(
+            colors.iter().all(|&c| rgb(c) == [0x10, 0x20, 0x30]),
+            "{:?}",
+            mode
+        );
+
+        // Clearing the ramp goes back to the shaded base color
+        let cleared =
+            update_configuration(ramped, Some(ConfigurationMessage::TerrainColorRamp(None)));
+        assert_eq!(
+            terrain_mesh(&heightmap, &cleared).colors,
+            terrain_mesh(&heightmap, &in_mode).colors
+        );
+    }
+}
+
+#[test]
+fn heightfield_colors_follow_height_and_slope() {
+    let terrain_configuration = update_configuration(
+        TerrainConfiguration::new(
+            2.0,
+            2.0,
+            40000345266,
+            0.1,
+            "304630".to_string(),
+            2.0,
+            200.0,
+            21.0,
+            8,
+            0.4,
+            1.0,
+        ),
+        Some(ConfigurationMessage::TerrainMode(MeshMode::Heightfield)),
+    );
+    let terrain_configuration = update_configuration(
+        terrain_configuration,
+        Some(ConfigurationMessage::TerrainColorRamp(Some(ramp(RAMP)))),
+    );
+    let heightmap = generate_heightmap(&terrain_configuration);
+    let mesh = terrain_mesh(&heightmap, &terrain_configuration);
+    let (Positions::F32(positions), Some(normals), Some(colors)) =
+        (&mesh.positions, &mesh.normals, &mesh.colors)
+    else {
+        panic!("the heightfield is built with f32 positions, normals and colors");
+    };
+
+    let palette = Palette::ramp(&ramp(RAMP), 2.1).unwrap();
+    for ((p, &normal), &color) in positions.iter().zip(normals).zip(colors) {
+        assert_eq!(color, palette.color(p.y, p.y, slope(normal)));
+    }
+}
@@ -0,0 +1,239 @@
+use terrainopensimplex2::{fast, smooth};
+
+const SEEDS: [i64; 4] = [0, 42, -7_340_032_993, 40000345266];
+const STEP: f64 = 1e-4;
+const TOLERANCE: f32 = 2e-2;
+
+type WithDerivatives2 = fn(i64, f64, f64) -> (f32, [f32; 2]);
+type WithDerivatives3 = fn(i64, f64, f64, f64) -> (f32, [f32; 3]);
+type WithDerivatives4 = fn(i64, f64, f64, f64, f64) -> (f32, [f32; 4]);
+
+/// Deterministic xorshift stream, so failures are reproducible.
+struct Points(u64);
+
+impl Points {
+    fn coordinate(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        let unit = (self.0 >> 11) as f64 / (1u64 << 53) as f64;
+        (unit - 0.5) * 64.0
+    }
+
+    fn point<const D: usize>(&mut self) -> [f64; D] {
+        std::array::from_fn(|_| self.coordinate())
+    }
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() <= TOLERANCE * b.abs().max(1.0)
+}
+
+/// Compares the analytic result against the scalar value and central differences.
+fn check<const D: usize>(
+    name: &str,
+    with_derivatives: impl Fn(i64, [f64; D]) -> (f32, [f32; D]),
+    scalar: impl Fn(i64, [f64; D]) -> f32,
+) {
+    let mut points = Points(0x9E3779B97F4A7C15);
+    let mut seams = 0;
+    let mut total = 0;
+    for seed in SEEDS {
+        for _ in 0..300 {
+            let p = points.point::<D>();
+            let (value, derivatives) = with_derivatives(seed, p);
+            assert_eq!(
+                value.to_bits(),
+                scalar(seed, p).to_bits(),
+                "{} seed {} at {:?}: value differs from the scalar function",
+                name,
+                seed,
+                p
+            );
+
+            for axis in 0..D {
+                let mut forward = p;
+                let mut backward = p;
+                forward[axis] += STEP;
+                backward[axis] -= STEP;
+                let forward = (scalar(seed, forward) - value) as f64 / STEP;
+                let backward = (value - scalar(seed, backward)) as f64 / STEP;
+                let central = ((forward + backward) / 2.0) as f32;
+                let analytic = derivatives[axis];
+                total += 1;
+                if close(analytic, central) {
+                    continue;
+                }
+
+                // The reference lattices leave a few tiny seams in the noise itself. When one
+                // falls inside the step, the difference on the other side still has to agree.
+                assert!(
+                    close(analytic, forward as f32) || close(analytic, backward as f32),
+                    "{} seed {} at {:?}, axis {}: central difference {}, analytic {}",
+                    name,
+                    seed,
+                    p,
+                    axis,
+                    central,
+                    analytic
+                );
+                seams += 1;
+            }
+        }
+    }
+    assert!(
+        seams * 100 < total,
+        "{}: {} of {} samples straddle a seam",
+        name,
+        seams,
+        total
+    );
+}
+
+fn check2(name: &str, with_derivatives: WithDerivatives2, scalar: fn(i64, f64, f64) -> f32) {
+    check::<2>(
+        name,
+        |seed, [x, y]| with_derivatives(seed, x, y),
+        |seed, [x, y]| scalar(seed, x, y),
+    );
+}
+
+fn check3(name: &str, with_derivatives: WithDerivatives3, scalar: fn(i64, f64, f64, f64) -> f32) {
+    check::<3>(
+        name,
+        |seed, [x, y, z]| with_derivatives(seed, x, y, z),
+        |seed, [x, y, z]| scalar(seed, x, y, z),
+    );
+}
+
+fn check4(
+    name: &str,
+    with_derivatives: WithDerivatives4,
+    scalar: fn(i64, f64, f64, f64, f64) -> f32,
+) {
+    check::<4>(
+        name,
+        |seed, [x, y, z, w]| with_derivatives(seed, x, y, z, w),
+        |seed, [x, y, z, w]| scalar(seed, x, y, z, w),
+    );
+}
+
+#[test]
+fn fast_derivatives2_match_central_differences() {
+    check2("fast::noise2", fast::noise2_with_derivatives, fast::noise2);
+    check2(
+        "fast::noise2_ImproveX",
+        fast::noise2_ImproveX_with_derivatives,
+        fast::noise2_ImproveX,
+    );
+}
+
+#[test]
+fn fast_derivatives3_match_central_differences() {
+    check3(
+        "fast::noise3_ImproveXY",
+        fast::noise3_ImproveXY_with_derivatives,
+        fast::noise3_ImproveXY,
+    );
+    check3(
+        "fast::noise3_ImproveXZ",
+        fast::noise3_ImproveXZ_with_derivatives,
+        fast::noise3_ImproveXZ,
+    );
+    check3(
+        "fast::noise3_Fallback",
+        fast::noise3_Fallback_with_derivatives,
+        fast::noise3_Fallback,
+    );
+}
+
+#[test]
+fn fast_derivatives4_match_central_differences() {
+    check4(
+        "fast::noise4_ImproveXYZ_ImproveXY",
+        fast::noise4_ImproveXYZ_ImproveXY_with_derivatives,
+        fast::noise4_ImproveXYZ_ImproveXY,
+    );
+    check4(
+        "fast::noise4_ImproveXYZ_ImproveXZ",
+        fast::noise4_ImproveXYZ_ImproveXZ_with_derivatives,
+        fast::noise4_ImproveXYZ_ImproveXZ,
+    );
+    check4(
+        "fast::noise4_ImproveXYZ",
+        fast::noise4_ImproveXYZ_with_derivatives,
+        fast::noise4_ImproveXYZ,
+    );
+    check4(
+        "fast::noise4_ImproveXY_ImproveZW",
+        fast::noise4_ImproveXY_ImproveZW_with_derivatives,
+        fast::noise4_ImproveXY_ImproveZW,
+    );
+    check4(
+        "fast::noise4_Fallback",
+        fast::noise4_Fallback_with_derivatives,
+        fast::noise4_Fallback,
+    );
+}
+
+#[test]
+fn smooth_derivatives2_match_central_differences() {
+    check2(
+        "smooth::noise2",
+        smooth::noise2_with_derivatives,
+        smooth::noise2,
+    );
+    check2(
+        "smooth::noise2_ImproveX",
+        smooth::noise2_ImproveX_with_derivatives,
+        smooth::noise2_ImproveX,
+    );
+}
+
+#[test]
+fn smooth_derivatives3_match_central_differences() {
+    check3(
+        "smooth::noise3_ImproveXY",
+        smooth::noise3_ImproveXY_with_derivatives,
+        smooth::noise3_ImproveXY,
+    );
+    check3(
+        "smooth::noise3_ImproveXZ",
+        smooth::noise3_ImproveXZ_with_derivatives,
+        smooth::noise3_ImproveXZ,
+    );
+    check3(
+        "smooth::noise3_Fallback",
+        smooth::noise3_Fallback_with_derivatives,
+        smooth::noise3_Fallback,
+    );
+}
+
+#[test]
+fn smooth_derivatives4_match_central_differences() {
+    check4(
+        "smooth::noise4_ImproveXYZ_ImproveXY",
+        smooth::noise4_ImproveXYZ_ImproveXY_with_derivatives,
+        smooth::noise4_ImproveXYZ_ImproveXY,
+    );
+    check4(
+        "smooth::noise4_ImproveXYZ_ImproveXZ",
+        smooth::noise4_ImproveXYZ_ImproveXZ_with_derivatives,
+        smooth::noise4_ImproveXYZ_ImproveXZ,
+    );
+    check4(
+        "smooth::noise4_ImproveXYZ",
+        smooth::noise4_ImproveXYZ_with_derivatives,
+        smooth::noise4_ImproveXYZ,
+    );
+    check4(
+        "smooth::noise4_ImproveXY_ImproveZW",
+        smooth::noise4_ImproveXY_ImproveZW_with_derivatives,
+        smooth::noise4_ImproveXY_ImproveZW,
+    );
+    check4(
+        "smooth::noise4_Fallback",
+        smooth::noise4_Fallback_with_derivatives,
+        smooth::noise4_Fallback,
+    );
+}
@@ -0,0 +1,57 @@
+use terrainopensimplex2::domain_warp::DomainWarp;
+use terrainopensimplex2::noise_source::NoiseSource;
+use terrainopensimplex2::opensimplex2::OpenSimplex2;
+
+fn points() -> impl Iterator<Item = (f64, f64)> {
+    (0..200).map(|i| (i as f64 * 0.29 - 20.0, i as f64 * 0.17 + 3.0))
+}
+
+#[test]
+fn no_levels_leaves_coordinates_alone() {
+    let noise = OpenSimplex2::smooth(40000345266);
+    let warp = DomainWarp::new(0, 7, 4.0, 0.5);
+    for (x, y) in points() {
+        assert_eq!(warp.apply(&noise, x, y, 1.5), (x, y));
+    }
+}
+
+#[test]
+fn offsets_stay_within_strength() {
+    let noise = OpenSimplex2::smooth(40000345266);
+    for levels in 1..=DomainWarp::MAX_LEVELS + 1 {
+        let warp = DomainWarp::new(levels, 7, 2.5, 0.3);
+        let mut moved = false;
+        for (x, y) in points() {
+            let (wx, wy) = warp.apply(&noise, x, y, 1.5);
+            assert!((wx - x).abs() <= 2.5 && (wy - y).abs() <= 2.5);
+            moved |= wx != x || wy != y;
+        }
+        assert!(moved, "{} levels did not warp", levels);
+    }
+}
+
+#[test]
+fn levels_and_seed_change_the_warp() {
+    let noise = OpenSimplex2::smooth(40000345266);
+    let one = DomainWarp::new(1, 7, 2.5, 0.3);
+    let two = DomainWarp { levels: 2, ..one };
+    let reseeded = DomainWarp { seed: 8, ..one };
+    let differs = |a: DomainWarp, b: DomainWarp| {
+        points().any(|(x, y)| a.apply(&noise, x, y, 0.0) != b.apply(&noise, x, y, 0.0))
+    };
+    assert!(differs(one, two));
+    assert!(differs(one, reseeded));
+    assert!(!differs(two, DomainWarp { levels: 5, ..two }));
+}
+
+#[test]
+fn the_warp_uses_its_own_seed() {
+    let noise = OpenSimplex2::smooth(40000345266);
+    let warp = DomainWarp::new(2, 7, 2.5, 0.3);
+    for (x, y) in points() {
+        assert_eq!(
+            warp.apply(&noise, x, y, 0.0),
+            warp.apply(&noise.with_seed(-3), x, y, 0.0)
+        );
+    }
+}
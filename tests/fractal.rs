@@ -0,0 +1,185 @@
+use terrainopensimplex2::fractal::{fractal, FractalType};
+use terrainopensimplex2::noise_source::{NoiseSource, Orientation};
+use terrainopensimplex2::opensimplex2::OpenSimplex2;
+use terrainopensimplex2::smooth;
+
+const SEED: i64 = 40000345266;
+const TYPES: [FractalType; 6] = [
+    FractalType::Fbm,
+    FractalType::Ridged,
+    FractalType::Billow,
+    FractalType::HybridMulti,
+    FractalType::Swiss,
+    FractalType::Jordan,
+];
+
+fn points() -> impl Iterator<Item = [f64; 3]> {
+    (0..400).map(|i| {
+        let i = i as f64;
+        [i * 0.173 - 30.0, i * 0.311 - 60.0, i * 0.05]
+    })
+}
+
+#[test]
+fn every_type_stays_in_unit_range() {
+    let variants = [
+        OpenSimplex2::smooth(SEED),
+        OpenSimplex2::fast(SEED).with_orientation(Orientation::Fallback),
+    ];
+    for noise in variants {
+        for fractal_type in TYPES {
+            for (octaves, gain, lacunarity) in
+                [(1, 0.5, 2.0), (6, 0.5, 2.0), (8, 0.9, 1.9), (4, 0.2, 3.0)]
+            {
+                let mut min = f32::INFINITY;
+                let mut max = f32::NEG_INFINITY;
+                for (point, scale_z) in points().zip([false, true].into_iter().cycle()) {
+                    let value = fractal(
+                        &noise,
+                        fractal_type,
+                        point,
+                        octaves,
+                        gain,
+                        lacunarity,
+                        scale_z,
+                    );
+                    assert!(
+                        (0.0..=1.0).contains(&value),
+                        "{} with {} octaves, gain {}: {} at {:?}",
+                        fractal_type,
+                        octaves,
+                        gain,
+                        value,
+                        point
+                    );
+                    min = min.min(value);
+                    max = max.max(value);
+                }
+                assert!(
+                    max - min > 0.05,
+                    "{} is flat: {}..{}",
+                    fractal_type,
+                    min,
+                    max
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn fbm_matches_the_original_terrain_sum() {
+    let noise = OpenSimplex2::smooth(SEED);
+    let octaves = 5;
+    // 0.4 and 2.0 are the terrain defaults; the original divided by the same sum whatever the gain.
+    for (gain, lacunarity) in [(0.4, 2.0), (0.5, 2.0), (0.3, 2.5)] {
+        for [x, y, z] in points() {
+            let mut height: f32 = 0.0;
+            let mut amplitude: f32 = 1.0;
+            let mut frequency: f64 = 1.0;
+            for _ in 0..octaves {
+                height +=
+                    smooth::noise3_ImproveXZ(SEED, x * frequency, y * frequency, z) * amplitude;
+                amplitude *= gain;
+                frequency *= lacunarity;
+            }
+            let expected = (height / (2.0 * (1.0 - 0.5_f32.powi(octaves))) + 1.0) * 0.5;
+            let value = fractal(
+                &noise,
+                FractalType::Fbm,
+                [x, y, z],
+                octaves,
+                gain,
+                lacunarity,
+                false,
+            );
+            assert_eq!(value, expected, "gain {} at {:?}", gain, [x, y, z]);
+        }
+    }
+}
+
+#[test]
+fn fbm_can_scale_every_axis() {
+    let noise = OpenSimplex2::smooth(SEED);
+    let octaves = 5;
+    for [x, y, z] in points() {
+        let mut height: f32 = 0.0;
+        let mut amplitude: f32 = 1.0;
+        let mut frequency: f64 = 1.0;
+        for _ in 0..octaves {
+            height += smooth::noise3_ImproveXZ(SEED, x * frequency, y * frequency, z * frequency)
+                * amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        let expected = (height / (2.0 * (1.0 - 0.5_f32.powi(octaves))) + 1.0) * 0.5;
+        let value = fractal(&noise, FractalType::Fbm, [x, y, z], octaves, 0.5, 2.0, true);
+        assert!(
+            (value - expected).abs() < 1e-6,
+            "{} and {}",
+            value,
+            expected
+        );
+    }
+}
+
+#[test]
+fn no_octaves_is_flat() {
+    let noise = OpenSimplex2::smooth(SEED);
+    for fractal_type in TYPES {
+        assert_eq!(
+            fractal(&noise, fractal_type, [1.0, 2.0, 3.0], 0, 0.5, 2.0, true),
+            0.5
+        );
+    }
+}
+
+#[test]
+fn default_derivatives_follow_the_samples() {
+    /// Wraps a generator but keeps the trait's finite-difference derivatives.
+    struct Plain(OpenSimplex2);
+
+    impl NoiseSource for Plain {
+        fn seed(&self) -> i64 {
+            self.0.seed()
+        }
+
+        fn with_seed(&self, seed: i64) -> Self {
+            Plain(self.0.with_seed(seed))
+        }
+
+        fn sample2(&self, x: f64, y: f64) -> f32 {
+            self.0.sample2(x, y)
+        }
+
+        fn sample3(&self, x: f64, y: f64, z: f64) -> f32 {
+            self.0.sample3(x, y, z)
+        }
+
+        fn sample4(&self, x: f64, y: f64, z: f64, w: f64) -> f32 {
+            self.0.sample4(x, y, z, w)
+        }
+    }
+
+    let noise = OpenSimplex2::smooth(SEED);
+    let plain = Plain(noise);
+    for [x, y, z] in points().take(50) {
+        let (value, derivatives) = noise.sample3_with_derivatives(x, y, z);
+        let (plain_value, plain_derivatives) = plain.sample3_with_derivatives(x, y, z);
+        assert_eq!(value, plain_value);
+        for (analytic, numeric) in derivatives.iter().zip(plain_derivatives) {
+            assert!((analytic - numeric).abs() < 2e-2 * analytic.abs().max(1.0));
+        }
+    }
+}
+
+#[test]
+fn names_round_trip() {
+    for fractal_type in TYPES {
+        assert_eq!(
+            fractal_type.to_string().parse::<FractalType>(),
+            Ok(fractal_type)
+        );
+    }
+    assert!("fBm".parse::<FractalType>().is_err());
+}
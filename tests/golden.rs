@@ -0,0 +1,394 @@
+//! Bit-exact regression tests against the datasets in `tests/golden`.
+//!
+//! Each row is a seed, the inputs, and the expected f32 outputs as hex bit patterns.
+//! The grid and lane evaluators are checked against the scalar functions in their own tests.
+//!
+//! Only regenerate after a deliberate change to the noise output:
+//! `cargo test --test golden -- --ignored regenerate`
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use terrainopensimplex2::{fast, smooth};
+
+type Evaluate = fn(i64, &[f64]) -> Vec<f32>;
+
+struct Dataset {
+    file: &'static str,
+    inputs: &'static [&'static str],
+    rows: usize,
+    columns: Vec<(&'static str, Evaluate)>,
+}
+
+macro_rules! noise_columns {
+    ($variant:ident) => {
+        vec![
+            (
+                "noise2",
+                (|s, p| vec![$variant::noise2(s, p[0], p[1])]) as Evaluate,
+            ),
+            ("noise2_ImproveX", |s, p| {
+                vec![$variant::noise2_ImproveX(s, p[0], p[1])]
+            }),
+            ("noise3_ImproveXY", |s, p| {
+                vec![$variant::noise3_ImproveXY(s, p[0], p[1], p[2])]
+            }),
+            ("noise3_ImproveXZ", |s, p| {
+                vec![$variant::noise3_ImproveXZ(s, p[0], p[1], p[2])]
+            }),
+            ("noise3_Fallback", |s, p| {
+                vec![$variant::noise3_Fallback(s, p[0], p[1], p[2])]
+            }),
+            ("noise4_ImproveXYZ_ImproveXY", |s, p| {
+                vec![$variant::noise4_ImproveXYZ_ImproveXY(
+                    s, p[0], p[1], p[2], p[3],
+                )]
+            }),
+            ("noise4_ImproveXYZ_ImproveXZ", |s, p| {
+                vec![$variant::noise4_ImproveXYZ_ImproveXZ(
+                    s, p[0], p[1], p[2], p[3],
+                )]
+            }),
+            ("noise4_ImproveXYZ", |s, p| {
+                vec![$variant::noise4_ImproveXYZ(s, p[0], p[1], p[2], p[3])]
+            }),
+            ("noise4_ImproveXY_ImproveZW", |s, p| {
+                vec![$variant::noise4_ImproveXY_ImproveZW(
+                    s, p[0], p[1], p[2], p[3],
+                )]
+            }),
+            ("noise4_Fallback", |s, p| {
+                vec![$variant::noise4_Fallback(s, p[0], p[1], p[2], p[3])]
+            }),
+        ]
+    };
+}
+
+// The values themselves are already pinned by the scalar dataset, so only the gradients are stored.
+macro_rules! derivative_columns {
+    ($variant:ident) => {
+        vec![
+            (
+                "noise2_with_derivatives",
+                (|s, p| $variant::noise2_with_derivatives(s, p[0], p[1]).1.to_vec()) as Evaluate,
+            ),
+            ("noise2_ImproveX_with_derivatives", |s, p| {
+                $variant::noise2_ImproveX_with_derivatives(s, p[0], p[1])
+                    .1
+                    .to_vec()
+            }),
+            ("noise3_ImproveXY_with_derivatives", |s, p| {
+                $variant::noise3_ImproveXY_with_derivatives(s, p[0], p[1], p[2])
+                    .1
+                    .to_vec()
+            }),
+            ("noise3_ImproveXZ_with_derivatives", |s, p| {
+                $variant::noise3_ImproveXZ_with_derivatives(s, p[0], p[1], p[2])
+                    .1
+                    .to_vec()
+            }),
+            ("noise3_Fallback_with_derivatives", |s, p| {
+                $variant::noise3_Fallback_with_derivatives(s, p[0], p[1], p[2])
+                    .1
+                    .to_vec()
+            }),
+            ("noise4_ImproveXYZ_ImproveXY_with_derivatives", |s, p| {
+                $variant::noise4_ImproveXYZ_ImproveXY_with_derivatives(s, p[0], p[1], p[2], p[3])
+                    .1
+                    .to_vec()
+            }),
+            ("noise4_ImproveXYZ_ImproveXZ_with_derivatives", |s, p| {
+                $variant::noise4_ImproveXYZ_ImproveXZ_with_derivatives(s, p[0], p[1], p[2], p[3])
+                    .1
+                    .to_vec()
+            }),
+            ("noise4_ImproveXYZ_with_derivatives", |s, p| {
+                $variant::noise4_ImproveXYZ_with_derivatives(s, p[0], p[1], p[2], p[3])
+                    .1
+                    .to_vec()
+            }),
+            ("noise4_ImproveXY_ImproveZW_with_derivatives", |s, p| {
+                $variant::noise4_ImproveXY_ImproveZW_with_derivatives(s, p[0], p[1], p[2], p[3])
+                    .1
+                    .to_vec()
+            }),
+            ("noise4_Fallback_with_derivatives", |s, p| {
+                $variant::noise4_Fallback_with_derivatives(s, p[0], p[1], p[2], p[3])
+                    .1
+                    .to_vec()
+            }),
+        ]
+    };
+}
+
+macro_rules! periodic_columns {
+    ($variant:ident) => {
+        vec![
+            (
+                "noise2_Periodic",
+                (|s, p| {
+                    vec![$variant::noise2_Periodic(
+                        s,
+                        p[0],
+                        p[1],
+                        [p[3] as u32, p[4] as u32],
+                    )]
+                }) as Evaluate,
+            ),
+            ("noise3_Periodic", |s, p| {
+                vec![$variant::noise3_Periodic(
+                    s,
+                    p[0],
+                    p[1],
+                    p[2],
+                    [p[3] as u32, p[4] as u32, p[5] as u32],
+                )]
+            }),
+        ]
+    };
+}
+
+const NOISE_INPUTS: &[&str] = &["x", "y", "z", "w"];
+const PERIODIC_INPUTS: &[&str] = &["x", "y", "z", "px", "py", "pz"];
+
+fn datasets() -> Vec<Dataset> {
+    vec![
+        Dataset {
+            file: "fast_noise.txt",
+            inputs: NOISE_INPUTS,
+            rows: 2048,
+            columns: noise_columns!(fast),
+        },
+        Dataset {
+            file: "smooth_noise.txt",
+            inputs: NOISE_INPUTS,
+            rows: 2048,
+            columns: noise_columns!(smooth),
+        },
+        Dataset {
+            file: "fast_derivatives.txt",
+            inputs: NOISE_INPUTS,
+            rows: 256,
+            columns: derivative_columns!(fast),
+        },
+        Dataset {
+            file: "smooth_derivatives.txt",
+            inputs: NOISE_INPUTS,
+            rows: 256,
+            columns: derivative_columns!(smooth),
+        },
+        Dataset {
+            file: "fast_periodic.txt",
+            inputs: PERIODIC_INPUTS,
+            rows: 512,
+            columns: periodic_columns!(fast),
+        },
+        Dataset {
+            file: "smooth_periodic.txt",
+            inputs: PERIODIC_INPUTS,
+            rows: 512,
+            columns: periodic_columns!(smooth),
+        },
+    ]
+}
+
+fn path(file: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(file)
+}
+
+/// Deterministic xorshift stream, so the datasets can be regenerated identically.
+struct Stream(u64);
+
+impl Stream {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn seed(&mut self) -> i64 {
+        match self.below(8) {
+            0 => [0, 1, -1, i64::MAX, i64::MIN][self.below(5) as usize],
+            1..=3 => self.below(2_000_001) as i64 - 1_000_000,
+            _ => self.next() as i64,
+        }
+    }
+
+    /// Multiples of 1/64, which are exact in binary and print compactly.
+    fn coordinate(&mut self) -> f64 {
+        let range: f64 = match self.below(16) {
+            // Exactly on lattice vertices and half-way between them.
+            0 => return (self.below(64) as f64 - 32.0) * 0.5,
+            1 => 1.0,
+            2..=10 => 256.0,
+            11..=13 => 65536.0,
+            // Lattice indices are i32 as in the Java original, and the skewed 4D coordinates
+            // overflow them well before 1e9.
+            _ => 1.0e8,
+        };
+        let steps = (range * 64.0) as u64;
+        (self.below(2 * steps + 1) as f64 - steps as f64) / 64.0
+    }
+
+    fn period(&mut self) -> f64 {
+        [1, 2, 3, 5, 8, 16, 64, 1000][self.below(8) as usize] as f64
+    }
+}
+
+fn generate(dataset: &Dataset) -> String {
+    let mut stream = Stream(0x2545F4914F6CDD1D ^ dataset.file.len() as u64);
+    let mut text = format!("# seed {} |", dataset.inputs.join(" "));
+    for (name, _) in &dataset.columns {
+        write!(text, " {}", name).unwrap();
+    }
+    text.push('\n');
+
+    for _ in 0..dataset.rows {
+        let seed = stream.seed();
+        let inputs: Vec<f64> = dataset
+            .inputs
+            .iter()
+            .map(|input| {
+                if input.starts_with('p') {
+                    stream.period()
+                } else {
+                    stream.coordinate()
+                }
+            })
+            .collect();
+        write!(text, "{}", seed).unwrap();
+        for input in &inputs {
+            write!(text, " {:?}", input).unwrap();
+        }
+        text.push_str(" |");
+        for (_, evaluate) in &dataset.columns {
+            for value in evaluate(seed, &inputs) {
+                write!(text, " {:08x}", value.to_bits()).unwrap();
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn check(dataset: &Dataset) {
+    let text = fs::read_to_string(path(dataset.file)).unwrap();
+    let mut rows = 0;
+    let mut mismatches = Vec::new();
+    for (line_number, line) in text.lines().enumerate().skip(1) {
+        let (inputs, expected) = line.split_once(" | ").unwrap();
+        let mut inputs = inputs.split(' ');
+        let seed: i64 = inputs.next().unwrap().parse().unwrap();
+        let inputs: Vec<f64> = inputs.map(|input| input.parse().unwrap()).collect();
+        assert_eq!(
+            inputs.len(),
+            dataset.inputs.len(),
+            "line {}",
+            line_number + 1
+        );
+        let mut expected = expected.split(' ');
+
+        for (name, evaluate) in &dataset.columns {
+            for (component, actual) in evaluate(seed, &inputs).into_iter().enumerate() {
+                let bits = u32::from_str_radix(expected.next().unwrap(), 16).unwrap();
+                if actual.to_bits() != bits {
+                    mismatches.push(format!(
+                        "{} line {}: {}[{}](seed {}, {:?}) expected {} ({:08x}), got {} ({:08x})",
+                        dataset.file,
+                        line_number + 1,
+                        name,
+                        component,
+                        seed,
+                        inputs,
+                        f32::from_bits(bits),
+                        bits,
+                        actual,
+                        actual.to_bits()
+                    ));
+                }
+            }
+        }
+        assert!(
+            expected.next().is_none(),
+            "line {} has extra values",
+            line_number + 1
+        );
+        rows += 1;
+    }
+
+    assert_eq!(rows, dataset.rows, "{} is truncated", dataset.file);
+    assert!(
+        mismatches.is_empty(),
+        "{} of the golden values changed, first ones:\n{}",
+        mismatches.len(),
+        mismatches[..mismatches.len().min(10)].join("\n")
+    );
+}
+
+fn check_file(file: &str) {
+    let dataset = datasets().into_iter().find(|d| d.file == file).unwrap();
+    check(&dataset);
+}
+
+#[test]
+fn fast_noise_matches_golden_values() {
+    check_file("fast_noise.txt");
+}
+
+#[test]
+fn smooth_noise_matches_golden_values() {
+    check_file("smooth_noise.txt");
+}
+
+#[test]
+fn fast_derivatives_match_golden_values() {
+    check_file("fast_derivatives.txt");
+}
+
+#[test]
+fn smooth_derivatives_match_golden_values() {
+    check_file("smooth_derivatives.txt");
+}
+
+#[test]
+fn fast_periodic_noise_matches_golden_values() {
+    check_file("fast_periodic.txt");
+}
+
+#[test]
+fn smooth_periodic_noise_matches_golden_values() {
+    check_file("smooth_periodic.txt");
+}
+
+#[test]
+fn golden_inputs_are_reproducible() {
+    // Guards the generator itself, so a regenerated file only differs where the noise does.
+    for dataset in datasets() {
+        let text = fs::read_to_string(path(dataset.file)).unwrap();
+        for (stored, generated) in text.lines().zip(generate(&dataset).lines()) {
+            assert_eq!(
+                stored.split_once(" | ").map(|(inputs, _)| inputs),
+                generated.split_once(" | ").map(|(inputs, _)| inputs),
+                "{}",
+                dataset.file
+            );
+        }
+    }
+}
+
+#[test]
+#[ignore]
+fn regenerate() {
+    fs::create_dir_all(path("")).unwrap();
+    for dataset in datasets() {
+        fs::write(path(dataset.file), generate(&dataset)).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+use terrainopensimplex2::{fast, smooth};
+
+const SEEDS: [i64; 4] = [0, 42, -7_340_032_993, 40000345266];
+
+type Grid2 = fn(i64, [f64; 2], [f64; 2], [usize; 2], &mut [f32]);
+type Grid3 = fn(i64, [f64; 3], [f64; 3], [usize; 3], &mut [f32]);
+type Grid4 = fn(i64, [f64; 4], [f64; 4], [usize; 4], &mut [f32]);
+
+fn axis(origin: f64, step: f64, i: usize) -> f64 {
+    origin + i as f64 * step
+}
+
+fn assert_bits(expected: f32, actual: f32, context: &str) {
+    assert_eq!(
+        expected.to_bits(),
+        actual.to_bits(),
+        "{}: expected {}, got {}",
+        context,
+        expected,
+        actual
+    );
+}
+
+fn check_grid2(
+    name: &str,
+    grid: Grid2,
+    scalar: fn(i64, f64, f64) -> f32,
+) {
+    let origin = [-13.37, 4.2];
+    let size = [23, 17];
+    for step in [[0.173, -0.291], [0.013, 0.021]] {
+        for seed in SEEDS {
+            let mut out = vec![0.0; size[0] * size[1]];
+            grid(seed, origin, step, size, &mut out);
+            for j in 0..size[1] {
+                for i in 0..size[0] {
+                    let expected = scalar(
+                        seed,
+                        axis(origin[0], step[0], i),
+                        axis(origin[1], step[1], j),
+                    );
+                    let context = format!("{} seed {} at ({}, {})", name, seed, i, j);
+                    assert_bits(expected, out[j * size[0] + i], &context);
+                }
+            }
+        }
+    }
+}
+
+fn check_grid3(
+    name: &str,
+    grid: Grid3,
+    scalar: fn(i64, f64, f64, f64) -> f32,
+) {
+    let origin = [-2.5, 31.75, -1e4];
+    let size = [11, 7, 5];
+    for step in [[0.37, 0.11, -0.53], [0.021, -0.013, 0.017]] {
+        for seed in SEEDS {
+            let mut out = vec![0.0; size[0] * size[1] * size[2]];
+            grid(seed, origin, step, size, &mut out);
+            for k in 0..size[2] {
+                for j in 0..size[1] {
+                    for i in 0..size[0] {
+                        let expected = scalar(
+                            seed,
+                            axis(origin[0], step[0], i),
+                            axis(origin[1], step[1], j),
+                            axis(origin[2], step[2], k),
+                        );
+                        let context = format!("{} seed {} at ({}, {}, {})", name, seed, i, j, k);
+                        assert_bits(expected, out[(k * size[1] + j) * size[0] + i], &context);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn check_grid4(
+    name: &str,
+    grid: Grid4,
+    scalar: fn(i64, f64, f64, f64, f64) -> f32,
+) {
+    let origin = [0.5, -8.125, 77.0, -0.03];
+    let step = [0.41, 0.29, -0.17, 0.61];
+    let size = [7, 5, 4, 3];
+    for seed in SEEDS {
+        let mut out = vec![0.0; size.iter().product()];
+        grid(seed, origin, step, size, &mut out);
+        for l in 0..size[3] {
+            for k in 0..size[2] {
+                for j in 0..size[1] {
+                    for i in 0..size[0] {
+                        let expected = scalar(
+                            seed,
+                            axis(origin[0], step[0], i),
+                            axis(origin[1], step[1], j),
+                            axis(origin[2], step[2], k),
+                            axis(origin[3], step[3], l),
+                        );
+                        let index = ((l * size[2] + k) * size[1] + j) * size[0] + i;
+                        let context =
+                            format!("{} seed {} at ({}, {}, {}, {})", name, seed, i, j, k, l);
+                        assert_bits(expected, out[index], &context);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn fast_grid2_matches_scalar() {
+    check_grid2("fast::noise2", fast::noise2_Grid, fast::noise2);
+    check_grid2(
+        "fast::noise2_ImproveX",
+        fast::noise2_ImproveX_Grid,
+        fast::noise2_ImproveX,
+    );
+}
+
+#[test]
+fn fast_grid3_matches_scalar() {
+    check_grid3(
+        "fast::noise3_ImproveXY",
+        fast::noise3_ImproveXY_Grid,
+        fast::noise3_ImproveXY,
+    );
+    check_grid3(
+        "fast::noise3_ImproveXZ",
+        fast::noise3_ImproveXZ_Grid,
+        fast::noise3_ImproveXZ,
+    );
+    check_grid3(
+        "fast::noise3_Fallback",
+        fast::noise3_Fallback_Grid,
+        fast::noise3_Fallback,
+    );
+}
+
+#[test]
+fn fast_grid4_matches_scalar() {
+    check_grid4(
+        "fast::noise4_ImproveXYZ_ImproveXY",
+        fast::noise4_ImproveXYZ_ImproveXY_Grid,
+        fast::noise4_ImproveXYZ_ImproveXY,
+    );
+    check_grid4(
+        "fast::noise4_ImproveXYZ_ImproveXZ",
+        fast::noise4_ImproveXYZ_ImproveXZ_Grid,
+        fast::noise4_ImproveXYZ_ImproveXZ,
+    );
+    check_grid4(
+        "fast::noise4_ImproveXYZ",
+        fast::noise4_ImproveXYZ_Grid,
+        fast::noise4_ImproveXYZ,
+    );
+    check_grid4(
+        "fast::noise4_ImproveXY_ImproveZW",
+        fast::noise4_ImproveXY_ImproveZW_Grid,
+        fast::noise4_ImproveXY_ImproveZW,
+    );
+    check_grid4(
+        "fast::noise4_Fallback",
+        fast::noise4_Fallback_Grid,
+        fast::noise4_Fallback,
+    );
+}
+
+#[test]
+fn smooth_grid2_matches_scalar() {
+    check_grid2("smooth::noise2", smooth::noise2_Grid, smooth::noise2);
+    check_grid2(
+        "smooth::noise2_ImproveX",
+        smooth::noise2_ImproveX_Grid,
+        smooth::noise2_ImproveX,
+    );
+}
+
+#[test]
+fn smooth_grid3_matches_scalar() {
+    check_grid3(
+        "smooth::noise3_ImproveXY",
+        smooth::noise3_ImproveXY_Grid,
+        smooth::noise3_ImproveXY,
+    );
+    check_grid3(
+        "smooth::noise3_ImproveXZ",
+        smooth::noise3_ImproveXZ_Grid,
+        smooth::noise3_ImproveXZ,
+    );
+    check_grid3(
+        "smooth::noise3_Fallback",
+        smooth::noise3_Fallback_Grid,
+        smooth::noise3_Fallback,
+    );
+}
+
+#[test]
+fn smooth_grid4_matches_scalar() {
+    check_grid4(
+        "smooth::noise4_ImproveXYZ_ImproveXY",
+        smooth::noise4_ImproveXYZ_ImproveXY_Grid,
+        smooth::noise4_ImproveXYZ_ImproveXY,
+    );
+    check_grid4(
+        "smooth::noise4_ImproveXYZ_ImproveXZ",
+        smooth::noise4_ImproveXYZ_ImproveXZ_Grid,
+        smooth::noise4_ImproveXYZ_ImproveXZ,
+    );
+    check_grid4(
+        "smooth::noise4_ImproveXYZ",
+        smooth::noise4_ImproveXYZ_Grid,
+        smooth::noise4_ImproveXYZ,
+    );
+    check_grid4(
+        "smooth::noise4_ImproveXY_ImproveZW",
+        smooth::noise4_ImproveXY_ImproveZW_Grid,
+        smooth::noise4_ImproveXY_ImproveZW,
+    );
+    check_grid4(
+        "smooth::noise4_Fallback",
+        smooth::noise4_Fallback_Grid,
+        smooth::noise4_Fallback,
+    );
+}
+
+#[test]
+fn empty_grid_is_accepted() {
+    let mut out: Vec<f32> = Vec::new();
+    fast::noise3_ImproveXZ_Grid(1, [0.0; 3], [1.0; 3], [4, 0, 3], &mut out);
+    smooth::noise2_Grid(1, [0.0; 2], [1.0; 2], [0, 0], &mut out);
+}
+
+#[test]
+#[should_panic(expected = "output buffer length must match the grid size")]
+fn wrong_buffer_length_panics() {
+    let mut out = vec![0.0; 5];
+    smooth::noise2_Grid(1, [0.0; 2], [1.0; 2], [2, 2], &mut out);
+}
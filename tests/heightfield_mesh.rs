@@ -0,0 +1,136 @@
+use terrainopensimplex2::color_ramp::Palette;
+use terrainopensimplex2::configuration::ConfigurationMessage;
+use terrainopensimplex2::heightfield_mesh::heightfield_mesh;
+use terrainopensimplex2::heightmap::Heightmap;
+use terrainopensimplex2::mesh_mode::MeshMode;
+use terrainopensimplex2::progress::Progress;
+use terrainopensimplex2::terrain_configuration::{
+    generate_heightmap, terrain_mesh, update_configuration, TerrainConfiguration,
+};
+use three_d::{CpuMesh, Indices, InnerSpace, Positions, Vec3};
+
+const COLOR: [u8; 3] = [0x30, 0x46, 0x30];
+
+fn buffers(mesh: &CpuMesh) -> (&[Vec3], &[u32], &[Vec3]) {
+    let (Positions::F32(positions), Indices::U32(indices), Some(normals)) =
+        (&mesh.positions, &mesh.indices, &mesh.normals)
+    else {
+        panic!("the heightfield is built with f32 positions, u32 indices and normals");
+    };
+    (positions, indices, normals)
+}
+
+fn face_normal(positions: &[Vec3], triangle: &[u32]) -> Vec3 {
+    let [a, b, c] = [0, 1, 2].map(|k| positions[triangle[k] as usize]);
+    (b - a).cross(c - a)
+}
+
+// Height rising along the width only, one unit per unit
+fn ramp(width: usize, depth: usize) -> Heightmap {
+    let heights = (0..depth)
+        .flat_map(|_| (0..width).map(|i| 1.0 + 0.5 * i as f32))
+        .collect();
+    Heightmap::new(width, depth, 0.5, heights, 0)
+}
+
+#[test]
+fn surface_passes_through_every_sample() {
+    let heightmap = ramp(4, 3);
+    let mesh =
+        heightfield_mesh(&heightmap, &Palette::shaded(COLOR), false, &Progress::new()).unwrap();
+    let (positions, indices, normals) = buffers(&mesh);
+
+    assert_eq!(positions.len(), 4 * 3);
+    assert_eq!(indices.len(), 3 * 2 * (3 * 2));
+    let xs = heightmap.x_positions();
+    let zs = heightmap.z_positions();
+    for j in 0..3 {
+        for i in 0..4 {
+            let position = positions[i + 4 * j];
+            assert_eq!(
+                [position.x, position.y, position.z],
+                [xs[i], heightmap.get(i, j), zs[j]]
+            );
+        }
+    }
+
+    // A plane rising one unit per unit along x tilts every normal 45 degrees towards -x
+    for normal in normals {
+        assert!((normal.magnitude() - 1.0).abs() < 1e-6);
+        assert!(
+            (normal.x + normal.y).abs() < 1e-6 && normal.z == 0.0,
+            "{:?}",
+            normal
+        );
+    }
+    for triangle in indices.chunks(3) {
+        assert!(face_normal(positions, triangle).y > 0.0);
+    }
+}
+
+#[test]
+fn skirts_close_the_sides_down_to_the_ground() {
+    let heightmap = ramp(4, 3);
+    let mesh =
+        heightfield_mesh(&heightmap, &Palette::shaded(COLOR), true, &Progress::new()).unwrap();
+    let (positions, indices, normals) = buffers(&mesh);
+
+    // Four vertices and two triangles for each of the 10 border edges
+    let border_edges = 2 * (4 - 1) + 2 * (3 - 1);
+    assert_eq!(positions.len(), 4 * 3 + 4 * border_edges);
+    assert_eq!(indices.len(), 3 * (2 * 3 * 2 + 2 * border_edges));
+
+    let centre = Vec3::new(0.75, 0.0, 0.5);
+    for triangle in indices[3 * 2 * 3 * 2..].chunks(3) {
+        let normal = face_normal(positions, triangle);
+        let corner = positions[triangle[0] as usize];
+        let outwards = Vec3::new(corner.x - centre.x, 0.0, corner.z - centre.z);
+        assert!(normal.y.abs() < 1e-6 && normal.dot(outwards) > 0.0);
+        assert!(triangle.iter().any(|&k| positions[k as usize].y == 0.0));
+        for &k in triangle {
+            assert_eq!(normals[k as usize], normal.normalize());
+        }
+    }
+}
+
+#[test]
+fn mode_selects_the_mesh() {
+    let terrain_configuration = TerrainConfiguration::new(
+        2.0,
+        2.0,
+        40000345266,
+        0.1,
+        "304630".to_string(),
+        2.0,
+        200.0,
+        21.0,
+        8,
+        0.4,
+        1.0,
+    );
+    let heightmap = generate_heightmap(&terrain_configuration);
+    // Voxel quads have four vertices of their own
+    assert_ne!(
+        terrain_mesh(&heightmap, &terrain_configuration).vertex_count(),
+        20 * 20
+    );
+
+    let terrain_configuration = update_configuration(
+        terrain_configuration,
+        Some(ConfigurationMessage::TerrainMode(MeshMode::Heightfield)),
+    );
+    let terrain_configuration = update_configuration(
+        terrain_configuration,
+        Some(ConfigurationMessage::TerrainSkirts(false)),
+    );
+    let mesh = terrain_mesh(&heightmap, &terrain_configuration);
+    assert_eq!(mesh.vertex_count(), 20 * 20);
+    assert_eq!(mesh.indices.len(), Some(6 * 19 * 19));
+}
+
+#[test]
+fn modes_parse_from_the_rest_values() {
+    assert_eq!("voxels".parse(), Ok(MeshMode::Voxels));
+    assert_eq!("heightfield".parse(), Ok(MeshMode::Heightfield));
+    assert!("cubes".parse::<MeshMode>().is_err());
+}
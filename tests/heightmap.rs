@@ -0,0 +1,82 @@
+use terrainopensimplex2::heightmap::Heightmap;
+use terrainopensimplex2::terrain_configuration::{
+    generate_heightmap, terrain_mesh, terrain_size, TerrainConfiguration,
+};
+
+fn configuration() -> TerrainConfiguration {
+    TerrainConfiguration::new(
+        2.0,
+        1.5,
+        40000345266,
+        0.1,
+        "304630".to_string(),
+        2.0,
+        200.0,
+        21.0,
+        8,
+        0.4,
+        1.0,
+    )
+}
+
+#[test]
+fn generation_is_pure() {
+    assert_eq!(
+        generate_heightmap(&configuration()),
+        generate_heightmap(&configuration())
+    );
+}
+
+#[test]
+fn heightmap_covers_every_column() {
+    let terrain_configuration = configuration();
+    let heightmap = generate_heightmap(&terrain_configuration);
+
+    assert_eq!((heightmap.width, heightmap.depth), (20, 15));
+    assert_eq!(
+        (heightmap.width, heightmap.depth),
+        terrain_size(&terrain_configuration)
+    );
+    assert_eq!(heightmap.heights.len(), heightmap.width * heightmap.depth);
+    assert_eq!(heightmap.cell_size, 0.1);
+    assert_eq!(heightmap.seed, 40000345266);
+
+    // Every column stands at least one cube tall and at most the height scale above that
+    let min = heightmap.min_height().unwrap();
+    let max = heightmap.max_height().unwrap();
+    assert!(min >= 0.1, "{}", min);
+    assert!(max <= 2.1 + 1e-4, "{}", max);
+    assert!(min < max);
+}
+
+#[test]
+fn heights_are_stored_width_fastest() {
+    let heightmap = Heightmap::new(3, 2, 0.5, vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0], 7);
+
+    assert_eq!(heightmap.get(2, 0), 2.0);
+    assert_eq!(heightmap.get(0, 1), 3.0);
+    assert_eq!(heightmap.get(2, 1), 5.0);
+    assert_eq!(heightmap.x_positions(), vec![0.0, 0.5, 1.0]);
+    assert_eq!(heightmap.z_positions(), vec![0.0, 0.5]);
+    assert_eq!(heightmap.min_height(), Some(0.0));
+    assert_eq!(heightmap.max_height(), Some(5.0));
+}
+
+#[test]
+#[should_panic(expected = "one height per column")]
+fn heights_must_match_the_grid() {
+    Heightmap::new(3, 2, 0.5, vec![0.0; 5], 7);
+}
+
+#[test]
+fn mesh_stacks_cubes_from_the_heightmap() {
+    let terrain_configuration = configuration();
+    let heightmap = Heightmap::new(2, 1, 0.1, vec![0.35, 0.05], 0);
+    let mesh = terrain_mesh(&heightmap, &terrain_configuration);
+
+    // 0.35 is two whole cubes and a partial top, each shaded differently, so its sides keep
+    // three quads each; 0.05 is only a partial top and shows no side towards the taller column
+    let quads = 14 + 5;
+    assert_eq!(mesh.vertex_count(), 4 * quads);
+    assert_eq!(mesh.indices.len(), Some(6 * quads));
+}
@@ -0,0 +1,160 @@
+use terrainopensimplex2::{fast, smooth};
+
+const EPSILON: f32 = 1e-6;
+const SEEDS: [i64; 4] = [0, 1234, -987_654_321_012, i64::MAX];
+
+/// Deterministic xorshift stream, so failures are reproducible.
+struct Points(u64);
+
+impl Points {
+    fn coordinate(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        let unit = (self.0 >> 11) as f64 / (1u64 << 53) as f64;
+        // Mostly terrain-sized coordinates, with some far from the origin.
+        if self.0.is_multiple_of(8) {
+            (unit - 0.5) * 2.0e6
+        } else {
+            (unit - 0.5) * 200.0
+        }
+    }
+
+    fn lanes<const N: usize>(&mut self) -> [f64; N] {
+        std::array::from_fn(|_| self.coordinate())
+    }
+}
+
+fn assert_lanes<const N: usize>(name: &str, lanes: [f32; N], scalar: impl Fn(usize) -> f32) {
+    for (l, &value) in lanes.iter().enumerate() {
+        let expected = scalar(l);
+        assert!(
+            (value - expected).abs() <= EPSILON,
+            "{} lane {} of {}: expected {}, got {}",
+            name,
+            l,
+            N,
+            expected,
+            value
+        );
+    }
+}
+
+type Lanes2<const N: usize> = fn(i64, [f64; N], [f64; N]) -> [f32; N];
+type Lanes3<const N: usize> = fn(i64, [f64; N], [f64; N], [f64; N]) -> [f32; N];
+
+fn check_lanes2<const N: usize>(name: &str, lanes: Lanes2<N>, scalar: fn(i64, f64, f64) -> f32) {
+    let mut points = Points(0x9E3779B97F4A7C15);
+    for seed in SEEDS {
+        for _ in 0..500 {
+            let x = points.lanes::<N>();
+            let y = points.lanes::<N>();
+            assert_lanes(name, lanes(seed, x, y), |l| scalar(seed, x[l], y[l]));
+        }
+    }
+}
+
+fn check_lanes3<const N: usize>(
+    name: &str,
+    lanes: Lanes3<N>,
+    scalar: fn(i64, f64, f64, f64) -> f32,
+) {
+    let mut points = Points(0xD1B54A32D192ED03);
+    for seed in SEEDS {
+        for _ in 0..500 {
+            let x = points.lanes::<N>();
+            let y = points.lanes::<N>();
+            let z = points.lanes::<N>();
+            assert_lanes(name, lanes(seed, x, y, z), |l| {
+                scalar(seed, x[l], y[l], z[l])
+            });
+        }
+    }
+}
+
+#[test]
+fn fast_lanes2_match_scalar() {
+    check_lanes2::<4>("fast::noise2", fast::noise2_Lanes, fast::noise2);
+    check_lanes2::<8>("fast::noise2", fast::noise2_Lanes, fast::noise2);
+    check_lanes2::<4>(
+        "fast::noise2_ImproveX",
+        fast::noise2_ImproveX_Lanes,
+        fast::noise2_ImproveX,
+    );
+    check_lanes2::<8>(
+        "fast::noise2_ImproveX",
+        fast::noise2_ImproveX_Lanes,
+        fast::noise2_ImproveX,
+    );
+}
+
+#[test]
+fn fast_lanes3_match_scalar() {
+    check_lanes3::<4>(
+        "fast::noise3_ImproveXY",
+        fast::noise3_ImproveXY_Lanes,
+        fast::noise3_ImproveXY,
+    );
+    check_lanes3::<8>(
+        "fast::noise3_ImproveXY",
+        fast::noise3_ImproveXY_Lanes,
+        fast::noise3_ImproveXY,
+    );
+    check_lanes3::<4>(
+        "fast::noise3_ImproveXZ",
+        fast::noise3_ImproveXZ_Lanes,
+        fast::noise3_ImproveXZ,
+    );
+    check_lanes3::<8>(
+        "fast::noise3_ImproveXZ",
+        fast::noise3_ImproveXZ_Lanes,
+        fast::noise3_ImproveXZ,
+    );
+    check_lanes3::<4>(
+        "fast::noise3_Fallback",
+        fast::noise3_Fallback_Lanes,
+        fast::noise3_Fallback,
+    );
+    check_lanes3::<8>(
+        "fast::noise3_Fallback",
+        fast::noise3_Fallback_Lanes,
+        fast::noise3_Fallback,
+    );
+}
+
+#[test]
+fn smooth_lanes2_match_scalar() {
+    check_lanes2::<4>("smooth::noise2", smooth::noise2_Lanes, smooth::noise2);
+    check_lanes2::<8>("smooth::noise2", smooth::noise2_Lanes, smooth::noise2);
+    check_lanes2::<4>(
+        "smooth::noise2_ImproveX",
+        smooth::noise2_ImproveX_Lanes,
+        smooth::noise2_ImproveX,
+    );
+    check_lanes2::<8>(
+        "smooth::noise2_ImproveX",
+        smooth::noise2_ImproveX_Lanes,
+        smooth::noise2_ImproveX,
+    );
+}
+
+#[test]
+fn lattice_points_match_scalar() {
+    // Integer and half-integer inputs hit the tie-breaking comparisons.
+    let x = [0.0, 0.5, -0.5, 1.0, -1.0, 2.5, -3.5, 1e3];
+    let y = [0.0, -0.5, 0.5, 1.0, 2.0, -2.5, 3.5, -1e3];
+    let z = [0.0, 0.5, 0.5, -1.0, 0.0, 1.5, -0.5, 0.25];
+    for seed in SEEDS {
+        assert_lanes("fast::noise2", fast::noise2_Lanes(seed, x, y), |l| {
+            fast::noise2(seed, x[l], y[l])
+        });
+        assert_lanes("smooth::noise2", smooth::noise2_Lanes(seed, x, y), |l| {
+            smooth::noise2(seed, x[l], y[l])
+        });
+        assert_lanes(
+            "fast::noise3_Fallback",
+            fast::noise3_Fallback_Lanes(seed, x, y, z),
+            |l| fast::noise3_Fallback(seed, x[l], y[l], z[l]),
+        );
+    }
+}
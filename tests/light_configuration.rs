@@ -0,0 +1,32 @@
+use terrainopensimplex2::configuration::ConfigurationMessage;
+use terrainopensimplex2::light_configuration::{update_configuration, LightConfiguration};
+use three_d::vec3;
+
+fn light(x: f32, y: f32, z: f32) -> LightConfiguration {
+    LightConfiguration::new(x, y, z, "fff2d9".to_string(), 2.5, 0.4)
+}
+
+#[test]
+fn direction_follows_each_axis() {
+    let light = update_configuration(
+        light(-1.0, -2.0, -0.5),
+        Some(ConfigurationMessage::LightDirectionZ(0.3)),
+    );
+    assert_eq!(light.direction(), vec3(-1.0, -2.0, 0.3));
+}
+
+#[test]
+fn a_zero_direction_keeps_the_previous_one() {
+    let mut light = light(0.0, -1.0, 0.0);
+    light = update_configuration(light, Some(ConfigurationMessage::LightDirectionY(0.0)));
+    assert_eq!(light.direction(), vec3(0.0, -1.0, 0.0));
+
+    // The zero direction was only on the way to the next axis update
+    light = update_configuration(light, Some(ConfigurationMessage::LightDirectionZ(-1.0)));
+    assert_eq!(light.direction(), vec3(0.0, 0.0, -1.0));
+}
+
+#[test]
+fn a_zero_initial_direction_points_down() {
+    assert_eq!(light(0.0, 0.0, 0.0).direction(), vec3(0.0, -1.0, 0.0));
+}
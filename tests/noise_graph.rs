@@ -0,0 +1,291 @@
+use terrainopensimplex2::fractal::FractalType;
+use terrainopensimplex2::noise_graph::NoiseModule;
+use terrainopensimplex2::noise_source::NoiseSource;
+use terrainopensimplex2::opensimplex2::OpenSimplex2;
+use terrainopensimplex2::smooth;
+
+const SEED: i64 = 40000345266;
+
+/// Returns the x coordinate, so module arithmetic can be checked exactly.
+struct Ramp;
+
+impl NoiseSource for Ramp {
+    fn seed(&self) -> i64 {
+        0
+    }
+
+    fn with_seed(&self, _seed: i64) -> Self {
+        Ramp
+    }
+
+    fn sample2(&self, x: f64, _y: f64) -> f32 {
+        x as f32
+    }
+
+    fn sample3(&self, x: f64, _y: f64, _z: f64) -> f32 {
+        x as f32
+    }
+
+    fn sample4(&self, x: f64, _y: f64, _z: f64, _w: f64) -> f32 {
+        x as f32
+    }
+}
+
+fn ramp() -> Box<NoiseModule> {
+    Box::new(NoiseModule::Noise {
+        frequency: 1.0,
+        seed_offset: 0,
+    })
+}
+
+fn constant(value: f32) -> NoiseModule {
+    NoiseModule::Constant { value }
+}
+
+fn at(module: &NoiseModule, x: f64) -> f32 {
+    module.get(&Ramp, x, 0.0, 0.0)
+}
+
+#[test]
+fn combiners_apply_to_every_source() {
+    let sources = vec![constant(2.0), *ramp(), constant(-1.0)];
+    let add = NoiseModule::Add {
+        sources: sources.clone(),
+    };
+    let multiply = NoiseModule::Multiply {
+        sources: sources.clone(),
+    };
+    let min = NoiseModule::Min {
+        sources: sources.clone(),
+    };
+    let max = NoiseModule::Max { sources };
+    assert_eq!(at(&add, 0.5), 1.5);
+    assert_eq!(at(&multiply, 0.5), -1.0);
+    assert_eq!(at(&min, 0.5), -1.0);
+    assert_eq!(at(&max, 3.0), 3.0);
+}
+
+#[test]
+fn modifiers_transform_the_source() {
+    let clamp = NoiseModule::Clamp {
+        source: ramp(),
+        lower: -0.5,
+        upper: 0.5,
+    };
+    let scale_bias = NoiseModule::ScaleBias {
+        source: ramp(),
+        scale: 0.5,
+        bias: 0.5,
+    };
+    let curve = NoiseModule::Curve {
+        source: ramp(),
+        points: vec![(0.0, 0.0), (0.5, 0.1), (1.0, 1.0)],
+    };
+    assert_eq!(at(&clamp, 0.75), 0.5);
+    assert_eq!(at(&clamp, -2.0), -0.5);
+    assert_eq!(at(&scale_bias, -1.0), 0.0);
+    assert_eq!(at(&scale_bias, 1.0), 1.0);
+    assert!((at(&curve, 0.25) - 0.05).abs() < 1e-6);
+    assert!((at(&curve, 0.75) - 0.55).abs() < 1e-6);
+}
+
+#[test]
+fn select_blends_across_the_bounds() {
+    let select = |edge_falloff| NoiseModule::Select {
+        control: ramp(),
+        low: Box::new(constant(-1.0)),
+        high: Box::new(constant(1.0)),
+        lower_bound: 0.0,
+        upper_bound: 1.0,
+        edge_falloff,
+    };
+    let hard = select(0.0);
+    assert_eq!(at(&hard, -0.01), -1.0);
+    assert_eq!(at(&hard, 0.5), 1.0);
+    assert_eq!(at(&hard, 1.01), -1.0);
+
+    let soft = select(0.1);
+    assert_eq!(at(&soft, -0.2), -1.0);
+    assert!(at(&soft, 0.0).abs() < 1e-6);
+    assert_eq!(at(&soft, 0.5), 1.0);
+    assert!(at(&soft, 1.0).abs() < 1e-6);
+    assert_eq!(at(&soft, 1.2), -1.0);
+}
+
+#[test]
+fn terrace_flattens_between_levels() {
+    let terrace = |invert| NoiseModule::Terrace {
+        source: ramp(),
+        points: vec![0.0, 1.0],
+        invert,
+    };
+    assert_eq!(at(&terrace(false), 0.0), 0.0);
+    assert_eq!(at(&terrace(false), 0.5), 0.25);
+    assert_eq!(at(&terrace(true), 0.5), 0.75);
+    assert_eq!(at(&terrace(false), 2.0), 1.0);
+    assert_eq!(at(&terrace(false), -2.0), 0.0);
+}
+
+#[test]
+fn noise_leaves_match_the_source() {
+    let noise = OpenSimplex2::smooth(SEED);
+    let leaf = NoiseModule::Noise {
+        frequency: 0.5,
+        seed_offset: 3,
+    };
+    assert_eq!(
+        leaf.get(&noise, 1.0, 2.0, 3.0),
+        smooth::noise3_ImproveXZ(SEED + 3, 0.5, 1.0, 1.5)
+    );
+
+    let fractal = NoiseModule::Fractal {
+        fractal_type: FractalType::Fbm,
+        frequency: 1.0,
+        octaves: 4,
+        persistence: 0.5,
+        lacunarity: 2.0,
+        seed_offset: 0,
+    };
+    for i in 0..100 {
+        let value = fractal.get(&noise, i as f64 * 0.37, 1.5, -2.5);
+        assert!((-1.0..=1.0).contains(&value), "{}", value);
+    }
+}
+
+#[test]
+fn fractal_nodes_scale_every_axis() {
+    let noise = OpenSimplex2::smooth(SEED);
+    let fractal = NoiseModule::Fractal {
+        fractal_type: FractalType::Fbm,
+        frequency: 0.5,
+        octaves: 4,
+        persistence: 0.5,
+        lacunarity: 2.0,
+        seed_offset: 1,
+    };
+    for i in 0..100 {
+        let [x, y, z] = [i as f64 * 0.37, 1.5, -2.5 + i as f64 * 0.11];
+        let mut value: f32 = 0.0;
+        let mut total: f32 = 0.0;
+        let mut amplitude: f32 = 1.0;
+        let mut frequency: f64 = 0.5;
+        for _ in 0..4 {
+            value +=
+                smooth::noise3_ImproveXZ(SEED + 1, x * frequency, y * frequency, z * frequency)
+                    * amplitude;
+            total += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        let expected = value / total;
+        let actual = fractal.get(&noise, x, y, z);
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "{} and {}",
+            actual,
+            expected
+        );
+    }
+}
+
+#[test]
+fn fractal_nodes_use_the_fractal_type() {
+    let noise = OpenSimplex2::smooth(SEED);
+    let fractal = |fractal_type| NoiseModule::Fractal {
+        fractal_type,
+        frequency: 1.0,
+        octaves: 4,
+        persistence: 0.5,
+        lacunarity: 2.0,
+        seed_offset: 0,
+    };
+    let types = [
+        FractalType::Ridged,
+        FractalType::Billow,
+        FractalType::HybridMulti,
+        FractalType::Swiss,
+        FractalType::Jordan,
+    ];
+    for fractal_type in types {
+        let mut differs = false;
+        for i in 0..100 {
+            let [x, y, z] = [i as f64 * 0.37, 1.5, -2.5];
+            let value = fractal(fractal_type).get(&noise, x, y, z);
+            assert!((-1.0..=1.0).contains(&value), "{}: {}", fractal_type, value);
+            differs |= value != fractal(FractalType::Fbm).get(&noise, x, y, z);
+        }
+        assert!(differs, "{}", fractal_type);
+    }
+
+    let json = r#"{ "type": "fractal", "fractal_type": "billow", "octaves": 3 }"#;
+    let graph: NoiseModule = serde_json::from_str(json).unwrap();
+    assert_eq!(graph.validate(), Ok(()));
+    assert!(matches!(
+        graph,
+        NoiseModule::Fractal {
+            fractal_type: FractalType::Billow,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn turbulence_displaces_the_source() {
+    let noise = OpenSimplex2::smooth(SEED);
+    let turbulence = |power| NoiseModule::Turbulence {
+        source: ramp(),
+        frequency: 1.0,
+        power,
+        roughness: 3,
+        seed_offset: 0,
+    };
+    let still = turbulence(0.0);
+    let moved = turbulence(0.5);
+    let mut displaced = false;
+    for i in 0..20 {
+        let x = i as f64 * 0.41;
+        assert_eq!(
+            still.get(&noise, x, 0.5, 0.5),
+            ramp().get(&noise, x, 0.5, 0.5)
+        );
+        displaced |= moved.get(&noise, x, 0.5, 0.5) != still.get(&noise, x, 0.5, 0.5);
+    }
+    assert!(displaced);
+}
+
+#[test]
+fn graphs_deserialize_from_json() {
+    let json = r#"{
+        "type": "clamp",
+        "lower": 0.0,
+        "upper": 1.0,
+        "source": {
+            "type": "add",
+            "sources": [
+                { "type": "constant", "value": 0.5 },
+                { "type": "scale_bias", "scale": 0.5, "source": { "type": "fractal", "octaves": 3 } }
+            ]
+        }
+    }"#;
+    let graph: NoiseModule = serde_json::from_str(json).unwrap();
+    assert_eq!(graph.validate(), Ok(()));
+    let round_trip: NoiseModule =
+        serde_json::from_str(&serde_json::to_string(&graph).unwrap()).unwrap();
+    assert_eq!(round_trip, graph);
+}
+
+#[test]
+fn invalid_graphs_are_rejected() {
+    let invalid = [
+        r#"{ "type": "min", "sources": [] }"#,
+        r#"{ "type": "fractal", "octaves": 0 }"#,
+        r#"{ "type": "clamp", "lower": 1.0, "upper": 0.0, "source": { "type": "noise" } }"#,
+        r#"{ "type": "curve", "points": [[0.0, 0.0]], "source": { "type": "noise" } }"#,
+        r#"{ "type": "terrace", "points": [0.5, 0.0], "source": { "type": "noise" } }"#,
+        r#"{ "type": "add", "sources": [{ "type": "turbulence", "power": 1.0, "roughness": 0, "source": { "type": "noise" } }] }"#,
+    ];
+    for json in invalid {
+        let graph: NoiseModule = serde_json::from_str(json).unwrap();
+        assert!(graph.validate().is_err(), "{}", json);
+    }
+}
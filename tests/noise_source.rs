@@ -0,0 +1,171 @@
+use terrainopensimplex2::noise_source::{Algorithm, NoiseSource, NoiseVariant, Orientation};
+use terrainopensimplex2::opensimplex2::OpenSimplex2;
+use terrainopensimplex2::{fast, smooth};
+
+const SEED: i64 = 40000345266;
+const POINTS: [[f64; 4]; 4] = [
+    [0.0, 0.0, 0.0, 0.0],
+    [1.25, -3.5, 7.75, 0.125],
+    [-120.3, 64.01, -0.6, 9.9],
+    [2048.5, -4096.25, 17.0, -33.3],
+];
+
+const ORIENTATIONS: [Orientation; 6] = [
+    Orientation::ImproveX,
+    Orientation::ImproveXY,
+    Orientation::ImproveXZ,
+    Orientation::ImproveXYZ,
+    Orientation::ImproveXYImproveZW,
+    Orientation::Fallback,
+];
+
+fn assert_variant(
+    variant: NoiseVariant,
+    noise2: fn(i64, f64, f64) -> f32,
+    noise3: fn(i64, f64, f64, f64) -> f32,
+    noise4: fn(i64, f64, f64, f64, f64) -> f32,
+) {
+    for [x, y, z, w] in POINTS {
+        assert_eq!(
+            variant.sample2(SEED, x, y),
+            noise2(SEED, x, y),
+            "{:?}",
+            variant
+        );
+        assert_eq!(
+            variant.sample3(SEED, x, y, z),
+            noise3(SEED, x, y, z),
+            "{:?}",
+            variant
+        );
+        assert_eq!(
+            variant.sample4(SEED, x, y, z, w),
+            noise4(SEED, x, y, z, w),
+            "{:?}",
+            variant
+        );
+    }
+}
+
+#[test]
+fn fast_variants_dispatch_to_fast_functions() {
+    let variant = |orientation| NoiseVariant::new(Algorithm::Fast, orientation);
+    assert_variant(
+        variant(Orientation::ImproveX),
+        fast::noise2_ImproveX,
+        fast::noise3_ImproveXZ,
+        fast::noise4_ImproveXYZ_ImproveXZ,
+    );
+    assert_variant(
+        variant(Orientation::ImproveXY),
+        fast::noise2,
+        fast::noise3_ImproveXY,
+        fast::noise4_ImproveXYZ_ImproveXY,
+    );
+    assert_variant(
+        variant(Orientation::ImproveXZ),
+        fast::noise2,
+        fast::noise3_ImproveXZ,
+        fast::noise4_ImproveXYZ_ImproveXZ,
+    );
+    assert_variant(
+        variant(Orientation::ImproveXYZ),
+        fast::noise2,
+        fast::noise3_Fallback,
+        fast::noise4_ImproveXYZ,
+    );
+    assert_variant(
+        variant(Orientation::ImproveXYImproveZW),
+        fast::noise2,
+        fast::noise3_ImproveXY,
+        fast::noise4_ImproveXY_ImproveZW,
+    );
+    assert_variant(
+        variant(Orientation::Fallback),
+        fast::noise2,
+        fast::noise3_Fallback,
+        fast::noise4_Fallback,
+    );
+}
+
+#[test]
+fn smooth_variants_dispatch_to_smooth_functions() {
+    let variant = |orientation| NoiseVariant::new(Algorithm::Smooth, orientation);
+    assert_variant(
+        variant(Orientation::ImproveX),
+        smooth::noise2_ImproveX,
+        smooth::noise3_ImproveXZ,
+        smooth::noise4_ImproveXYZ_ImproveXZ,
+    );
+    assert_variant(
+        variant(Orientation::ImproveXY),
+        smooth::noise2,
+        smooth::noise3_ImproveXY,
+        smooth::noise4_ImproveXYZ_ImproveXY,
+    );
+    assert_variant(
+        variant(Orientation::ImproveXZ),
+        smooth::noise2,
+        smooth::noise3_ImproveXZ,
+        smooth::noise4_ImproveXYZ_ImproveXZ,
+    );
+    assert_variant(
+        variant(Orientation::ImproveXYZ),
+        smooth::noise2,
+        smooth::noise3_Fallback,
+        smooth::noise4_ImproveXYZ,
+    );
+    assert_variant(
+        variant(Orientation::ImproveXYImproveZW),
+        smooth::noise2,
+        smooth::noise3_ImproveXY,
+        smooth::noise4_ImproveXY_ImproveZW,
+    );
+    assert_variant(
+        variant(Orientation::Fallback),
+        smooth::noise2,
+        smooth::noise3_Fallback,
+        smooth::noise4_Fallback,
+    );
+}
+
+#[test]
+fn default_variant_is_the_original_terrain_noise() {
+    let variant = NoiseVariant::default();
+    for [x, y, z, _] in POINTS {
+        assert_eq!(
+            variant.sample3(SEED, x, y, z),
+            smooth::noise3_ImproveXZ(SEED, x, y, z)
+        );
+    }
+}
+
+#[test]
+fn sources_can_be_sampled_through_trait_objects() {
+    fn sample<N: NoiseSource + ?Sized>(noise: &N) -> f32 {
+        noise.sample3(1.0, 2.0, 3.0)
+    }
+
+    let generator = OpenSimplex2::fast(SEED).with_orientation(Orientation::Fallback);
+    let expected = fast::noise3_Fallback(SEED, 1.0, 2.0, 3.0);
+    let dynamic: &dyn NoiseSource = &generator;
+    let boxed: Box<dyn NoiseSource> = Box::new(generator);
+    assert_eq!(sample(&generator), expected);
+    assert_eq!(sample(dynamic), expected);
+    assert_eq!(sample(boxed.as_ref()), expected);
+}
+
+#[test]
+fn names_round_trip() {
+    for algorithm in [Algorithm::Fast, Algorithm::Smooth] {
+        assert_eq!(algorithm.to_string().parse::<Algorithm>(), Ok(algorithm));
+    }
+    for orientation in ORIENTATIONS {
+        assert_eq!(
+            orientation.to_string().parse::<Orientation>(),
+            Ok(orientation)
+        );
+    }
+    assert!("Smooth".parse::<Algorithm>().is_err());
+    assert!("ImproveYZ".parse::<Orientation>().is_err());
+}
@@ -0,0 +1,181 @@
+use terrainopensimplex2::noise_source::{Algorithm, NoiseSource, NoiseVariant, Orientation};
+use terrainopensimplex2::opensimplex2::OpenSimplex2;
+use terrainopensimplex2::{fast, smooth};
+
+const SEED: i64 = 40000345266;
+const POINTS: [[f64; 4]; 3] = [
+    [0.0, 0.0, 0.0, 0.0],
+    [1.25, -3.5, 7.75, 0.125],
+    [-120.3, 64.01, -0.6, 9.9],
+];
+
+type Noise2 = fn(i64, f64, f64) -> f32;
+type Noise3 = fn(i64, f64, f64, f64) -> f32;
+type Noise4 = fn(i64, f64, f64, f64, f64) -> f32;
+
+fn assert_generator(
+    generator: OpenSimplex2,
+    noise2: [Noise2; 2],
+    noise3: [Noise3; 3],
+    noise4: [Noise4; 5],
+) {
+    for [x, y, z, w] in POINTS {
+        let methods2 = [OpenSimplex2::noise2, OpenSimplex2::noise2_ImproveX];
+        for (method, function) in methods2.iter().zip(noise2) {
+            assert_eq!(method(&generator, x, y), function(SEED, x, y));
+        }
+        let methods3 = [
+            OpenSimplex2::noise3_ImproveXY,
+            OpenSimplex2::noise3_ImproveXZ,
+            OpenSimplex2::noise3_Fallback,
+        ];
+        for (method, function) in methods3.iter().zip(noise3) {
+            assert_eq!(method(&generator, x, y, z), function(SEED, x, y, z));
+        }
+        let methods4 = [
+            OpenSimplex2::noise4_ImproveXYZ_ImproveXY,
+            OpenSimplex2::noise4_ImproveXYZ_ImproveXZ,
+            OpenSimplex2::noise4_ImproveXYZ,
+            OpenSimplex2::noise4_ImproveXY_ImproveZW,
+            OpenSimplex2::noise4_Fallback,
+        ];
+        for (method, function) in methods4.iter().zip(noise4) {
+            assert_eq!(method(&generator, x, y, z, w), function(SEED, x, y, z, w));
+        }
+    }
+}
+
+#[test]
+fn methods_match_the_free_functions() {
+    assert_generator(
+        OpenSimplex2::fast(SEED),
+        [fast::noise2, fast::noise2_ImproveX],
+        [
+            fast::noise3_ImproveXY,
+            fast::noise3_ImproveXZ,
+            fast::noise3_Fallback,
+        ],
+        [
+            fast::noise4_ImproveXYZ_ImproveXY,
+            fast::noise4_ImproveXYZ_ImproveXZ,
+            fast::noise4_ImproveXYZ,
+            fast::noise4_ImproveXY_ImproveZW,
+            fast::noise4_Fallback,
+        ],
+    );
+    assert_generator(
+        OpenSimplex2::smooth(SEED),
+        [smooth::noise2, smooth::noise2_ImproveX],
+        [
+            smooth::noise3_ImproveXY,
+            smooth::noise3_ImproveXZ,
+            smooth::noise3_Fallback,
+        ],
+        [
+            smooth::noise4_ImproveXYZ_ImproveXY,
+            smooth::noise4_ImproveXYZ_ImproveXZ,
+            smooth::noise4_ImproveXYZ,
+            smooth::noise4_ImproveXY_ImproveZW,
+            smooth::noise4_Fallback,
+        ],
+    );
+}
+
+#[test]
+fn frequency_and_offset_transform_the_input() {
+    let generator = OpenSimplex2::new(Algorithm::Smooth, SEED)
+        .with_frequency(0.5)
+        .with_offset([10.0, -20.0, 30.0, -40.0]);
+    for [x, y, z, w] in POINTS {
+        assert_eq!(
+            generator.noise2(x, y),
+            smooth::noise2(SEED, x * 0.5 + 10.0, y * 0.5 - 20.0)
+        );
+        assert_eq!(
+            generator.noise3_ImproveXZ(x, y, z),
+            smooth::noise3_ImproveXZ(SEED, x * 0.5 + 10.0, y * 0.5 - 20.0, z * 0.5 + 30.0)
+        );
+        assert_eq!(
+            generator.noise4_Fallback(x, y, z, w),
+            smooth::noise4_Fallback(
+                SEED,
+                x * 0.5 + 10.0,
+                y * 0.5 - 20.0,
+                z * 0.5 + 30.0,
+                w * 0.5 - 40.0
+            )
+        );
+    }
+}
+
+#[test]
+fn noise_source_matches_the_variant_under_its_seed() {
+    let orientations = [
+        Orientation::ImproveX,
+        Orientation::ImproveXY,
+        Orientation::ImproveXZ,
+        Orientation::ImproveXYZ,
+        Orientation::ImproveXYImproveZW,
+        Orientation::Fallback,
+    ];
+    for algorithm in [Algorithm::Fast, Algorithm::Smooth] {
+        for orientation in orientations {
+            let generator = OpenSimplex2::new(algorithm, SEED).with_orientation(orientation);
+            let variant = NoiseVariant::new(algorithm, orientation);
+            assert_eq!(generator.seed(), SEED);
+            for [x, y, z, w] in POINTS {
+                assert_eq!(generator.sample2(x, y), variant.sample2(SEED, x, y));
+                assert_eq!(generator.sample3(x, y, z), variant.sample3(SEED, x, y, z));
+                assert_eq!(
+                    generator.sample4(x, y, z, w),
+                    variant.sample4(SEED, x, y, z, w)
+                );
+                assert_eq!(
+                    generator.sample3_with_derivatives(x, y, z),
+                    variant.sample3_with_derivatives(SEED, x, y, z)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn noise_source_transforms_the_input() {
+    let generator = OpenSimplex2::new(Algorithm::Fast, SEED)
+        .with_frequency(0.5)
+        .with_offset([10.0, -20.0, 30.0, -40.0]);
+    let variant = NoiseVariant::new(Algorithm::Fast, Orientation::ImproveXZ);
+    for [x, y, z, _] in POINTS {
+        let expected = variant.sample3(SEED, x * 0.5 + 10.0, y * 0.5 - 20.0, z * 0.5 + 30.0);
+        assert_eq!(generator.sample3(x, y, z), expected);
+
+        // The gradient is with respect to the caller's coordinates
+        let (_, unscaled) =
+            variant.sample3_with_derivatives(SEED, x * 0.5 + 10.0, y * 0.5 - 20.0, z * 0.5 + 30.0);
+        assert_eq!(
+            generator.sample3_with_derivatives(x, y, z),
+            (expected, unscaled.map(|d| d * 0.5))
+        );
+    }
+}
+
+#[test]
+fn with_seed_keeps_the_transform() {
+    let generator = OpenSimplex2::smooth(5)
+        .with_frequency(0.5)
+        .with_offset([10.0, -20.0, 30.0, -40.0]);
+    let reseeded = generator.with_seed(SEED);
+    assert_eq!(
+        reseeded,
+        OpenSimplex2 {
+            seed: SEED,
+            ..generator
+        }
+    );
+    for [x, y, z, _] in POINTS {
+        assert_eq!(
+            reseeded.sample3(x, y, z),
+            smooth::noise3_ImproveXZ(SEED, x * 0.5 + 10.0, y * 0.5 - 20.0, z * 0.5 + 30.0)
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+use terrainopensimplex2::configuration::ConfigurationMessage;
+use terrainopensimplex2::mesh_mode::MeshMode;
+use terrainopensimplex2::noise_source::NoiseSource;
+use terrainopensimplex2::opensimplex2::OpenSimplex2;
+use terrainopensimplex2::terrain_configuration::{
+    generate_heightmap, generate_heightmap_with_noise, terrain_mesh, update_configuration,
+    TerrainConfiguration,
+};
+use three_d::{CpuMesh, Indices, Positions, Srgba, Vec3};
+
+fn configuration(threads: usize) -> TerrainConfiguration {
+    let terrain_configuration = TerrainConfiguration::new(
+        3.0,
+        2.5,
+        40000345266,
+        0.1,
+        "304630".to_string(),
+        2.0,
+        200.0,
+        21.0,
+        8,
+        0.4,
+        1.0,
+    );
+    update_configuration(
+        terrain_configuration,
+        Some(ConfigurationMessage::TerrainThreads(threads)),
+    )
+}
+
+#[test]
+fn heightmap_does_not_depend_on_thread_count() {
+    let sequential = generate_heightmap(&configuration(1));
+    for threads in [0, 2, 3, 8] {
+        assert_eq!(
+            generate_heightmap(&configuration(threads)),
+            sequential,
+            "{} threads",
+            threads
+        );
+    }
+}
+
+#[test]
+fn mesh_does_not_depend_on_thread_count() {
+    let heightmap = generate_heightmap(&configuration(1));
+    for mode in [MeshMode::Voxels, MeshMode::Heightfield, MeshMode::Volume] {
+        let in_mode = |threads: usize| {
+            update_configuration(
+                configuration(threads),
+                Some(ConfigurationMessage::TerrainMode(mode)),
+            )
+        };
+        let sequential = buffers(&terrain_mesh(&heightmap, &in_mode(1)));
+        for threads in [0, 2, 3, 8] {
+            assert!(
+                buffers(&terrain_mesh(&heightmap, &in_mode(threads))) == sequential,
+                "{:?} with {} threads",
+                mode,
+                threads
+            );
+        }
+    }
+}
+
+/// The terrain noise, remembering which threads sampled it. Copies under other seeds share
+/// the record.
+struct Sampled {
+    noise: OpenSimplex2,
+    threads: Arc<Mutex<HashSet<ThreadId>>>,
+}
+
+impl Sampled {
+    fn record(&self) {
+        self.threads.lock().unwrap().insert(thread::current().id());
+    }
+}
+
+impl NoiseSource for Sampled {
+    fn seed(&self) -> i64 {
+        self.noise.seed()
+    }
+
+    fn with_seed(&self, seed: i64) -> Self {
+        Self {
+            noise: self.noise.with_seed(seed),
+            threads: Arc::clone(&self.threads),
+        }
+    }
+
+    fn sample2(&self, x: f64, y: f64) -> f32 {
+        self.record();
+        self.noise.sample2(x, y)
+    }
+
+    fn sample3(&self, x: f64, y: f64, z: f64) -> f32 {
+        self.record();
+        self.noise.sample3(x, y, z)
+    }
+
+    fn sample4(&self, x: f64, y: f64, z: f64, w: f64) -> f32 {
+        self.record();
+        self.noise.sample4(x, y, z, w)
+    }
+}
+
+#[test]
+fn builds_reuse_the_pool_of_the_configuration() {
+    let terrain_configuration = configuration(2);
+    let sampled = Sampled {
+        noise: OpenSimplex2::smooth(40000345266),
+        threads: Arc::default(),
+    };
+    for _ in 0..3 {
+        generate_heightmap_with_noise(&terrain_configuration.clone(), &sampled);
+    }
+    let threads = sampled.threads.lock().unwrap();
+    assert!(!threads.is_empty());
+    assert!(threads.len() <= 2, "{} threads", threads.len());
+}
+
+fn buffers(mesh: &CpuMesh) -> (Vec<Vec3>, Vec<u32>, Vec<Srgba>) {
+    let (Positions::F32(positions), Indices::U32(indices), Some(colors)) =
+        (&mesh.positions, &mesh.indices, &mesh.colors)
+    else {
+        panic!("terrain meshes are built with f32 positions, u32 indices and colors");
+    };
+    (positions.clone(), indices.clone(), colors.clone())
+}
@@ -0,0 +1,154 @@
+use terrainopensimplex2::{fast, smooth};
+
+const SEEDS: [i64; 3] = [0, 42, -7_340_032_993];
+const EPSILON: f32 = 1e-5;
+// Comfortably above the steepest slope either variant produces.
+const MAX_SLOPE: f32 = 10.0;
+const STEP: f64 = 1e-3;
+
+type Periodic2 = fn(i64, f64, f64, [u32; 2]) -> f32;
+type Periodic3 = fn(i64, f64, f64, f64, [u32; 3]) -> f32;
+
+fn coordinates(period: u32) -> impl Iterator<Item = f64> {
+    (0..4 * period).map(|i| i as f64 * 0.25 + 0.0625)
+}
+
+fn assert_close(name: &str, a: f32, b: f32, tolerance: f32, context: String) {
+    assert!(
+        (a - b).abs() <= tolerance,
+        "{} {}: {} and {} differ by more than {}",
+        name,
+        context,
+        a,
+        b,
+        tolerance
+    );
+}
+
+fn check_period2(name: &str, noise: Periodic2) {
+    for seed in SEEDS {
+        for period in [[1, 1], [8, 8], [16, 5]] {
+            let [px, py] = period.map(f64::from);
+            for y in coordinates(period[1]) {
+                for x in coordinates(period[0]) {
+                    let value = noise(seed, x, y, period);
+                    let context = format!("seed {} period {:?} at ({}, {})", seed, period, x, y);
+                    assert_close(
+                        name,
+                        value,
+                        noise(seed, x + px, y, period),
+                        EPSILON,
+                        context.clone(),
+                    );
+                    assert_close(
+                        name,
+                        value,
+                        noise(seed, x, y - py, period),
+                        EPSILON,
+                        context,
+                    );
+                }
+            }
+
+            // Crossing the right and bottom edges lands next to the left and top edges.
+            for t in coordinates(period[0].max(period[1])) {
+                let bound = MAX_SLOPE * STEP as f32;
+                let context = format!("seed {} period {:?} edge at {}", seed, period, t);
+                let left = noise(seed, 0.0, t, period);
+                assert_close(
+                    name,
+                    left,
+                    noise(seed, px - STEP, t, period),
+                    bound,
+                    context.clone(),
+                );
+                let top = noise(seed, t, 0.0, period);
+                assert_close(name, top, noise(seed, t, py - STEP, period), bound, context);
+            }
+        }
+    }
+}
+
+fn check_period3(name: &str, noise: Periodic3) {
+    for seed in SEEDS {
+        for period in [[1, 1, 1], [4, 4, 4], [6, 3, 5]] {
+            let [px, py, pz] = period.map(f64::from);
+            for z in coordinates(period[2]) {
+                for y in coordinates(period[1]) {
+                    for x in coordinates(period[0]) {
+                        let value = noise(seed, x, y, z, period);
+                        let context =
+                            format!("seed {} period {:?} at ({}, {}, {})", seed, period, x, y, z);
+                        let shifted = [
+                            noise(seed, x + px, y, z, period),
+                            noise(seed, x, y - py, z, period),
+                            noise(seed, x, y, z + 2.0 * pz, period),
+                        ];
+                        for other in shifted {
+                            assert_close(name, value, other, EPSILON, context.clone());
+                        }
+                    }
+                }
+            }
+
+            for u in coordinates(period[0].max(period[1]).max(period[2])) {
+                let v = u * 0.5;
+                let bound = MAX_SLOPE * STEP as f32;
+                let context = format!("seed {} period {:?} edge at ({}, {})", seed, period, u, v);
+                let edges = [
+                    (
+                        noise(seed, 0.0, u, v, period),
+                        noise(seed, px - STEP, u, v, period),
+                    ),
+                    (
+                        noise(seed, u, 0.0, v, period),
+                        noise(seed, u, py - STEP, v, period),
+                    ),
+                    (
+                        noise(seed, u, v, 0.0, period),
+                        noise(seed, u, v, pz - STEP, period),
+                    ),
+                ];
+                for (start, end) in edges {
+                    assert_close(name, start, end, bound, context.clone());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn fast_periodic2_wraps() {
+    check_period2("fast::noise2_Periodic", fast::noise2_Periodic);
+}
+
+#[test]
+fn fast_periodic3_wraps() {
+    check_period3("fast::noise3_Periodic", fast::noise3_Periodic);
+}
+
+#[test]
+fn smooth_periodic2_wraps() {
+    check_period2("smooth::noise2_Periodic", smooth::noise2_Periodic);
+}
+
+#[test]
+fn smooth_periodic3_wraps() {
+    check_period3("smooth::noise3_Periodic", smooth::noise3_Periodic);
+}
+
+#[test]
+fn periodic_noise_is_not_constant() {
+    let samples: Vec<f32> = (0..64)
+        .map(|i| smooth::noise3_Periodic(7, i as f64 * 0.37, 1.5, 2.25, [16, 16, 16]))
+        .collect();
+    let min = samples.iter().cloned().fold(f32::INFINITY, f32::min);
+    let max = samples.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    assert!(max - min > 0.1, "range {}..{}", min, max);
+}
+
+#[test]
+#[should_panic(expected = "period must be positive")]
+fn zero_period_panics() {
+    fast::noise2_Periodic(0, 0.5, 0.5, [4, 0]);
+}
@@ -0,0 +1,165 @@
+"""
+Checks the Python extension against the Rust implementation.
+
+Run after `maturin develop` with: pytest tests/python
+"""
+
+import ctypes
+import struct
+from pathlib import Path
+
+import numpy as np
+import pytest
+
+import terrainopensimplex2 as t
+
+GOLDEN = Path(__file__).resolve().parents[1] / "golden"
+SEED = 40000345266
+
+NOISE_FUNCTIONS = [
+    "noise2",
+    "noise2_ImproveX",
+    "noise3_ImproveXY",
+    "noise3_ImproveXZ",
+    "noise3_Fallback",
+    "noise4_ImproveXYZ_ImproveXY",
+    "noise4_ImproveXYZ_ImproveXZ",
+    "noise4_ImproveXYZ",
+    "noise4_ImproveXY_ImproveZW",
+    "noise4_Fallback",
+]
+
+
+def bits(value):
+    return struct.pack(">f", value).hex()
+
+
+def golden_rows(file):
+    for line in (GOLDEN / file).read_text().splitlines():
+        if line.startswith("#"):
+            continue
+        inputs, outputs = line.split("|")
+        seed, *point = inputs.split()
+        yield int(seed), [float(p) for p in point], outputs.split()
+
+
+@pytest.mark.parametrize("algorithm", ["fast", "smooth"])
+def test_noise_matches_golden_values(algorithm):
+    # The golden files are written by tests/golden.rs from the Rust functions.
+    for seed, point, expected in golden_rows(f"{algorithm}_noise.txt"):
+        for name, expected_bits in zip(NOISE_FUNCTIONS, expected):
+            dimensions = int(name[5])
+            value = getattr(t, f"{algorithm}_{name}")(seed, *point[:dimensions])
+            assert bits(value) == expected_bits, (name, seed, point)
+
+
+@pytest.mark.parametrize("algorithm", ["fast", "smooth"])
+@pytest.mark.parametrize("orientation", ["standard", "ImproveX"])
+def test_grid2_matches_scalar_noise(algorithm, orientation):
+    name = "noise2" if orientation == "standard" else "noise2_ImproveX"
+    scalar = getattr(t, f"{algorithm}_{name}")
+    grid = t.grid2(SEED, (-13.5, 4.25), (0.25, -0.5), (5, 3), algorithm, orientation)
+    assert grid.shape == (3, 5) and grid.dtype == np.float32
+    for j in range(3):
+        for i in range(5):
+            assert grid[j, i] == scalar(SEED, -13.5 + i * 0.25, 4.25 + j * -0.5)
+
+
+@pytest.mark.parametrize("algorithm", ["fast", "smooth"])
+@pytest.mark.parametrize("orientation", ["ImproveXY", "ImproveXZ", "Fallback"])
+def test_grid3_matches_scalar_noise(algorithm, orientation):
+    scalar = getattr(t, f"{algorithm}_noise3_{orientation}")
+    grid = t.grid3(SEED, (1.0, -2.0, 3.0), (0.5, 0.25, 0.125), (4, 3, 2), algorithm, orientation)
+    assert grid.shape == (2, 3, 4)
+    for k in range(2):
+        for j in range(3):
+            for i in range(4):
+                expected = scalar(SEED, 1.0 + i * 0.5, -2.0 + j * 0.25, 3.0 + k * 0.125)
+                assert grid[k, j, i] == expected
+
+
+class TerrainConfig(ctypes.Structure):
+    _fields_ = [
+        ("tot_width", ctypes.c_float),
+        ("tot_depth", ctypes.c_float),
+        ("seed", ctypes.c_longlong),
+        ("cube_size", ctypes.c_float),
+        ("color", ctypes.c_ubyte * 3),
+        ("max_height", ctypes.c_float),
+        ("failoff", ctypes.c_float),
+        ("z", ctypes.c_double),
+        ("fractal_octaves", ctypes.c_int),
+        ("fractal_amplitude", ctypes.c_float),
+        ("fractal_frequency", ctypes.c_double),
+        ("fractal_type", ctypes.c_int),
+        ("warp_levels", ctypes.c_uint),
+        ("warp_seed", ctypes.c_longlong),
+        ("warp_strength", ctypes.c_double),
+        ("warp_frequency", ctypes.c_double),
+        ("noise_algorithm", ctypes.c_int),
+        ("noise_orientation", ctypes.c_int),
+        ("graph", ctypes.c_char_p),
+    ]
+
+
+def test_heightmap_matches_the_c_api():
+    # The extension is the crate's cdylib, so the C exports are in the same library.
+    library = ctypes.CDLL(t.__file__)
+    library.opensimplex2_terrain_default_config.restype = TerrainConfig
+    config = library.opensimplex2_terrain_default_config()
+    config.tot_width, config.tot_depth, config.cube_size, config.seed = 3.0, 2.0, 0.25, 7
+
+    size_x, size_y = ctypes.c_size_t(), ctypes.c_size_t()
+    assert library.opensimplex2_terrain_size(ctypes.byref(config), ctypes.byref(size_x), ctypes.byref(size_y)) == 0
+    expected = np.zeros((size_y.value, size_x.value), dtype=np.float32)
+    pointer = expected.ctypes.data_as(ctypes.POINTER(ctypes.c_float))
+    assert library.opensimplex2_terrain_heights(ctypes.byref(config), pointer, ctypes.c_size_t(expected.size)) == 0
+
+    heights = t.heightmap({"tot_width": 3.0, "tot_depth": 2.0, "cube_size": 0.25, "seed": 7})
+    assert heights.shape == (8, 12)
+    np.testing.assert_array_equal(heights, expected)
+
+
+def test_heightmap_takes_every_height_setting():
+    library = ctypes.CDLL(t.__file__)
+    library.opensimplex2_terrain_default_config.restype = TerrainConfig
+    config = library.opensimplex2_terrain_default_config()
+    config.tot_width, config.tot_depth, config.cube_size = 3.0, 2.0, 0.25
+    config.fractal_type, config.warp_levels, config.noise_algorithm = 1, 2, 0
+    expected = np.zeros((8, 12), dtype=np.float32)
+    pointer = expected.ctypes.data_as(ctypes.POINTER(ctypes.c_float))
+    assert library.opensimplex2_terrain_heights(ctypes.byref(config), pointer, ctypes.c_size_t(expected.size)) == 0
+
+    settings = {"tot_width": 3.0, "tot_depth": 2.0, "cube_size": 0.25}
+    heights = t.heightmap({**settings, "fractal_type": "ridged", "warp_levels": 2, "noise_algorithm": "fast"})
+    np.testing.assert_array_equal(heights, expected)
+
+    flat = t.heightmap({**settings, "graph": {"type": "constant", "value": 0.5}})
+    assert np.ptp(flat) < 0.05
+
+
+def test_heightmap_defaults_to_the_viewer_terrain():
+    heights = t.heightmap()
+    assert heights.shape == (200, 200)
+    assert heights.min() > 0.0 and heights.max() < 2.2
+
+
+def test_invalid_arguments_raise():
+    with pytest.raises(ValueError):
+        t.grid2(SEED, (0.0, 0.0), (1.0, 1.0), (2, 2), "fast", "ImproveXZ")
+    with pytest.raises(ValueError):
+        t.grid3(SEED, (0.0, 0.0, 0.0), (1.0, 1.0, 1.0), (2, 2, 2), "medium")
+    with pytest.raises(KeyError):
+        t.heightmap({"octaves": 4})
+    with pytest.raises(ValueError):
+        t.heightmap({"color": "30463"})
+    with pytest.raises(ValueError):
+        t.heightmap({"cube_size": 0.0})
+    with pytest.raises(ValueError):
+        t.heightmap({"cube_size": 1e-7})
+    with pytest.raises(ValueError):
+        t.heightmap({"max_height": 1e30})
+    with pytest.raises(ValueError):
+        t.heightmap({"warp_levels": 3})
+    with pytest.raises(ValueError):
+        t.heightmap({"graph": {"type": "min", "sources": []}})
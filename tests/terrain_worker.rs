@@ -0,0 +1,142 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use terrainopensimplex2::configuration::ConfigurationMessage;
+use terrainopensimplex2::progress::Progress;
+use terrainopensimplex2::terrain_configuration::{
+    build_terrain, generate_heightmap, terrain_mesh, terrain_stats, update_configuration,
+    TerrainConfiguration,
+};
+use terrainopensimplex2::terrain_worker::{TerrainStatus, TerrainWorker};
+use three_d::{CpuMesh, Positions};
+
+fn configuration(seed: i64) -> TerrainConfiguration {
+    let terrain_configuration = TerrainConfiguration::new(
+        2.0,
+        2.0,
+        40000345266,
+        0.1,
+        "304630".to_string(),
+        2.0,
+        200.0,
+        21.0,
+        8,
+        0.4,
+        1.0,
+    );
+    update_configuration(
+        terrain_configuration,
+        Some(ConfigurationMessage::TerrainSeed(seed)),
+    )
+}
+
+fn positions(mesh: &CpuMesh) -> Vec<three_d::Vec3> {
+    match &mesh.positions {
+        Positions::F32(positions) => positions.clone(),
+        Positions::F64(_) => panic!("the voxel mesh is built with f32 positions"),
+    }
+}
+
+// Every mesh received until the newest submitted build is ready
+fn wait_for_newest(worker: &TerrainWorker) -> Vec<CpuMesh> {
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let mut meshes = Vec::new();
+    loop {
+        meshes.extend(worker.try_mesh());
+        let report = worker.status().report();
+        if report.ready == report.requested && report.progress.is_none() {
+            meshes.extend(worker.try_mesh());
+            return meshes;
+        }
+        assert!(Instant::now() < deadline, "no mesh after a minute");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn expected_mesh(terrain_configuration: &TerrainConfiguration) -> CpuMesh {
+    terrain_mesh(
+        &generate_heightmap(terrain_configuration),
+        terrain_configuration,
+    )
+}
+
+#[test]
+fn worker_builds_the_submitted_terrain() {
+    let worker = TerrainWorker::new(Arc::new(TerrainStatus::new()));
+    assert_eq!(worker.status().report().requested, 0);
+    assert_eq!(worker.status().stats(), None);
+
+    worker.submit(configuration(7));
+    let meshes = wait_for_newest(&worker);
+
+    assert_eq!(meshes.len(), 1);
+    assert_eq!(
+        positions(&meshes[0]),
+        positions(&expected_mesh(&configuration(7)))
+    );
+    let report = worker.status().report();
+    assert_eq!(
+        (report.requested, report.ready, report.progress),
+        (1, 1, None)
+    );
+    assert_eq!(
+        worker.status().stats(),
+        Some(terrain_stats(
+            &generate_heightmap(&configuration(7)),
+            &configuration(7)
+        ))
+    );
+}
+
+#[test]
+fn newer_submissions_replace_older_ones() {
+    let worker = TerrainWorker::new(Arc::new(TerrainStatus::new()));
+    for seed in 0..10 {
+        worker.submit(configuration(seed));
+    }
+    let meshes = wait_for_newest(&worker);
+
+    // Stale builds are cancelled or dropped, so the last mesh is always the newest terrain
+    assert!(!meshes.is_empty());
+    assert_eq!(
+        positions(meshes.last().unwrap()),
+        positions(&expected_mesh(&configuration(9)))
+    );
+    assert_eq!(worker.status().report().ready, 10);
+}
+
+#[test]
+fn cancelled_builds_stop() {
+    let progress = Progress::new();
+    progress.cancel();
+    assert!(build_terrain(&configuration(7), &progress).is_none());
+}
+
+#[test]
+fn progress_reaches_one() {
+    let progress = Progress::new();
+    assert_eq!(progress.fraction(), 0.0);
+
+    let (heightmap, mesh) = build_terrain(&configuration(7), &progress).unwrap();
+    assert_eq!(progress.fraction(), 1.0);
+    assert_eq!(heightmap, generate_heightmap(&configuration(7)));
+    assert_eq!(
+        positions(&mesh),
+        positions(&expected_mesh(&configuration(7)))
+    );
+}
+
+#[test]
+fn view_messages_do_not_rebuild_the_terrain() {
+    assert!(!ConfigurationMessage::CameraUpZ(1.0).changes_terrain());
+    assert!(!ConfigurationMessage::CameraZFar(100.0).changes_terrain());
+    assert!(!ConfigurationMessage::LightDirectionX(-1.0).changes_terrain());
+    assert!(!ConfigurationMessage::LightColor("ffffff".to_string()).changes_terrain());
+    assert!(ConfigurationMessage::TerrainSeed(1).changes_terrain());
+    assert!(ConfigurationMessage::TerrainThreads(2).changes_terrain());
+    assert!(!ConfigurationMessage::TerrainWaterOpacity(0.5).changes_terrain());
+    assert!(!ConfigurationMessage::TerrainWaterColor("ffffff".to_string()).changes_terrain());
+    // The seabed is colored into the terrain mesh
+    assert!(ConfigurationMessage::TerrainWaterLevel(0.5).changes_terrain());
+    assert!(ConfigurationMessage::TerrainWaterSeabedColor("ffffff".to_string()).changes_terrain());
+}
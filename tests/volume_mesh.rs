@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use terrainopensimplex2::color_ramp::Palette;
+use terrainopensimplex2::configuration::ConfigurationMessage;
+use terrainopensimplex2::mesh_mode::MeshMode;
+use terrainopensimplex2::progress::Progress;
+use terrainopensimplex2::terrain_configuration::{
+    build_terrain, generate_density, generate_heightmap, terrain_mesh, update_configuration,
+    volume_size, TerrainConfiguration,
+};
+use terrainopensimplex2::volume::DensityGrid;
+use terrainopensimplex2::volume_mesh::volume_mesh;
+use three_d::{CpuMesh, Indices, InnerSpace, Positions, Vec3};
+
+const COLOR: [u8; 3] = [0x30, 0x46, 0x30];
+
+fn buffers(mesh: &CpuMesh) -> (&[Vec3], &[u32], &[Vec3]) {
+    let (Positions::F32(positions), Indices::U32(indices), Some(normals)) =
+        (&mesh.positions, &mesh.indices, &mesh.normals)
+    else {
+        panic!("the volume is built with f32 positions, u32 indices and normals");
+    };
+    (positions, indices, normals)
+}
+
+fn grid(size: [usize; 3], step: f32, density: impl Fn(Vec3) -> f32) -> DensityGrid {
+    let mut values = Vec::new();
+    for z in 0..size[2] {
+        for y in 0..size[1] {
+            for x in 0..size[0] {
+                values.push(density(Vec3::new(x as f32, y as f32, z as f32) * step));
+            }
+        }
+    }
+    DensityGrid::new(size, step, values)
+}
+
+/**
+    Every edge must be crossed once in each direction: the surface is closed and every
+    triangle is wound the same way as its neighbours.
+*/
+fn assert_closed(mesh: &CpuMesh) {
+    let (_, indices, _) = buffers(mesh);
+    let mut balance: HashMap<(u32, u32), i32> = HashMap::new();
+    for triangle in indices.chunks(3) {
+        for k in 0..3 {
+            let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+            *balance.entry((a.min(b), a.max(b))).or_default() += if a < b { 1 } else { -1 };
+        }
+    }
+    assert!(balance.values().all(|&count| count == 0));
+}
+
+// Divergence theorem, positive when the triangles face outwards
+fn volume(mesh: &CpuMesh) -> f32 {
+    let (positions, indices, _) = buffers(mesh);
+    indices
+        .chunks(3)
+        .map(|t| {
+            let [a, b, c] = [0, 1, 2].map(|k| positions[t[k] as usize]);
+            a.dot(b.cross(c)) / 6.0
+        })
+        .sum()
+}
+
+fn configuration() -> TerrainConfiguration {
+    let terrain_configuration = TerrainConfiguration::new(
+        2.0,
+        1.5,
+        40000345266,
+        0.1,
+        "304630".to_string(),
+        1.0,
+        200.0,
+        21.0,
+        8,
+        0.4,
+        1.0,
+    );
+    update_configuration(
+        terrain_configuration,
+        Some(ConfigurationMessage::TerrainMode(MeshMode::Volume)),
+    )
+}
+
+#[test]
+fn solid_grid_meshes_to_its_box() {
+    let grid = grid([4, 3, 5], 0.5, |_| 1.0);
+    let mesh = volume_mesh(&grid, &Palette::shaded(COLOR), &Progress::new()).unwrap();
+    let (positions, _, _) = buffers(&mesh);
+
+    assert_closed(&mesh);
+    assert!((volume(&mesh) - 1.5 * 1.0 * 2.0).abs() < 1e-4);
+    for p in positions {
+        assert!(p.x >= 0.0 && p.y >= 0.0 && p.z >= 0.0);
+        assert!(p.x <= 1.5 && p.y <= 1.0 && p.z <= 2.0);
+    }
+}
+
+#[test]
+fn empty_grids_have_no_surface() {
+    let empty = grid([3, 3, 3], 1.0, |_| -1.0);
+    assert_eq!(
+        volume_mesh(&empty, &Palette::shaded(COLOR), &Progress::new())
+            .unwrap()
+            .vertex_count(),
+        0
+    );
+    let nothing = DensityGrid::new([0, 2, 2], 1.0, Vec::new());
+    assert_eq!(
+        volume_mesh(&nothing, &Palette::shaded(COLOR), &Progress::new())
+            .unwrap()
+            .vertex_count(),
+        0
+    );
+}
+
+#[test]
+fn sphere_is_round_and_faces_outwards() {
+    let centre = Vec3::new(1.0, 1.0, 1.0);
+    let radius = 0.7;
+    let grid = grid([21, 21, 21], 0.1, |p| radius - (p - centre).magnitude());
+    let mesh = volume_mesh(&grid, &Palette::shaded(COLOR), &Progress::new()).unwrap();
+    let (positions, _, normals) = buffers(&mesh);
+
+    assert_closed(&mesh);
+    let expected = 4.0 / 3.0 * std::f32::consts::PI * radius.powi(3);
+    assert!(
+        (volume(&mesh) / expected - 1.0).abs() < 0.03,
+        "{}",
+        volume(&mesh)
+    );
+    for (p, normal) in positions.iter().zip(normals) {
+        assert!(((p - centre).magnitude() - radius).abs() < 0.05);
+        assert!((normal.magnitude() - 1.0).abs() < 1e-5);
+        assert!(normal.dot((p - centre).normalize()) > 0.95);
+    }
+}
+
+#[test]
+fn cancelled_builds_stop() {
+    let progress = Progress::new();
+    progress.cancel();
+    let grid = grid([3, 3, 3], 1.0, |_| 1.0);
+    assert!(volume_mesh(&grid, &Palette::shaded(COLOR), &progress).is_none());
+}
+
+#[test]
+fn density_follows_the_heightmap_under_a_strong_bias() {
+    let terrain_configuration = update_configuration(
+        configuration(),
+        Some(ConfigurationMessage::TerrainVolumeVerticalBias(1000.0)),
+    );
+    let heightmap = generate_heightmap(&terrain_configuration);
+    let density = generate_density(&heightmap, &terrain_configuration);
+
+    assert_eq!(density.size, volume_size(&terrain_configuration));
+    assert_eq!(density.size[..], [20, 13, 15]);
+    let ys = density.positions(1);
+    for z in 0..density.size[2] {
+        for x in 0..density.size[0] {
+            let surface = heightmap.get(x, z);
+            for (y, &height) in ys.iter().enumerate() {
+                if (height - surface).abs() > 0.01 {
+                    assert_eq!(density.get(x, y, z) > 0.0, height < surface);
+                }
+            }
+        }
+    }
+
+    // Without room for the noise there is nothing overhanging
+    let mesh = terrain_mesh(&heightmap, &terrain_configuration);
+    let (positions, _, normals) = buffers(&mesh);
+    assert_closed(&mesh);
+    assert!(positions
+        .iter()
+        .zip(normals)
+        .all(|(p, normal)| p.y == 0.0 || normal.y > -0.5));
+}
+
+#[test]
+fn surface_is_where_columns_turn_empty() {
+    // Solid below 1.25 in the first column, a floating slab from 2.125 to 3.625 in the second
+    let columns = grid([3, 5, 1], 1.0, |p| match p.x as i32 {
+        0 => 1.25 - p.y,
+        1 => 0.75 - (p.y - 2.875).abs(),
+        _ => -1.0,
+    });
+
+    assert!((columns.surface(0, 0) - 1.25).abs() < 1e-6);
+    // Interpolated from 0.625 at 3 to -0.375 at 4
+    assert!((columns.surface(1, 0) - 3.625).abs() < 1e-6);
+    assert_eq!(columns.surface(2, 0), 0.0);
+
+    let solid = grid([1, 3, 1], 0.5, |_| 1.0);
+    assert_eq!(solid.surface(0, 0), 1.0);
+}
+
+#[test]
+fn noise_carves_caves_and_overhangs() {
+    let terrain_configuration = update_configuration(
+        configuration(),
+        Some(ConfigurationMessage::TerrainVolumeCaveFrequency(4.0)),
+    );
+    let heightmap = generate_heightmap(&terrain_configuration);
+    let mesh = terrain_mesh(&heightmap, &terrain_configuration);
+    let (positions, _, normals) = buffers(&mesh);
+
+    assert_closed(&mesh);
+    assert!(positions
+        .iter()
+        .zip(normals)
+        .any(|(p, normal)| p.y > 0.0 && normal.y < -0.5));
+
+    // A higher threshold leaves less solid
+    let carved = update_configuration(
+        terrain_configuration.clone(),
+        Some(ConfigurationMessage::TerrainVolumeThreshold(0.5)),
+    );
+    assert!(volume(&terrain_mesh(&heightmap, &carved)) < volume(&mesh));
+}
+
+#[test]
+fn progress_counts_density_and_mesh() {
+    let progress = Progress::new();
+    let (heightmap, mesh) = build_terrain(&configuration(), &progress).unwrap();
+    assert_eq!(progress.fraction(), 1.0);
+    assert_eq!(
+        buffers(&mesh).0,
+        buffers(&terrain_mesh(&heightmap, &configuration())).0
+    );
+}
+
+#[test]
+fn volume_mode_parses() {
+    assert_eq!("volume".parse(), Ok(MeshMode::Volume));
+}
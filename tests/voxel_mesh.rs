@@ -0,0 +1,248 @@
+use std::collections::{BTreeMap, HashMap};
+use terrainopensimplex2::color_ramp::Palette;
+use terrainopensimplex2::heightmap::Heightmap;
+use terrainopensimplex2::progress::Progress;
+use terrainopensimplex2::terrain_configuration::{generate_heightmap, TerrainConfiguration};
+use terrainopensimplex2::voxel_mesh::greedy_voxel_mesh;
+use three_d::{CpuMesh, Indices, InnerSpace, Positions, Srgba};
+
+const COLOR: [u8; 3] = [0x30, 0x46, 0x30];
+
+type Point = [f32; 3];
+
+fn mesh(heightmap: &Heightmap) -> CpuMesh {
+    greedy_voxel_mesh(heightmap, &Palette::shaded(COLOR), &Progress::new()).unwrap()
+}
+
+fn triangles(mesh: &CpuMesh) -> Vec<([Point; 3], Srgba)> {
+    let (Positions::F32(positions), Indices::U32(indices), Some(colors)) =
+        (&mesh.positions, &mesh.indices, &mesh.colors)
+    else {
+        panic!("the voxel mesh is built with f32 positions, u32 indices and colors");
+    };
+    indices
+        .chunks(3)
+        .map(|triangle| {
+            let corner = |k: usize| positions[triangle[k] as usize].into();
+            (
+                [corner(0), corner(1), corner(2)],
+                colors[triangle[0] as usize],
+            )
+        })
+        .collect()
+}
+
+// Stack rule of the viewer: whole cubes below one partial cube, starting one level down
+fn solid_height(height: f32, cell_size: f32) -> f32 {
+    let levels = ((height / cell_size).floor() as i32 - 1).max(0);
+    levels as f32 * cell_size + height % cell_size
+}
+
+fn terrain() -> Heightmap {
+    let terrain_configuration = TerrainConfiguration::new(
+        4.0,
+        3.0,
+        40000345266,
+        0.1,
+        "304630".to_string(),
+        2.0,
+        200.0,
+        21.0,
+        8,
+        0.4,
+        1.0,
+    );
+    generate_heightmap(&terrain_configuration)
+}
+
+/**
+    Every edge, split where other vertices lie on it, must be crossed once in each direction:
+    the faces cover the boundary with no gaps or overlaps. Merged quads still leave
+    T-junctions where they meet smaller ones, so the mesh is not watertight as an index graph.
+*/
+fn assert_surface_covered(mesh: &CpuMesh) {
+    let triangles = triangles(mesh);
+    let bits = |p: Point| p.map(f32::to_bits);
+
+    // Vertices on every axis-aligned line, keyed by the axis and the two fixed coordinates
+    let mut lines: HashMap<(usize, [u32; 2]), Vec<f32>> = HashMap::new();
+    for (triangle, _) in &triangles {
+        for p in triangle {
+            for axis in 0..3 {
+                let fixed = [p[(axis + 1) % 3], p[(axis + 2) % 3]].map(f32::to_bits);
+                lines.entry((axis, fixed)).or_default().push(p[axis]);
+            }
+        }
+    }
+    for values in lines.values_mut() {
+        values.sort_by(f32::total_cmp);
+        values.dedup();
+    }
+
+    let mut balance: BTreeMap<([u32; 3], [u32; 3]), i32> = BTreeMap::new();
+    let mut cross = |a: Point, b: Point| {
+        let (key, sign) = if bits(a) < bits(b) {
+            ((bits(a), bits(b)), 1)
+        } else {
+            ((bits(b), bits(a)), -1)
+        };
+        *balance.entry(key).or_default() += sign;
+    };
+    for (triangle, _) in &triangles {
+        for k in 0..3 {
+            let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+            let axis = (0..3).find(|&axis| {
+                a[(axis + 1) % 3] == b[(axis + 1) % 3] && a[(axis + 2) % 3] == b[(axis + 2) % 3]
+            });
+            let Some(axis) = axis else {
+                // Quad diagonals are never shared with other quads
+                cross(a, b);
+                continue;
+            };
+            let fixed = [a[(axis + 1) % 3], a[(axis + 2) % 3]].map(f32::to_bits);
+            let (lo, hi) = (a[axis].min(b[axis]), a[axis].max(b[axis]));
+            let mut stops: Vec<f32> = lines[&(axis, fixed)]
+                .iter()
+                .copied()
+                .filter(|&v| v >= lo && v <= hi)
+                .collect();
+            if a[axis] > b[axis] {
+                stops.reverse();
+            }
+            for pair in stops.windows(2) {
+                let (mut p, mut q) = (a, a);
+                p[axis] = pair[0];
+                q[axis] = pair[1];
+                cross(p, q);
+            }
+        }
+    }
+    let open: Vec<_> = balance.iter().filter(|(_, &count)| count != 0).collect();
+    assert!(
+        open.is_empty(),
+        "open edges: {:?}",
+        &open[..open.len().min(5)]
+    );
+}
+
+fn volume(mesh: &CpuMesh) -> f64 {
+    triangles(mesh)
+        .iter()
+        .map(|([a, b, c], _)| {
+            let [a, b, c] = [a, b, c].map(|p| p.map(f64::from));
+            let cross = [
+                b[1] * c[2] - b[2] * c[1],
+                b[2] * c[0] - b[0] * c[2],
+                b[0] * c[1] - b[1] * c[0],
+            ];
+            (a[0] * cross[0] + a[1] * cross[1] + a[2] * cross[2]) / 6.0
+        })
+        .sum()
+}
+
+#[test]
+fn equal_columns_merge_into_one_box() {
+    let heightmap = Heightmap::new(2, 2, 0.1, vec![0.15; 4], 0);
+    let mesh = mesh(&heightmap);
+
+    assert_eq!(triangles(&mesh).len(), 12);
+    assert_surface_covered(&mesh);
+}
+
+#[test]
+fn differently_shaded_cubes_keep_their_own_sides() {
+    // Two whole cubes and a partial top, each a different shade: a naive stack has 36 triangles
+    let heightmap = Heightmap::new(1, 1, 0.1, vec![0.35], 0);
+    let mesh = mesh(&heightmap);
+
+    assert_eq!(triangles(&mesh).len(), 2 * (4 * 3 + 2));
+    assert_surface_covered(&mesh);
+}
+
+#[test]
+fn terrain_surface_is_covered_and_small() {
+    let heightmap = terrain();
+    let mesh = mesh(&heightmap);
+    assert_surface_covered(&mesh);
+
+    let expected: f64 = heightmap
+        .heights
+        .iter()
+        .map(|&height| f64::from(solid_height(height, 0.1)) * f64::from(0.1f32) * f64::from(0.1f32))
+        .sum();
+    let volume = volume(&mesh);
+    assert!(
+        (volume - expected).abs() < 1e-4 * expected,
+        "{} {}",
+        volume,
+        expected
+    );
+
+    // Cubes per column as drawn before culling, at 12 triangles each
+    let naive: usize = heightmap
+        .heights
+        .iter()
+        .map(|&height| {
+            let levels = ((height / 0.1).floor() as usize).saturating_sub(1);
+            12 * (levels + usize::from(height % 0.1 > 0.0))
+        })
+        .sum();
+    let triangles = triangles(&mesh).len();
+    assert!(triangles * 4 < naive, "{} of {}", triangles, naive);
+}
+
+#[test]
+fn tops_match_the_column_stacks() {
+    let heightmap = terrain();
+    let triangles = triangles(&mesh(&heightmap));
+    let xs = heightmap.x_positions();
+    let zs = heightmap.z_positions();
+
+    for (j, &z) in zs.iter().enumerate() {
+        for (i, &x) in xs.iter().enumerate() {
+            let height = heightmap.get(i, j);
+            let (x, z) = (x + 0.05, z + 0.05);
+            let top = triangles
+                .iter()
+                .filter(|([a, b, c], _)| a[1] == b[1] && b[1] == c[1] && a[1] > 0.0)
+                .filter(|([a, b, c], _)| {
+                    let (x0, x1) = (a[0].min(b[0]).min(c[0]), a[0].max(b[0]).max(c[0]));
+                    let (z0, z1) = (a[2].min(b[2]).min(c[2]), a[2].max(b[2]).max(c[2]));
+                    x0 < x && x < x1 && z0 < z && z < z1
+                })
+                .map(|([a, _, _], color)| (a[1], *color))
+                .fold(None, |top: Option<(f32, Srgba)>, face| match top {
+                    Some(top) if top.0 >= face.0 => Some(top),
+                    _ => Some(face),
+                });
+
+            // The partial cube on top sets the color of the whole top face
+            let top_level = (height / 0.1).floor();
+            let t = height - (2.0 / top_level * 0.5);
+            let shade = |c: u8| (c as f32 + 0.25 + (0.45 * t) * 50.0) as u8;
+            let color = Srgba::new(shade(COLOR[0]), shade(COLOR[1]), shade(COLOR[2]), 255);
+            let (y, top_color) = top.expect("every column has a top");
+            assert!((y - solid_height(height, 0.1)).abs() < 1e-5, "{} {}", i, j);
+            assert_eq!(top_color, color, "{} {}", i, j);
+        }
+    }
+}
+
+#[test]
+fn normals_face_out_of_every_quad() {
+    let mesh = mesh(&terrain());
+    let (Positions::F32(positions), Indices::U32(indices), Some(normals)) =
+        (&mesh.positions, &mesh.indices, &mesh.normals)
+    else {
+        panic!("the voxel mesh is built with normals");
+    };
+    assert_eq!(normals.len(), positions.len());
+
+    for triangle in indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| positions[triangle[k] as usize]);
+        let face = (b - a).cross(c - a).normalize();
+        for &k in triangle {
+            assert!((normals[k as usize] - face).magnitude() < 1e-5);
+        }
+    }
+}
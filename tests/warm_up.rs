@@ -0,0 +1,36 @@
+use std::sync::{Arc, Barrier};
+use std::thread;
+use terrainopensimplex2::{fast, smooth};
+
+fn sample(seed: i64) -> [f32; 4] {
+    [
+        fast::noise2(seed, 1.5, -2.25),
+        fast::noise4_Fallback(seed, 0.5, 1.5, 2.5, 3.5),
+        smooth::noise3_ImproveXZ(seed, 4.0, -1.0, 0.75),
+        smooth::noise4_ImproveXYZ(seed, -0.5, 9.0, 1.25, 2.0),
+    ]
+}
+
+#[test]
+fn first_use_from_many_threads_agrees() {
+    // Nothing has touched the tables yet in this test binary, so the threads race to build them.
+    let threads = 16;
+    let barrier = Arc::new(Barrier::new(threads));
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                sample(1234)
+            })
+        })
+        .collect();
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    fast::warm_up();
+    smooth::warm_up();
+    let expected = sample(1234);
+    for result in results {
+        assert_eq!(result, expected);
+    }
+}
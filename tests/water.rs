@@ -0,0 +1,219 @@
+use terrainopensimplex2::configuration::ConfigurationMessage;
+use terrainopensimplex2::heightmap::Heightmap;
+use terrainopensimplex2::mesh_mode::MeshMode;
+use terrainopensimplex2::terrain_configuration::{
+    generate_heightmap, terrain_mesh, terrain_stats, terrain_surface, update_configuration,
+    water_surface, TerrainConfiguration,
+};
+use terrainopensimplex2::voxel_mesh::column_top;
+use terrainopensimplex2::water::TerrainStats;
+use three_d::{Indices, Positions, Srgba, Vec3};
+
+fn configuration(mode: MeshMode, level: f32) -> TerrainConfiguration {
+    let terrain_configuration = TerrainConfiguration::new(
+        2.0,
+        2.0,
+        40000345266,
+        0.1,
+        "304630".to_string(),
+        2.0,
+        200.0,
+        21.0,
+        8,
+        0.4,
+        1.0,
+    );
+    let terrain_configuration = update_configuration(
+        terrain_configuration,
+        Some(ConfigurationMessage::TerrainMode(mode)),
+    );
+    update_configuration(
+        terrain_configuration,
+        Some(ConfigurationMessage::TerrainWaterLevel(level)),
+    )
+}
+
+fn positions(positions: &Positions) -> Vec<Vec3> {
+    match positions {
+        Positions::F32(positions) => positions.clone(),
+        Positions::F64(_) => panic!("meshes are built with f32 positions"),
+    }
+}
+
+#[test]
+fn stats_count_land_and_coastline() {
+    // A 2x2 island in the middle of a 4x4 sea
+    #[rustfmt::skip]
+    let heights = vec![
+        0.1, 0.1, 0.1, 0.1,
+        0.1, 1.0, 1.0, 0.1,
+        0.1, 1.0, 1.0, 0.1,
+        0.1, 0.1, 0.1, 0.1,
+    ];
+    let heightmap = Heightmap::new(4, 4, 0.5, heights, 0);
+
+    let stats = TerrainStats::new(&heightmap, 0.5);
+    assert_eq!(stats.sea_level, 0.5);
+    assert_eq!(stats.land_share, 0.25);
+    assert_eq!(stats.coastline_length, 8.0 * 0.5);
+
+    // The edges of the map are not coastline
+    let dry = TerrainStats::new(&heightmap, 0.0);
+    assert_eq!((dry.land_share, dry.coastline_length), (1.0, 0.0));
+    // Columns level with the sea are land, as they are not colored as seabed
+    let level = TerrainStats::new(&heightmap, 1.0);
+    assert_eq!(level.land_share, 0.25);
+    let flooded = TerrainStats::new(&heightmap, 1.5);
+    assert_eq!((flooded.land_share, flooded.coastline_length), (0.0, 0.0));
+
+    let empty = TerrainStats::new(&Heightmap::new(0, 0, 0.5, vec![], 0), 0.5);
+    assert_eq!((empty.land_share, empty.coastline_length), (0.0, 0.0));
+}
+
+#[test]
+fn configured_sea_level_drives_the_stats() {
+    let terrain_configuration = configuration(MeshMode::Voxels, 0.5);
+    let heightmap = generate_heightmap(&terrain_configuration);
+    let stats = terrain_stats(&heightmap, &terrain_configuration);
+
+    assert_eq!(
+        stats,
+        TerrainStats::new(&terrain_surface(&heightmap, &terrain_configuration), 0.5)
+    );
+    // The falloff makes an island: some land, some sea, and a shore between them
+    assert!(stats.land_share > 0.0 && stats.land_share < 1.0);
+    assert!(stats.coastline_length > 0.0);
+}
+
+#[test]
+fn no_water_by_default() {
+    let terrain_configuration = configuration(MeshMode::Voxels, 0.0);
+    assert!(water_surface(&terrain_configuration).is_none());
+
+    let dry = terrain_stats(
+        &generate_heightmap(&terrain_configuration),
+        &terrain_configuration,
+    );
+    assert_eq!(dry.land_share, 1.0);
+}
+
+#[test]
+fn water_surface_covers_the_terrain() {
+    // 20 columns of 0.1: cubes reach 2.0, smooth surfaces end at the last column origin
+    for (mode, extent) in [
+        (MeshMode::Voxels, 2.0),
+        (MeshMode::Heightfield, 1.9),
+        (MeshMode::Volume, 1.9),
+    ] {
+        let water = water_surface(&configuration(mode, 0.5)).unwrap();
+        let corners = positions(&water.positions);
+
+        assert!(corners.iter().all(|p| p.y == 0.5), "{:?}", mode);
+        let max = |axis: fn(&Vec3) -> f32| corners.iter().map(axis).fold(0.0, f32::max);
+        assert!((max(|p| p.x) - extent).abs() < 1e-4, "{:?}", mode);
+        assert!((max(|p| p.z) - extent).abs() < 1e-4, "{:?}", mode);
+        assert!(water
+            .normals
+            .unwrap()
+            .iter()
+            .all(|n| *n == Vec3::new(0.0, 1.0, 0.0)));
+    }
+}
+
+#[test]
+fn terrain_below_the_sea_is_seabed() {
+    let seabed = Srgba::new(0x8c, 0x7a, 0x5b, 255);
+    for mode in [MeshMode::Heightfield, MeshMode::Volume] {
+        let terrain_configuration = configuration(mode, 0.5);
+        let heightmap = generate_heightmap(&terrain_configuration);
+        let mesh = terrain_mesh(&heightmap, &terrain_configuration);
+        let colors = mesh.colors.unwrap();
+
+        for (p, color) in positions(&mesh.positions).iter().zip(&colors) {
+            assert_eq!(p.y < 0.5, *color == seabed, "{:?} at {:?}", mode, p);
+        }
+    }
+
+    // Cubes are seabed when their top is under water
+    let terrain_configuration = configuration(MeshMode::Voxels, 0.5);
+    let heightmap = generate_heightmap(&terrain_configuration);
+    let mesh = terrain_mesh(&heightmap, &terrain_configuration);
+    let colors = mesh.colors.unwrap();
+    assert!(colors.contains(&seabed));
+    assert!(colors.iter().any(|&c| c != seabed));
+    let positions = positions(&mesh.positions);
+    for (p, color) in positions.iter().zip(&colors) {
+        if p.y > 0.5 + 1e-4 {
+            assert_ne!(*color, seabed, "{:?}", p);
+        }
+    }
+
+    // Every column shows exactly one face upwards, its top, so the upward area that is not
+    // seabed is the land
+    let Indices::U32(indices) = &mesh.indices else {
+        panic!("the voxel mesh is built with u32 indices");
+    };
+    let (mut up, mut land) = (0.0, 0.0);
+    for triangle in indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| positions[triangle[k] as usize]);
+        let normal = (b - a).cross(c - a);
+        if normal.y > 0.0 && normal.x == 0.0 && normal.z == 0.0 {
+            up += normal.y / 2.0;
+            if colors[triangle[0] as usize] != seabed {
+                land += normal.y / 2.0;
+            }
+        }
+    }
+    assert!((up - 4.0).abs() < 1e-3, "{}", up);
+    let stats = terrain_stats(&heightmap, &terrain_configuration);
+    assert!((land / up - stats.land_share).abs() < 1e-4);
+    // Columns drawn under water although their height is above the sea level
+    let raised = heightmap.heights.iter().filter(|&&h| h >= 0.5).count();
+    assert!(stats.land_share < raised as f32 / heightmap.heights.len() as f32);
+}
+
+#[test]
+fn the_surface_is_what_each_mode_draws() {
+    let terrain_configuration = configuration(MeshMode::Voxels, 0.5);
+    let heightmap = generate_heightmap(&terrain_configuration);
+    let voxels = terrain_surface(&heightmap, &terrain_configuration);
+    for (&top, &height) in voxels.heights.iter().zip(&heightmap.heights) {
+        assert_eq!(top, column_top(height, heightmap.cell_size));
+        assert!(top < height);
+    }
+
+    let terrain_configuration = configuration(MeshMode::Heightfield, 0.5);
+    let heightfield = terrain_surface(&heightmap, &terrain_configuration);
+    assert_eq!(heightfield, heightmap);
+
+    // The first width * depth vertices are the surface, then the skirts
+    let colors = terrain_mesh(&heightmap, &terrain_configuration)
+        .colors
+        .unwrap();
+    let seabed = Srgba::new(0x8c, 0x7a, 0x5b, 255);
+    let land = colors[..heightmap.heights.len()]
+        .iter()
+        .filter(|&&c| c != seabed)
+        .count();
+    assert_eq!(
+        terrain_stats(&heightmap, &terrain_configuration).land_share,
+        land as f32 / heightmap.heights.len() as f32
+    );
+}
+
+#[test]
+fn seabed_color_is_configurable() {
+    let terrain_configuration = update_configuration(
+        configuration(MeshMode::Heightfield, 0.5),
+        Some(ConfigurationMessage::TerrainWaterSeabedColor(
+            "102030".to_string(),
+        )),
+    );
+    let heightmap = generate_heightmap(&terrain_configuration);
+    let mesh = terrain_mesh(&heightmap, &terrain_configuration);
+
+    assert!(mesh
+        .colors
+        .unwrap()
+        .contains(&Srgba::new(0x10, 0x20, 0x30, 255)));
+}
@@ -0,0 +1,90 @@
+#![cfg(target_arch = "wasm32")]
+
+// Run with: wasm-pack test --headless --firefox
+use std::sync::Arc;
+use terrainopensimplex2::configuration::ConfigurationMessage;
+use terrainopensimplex2::mesh_mode::MeshMode;
+use terrainopensimplex2::terrain_configuration::{
+    generate_heightmap, terrain_stats, update_configuration, TerrainConfiguration,
+};
+use terrainopensimplex2::terrain_worker::{TerrainStatus, TerrainWorker};
+use terrainopensimplex2::web::{connect, send, stats, status};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn send_forwards_to_the_viewer() {
+    let rx = connect();
+    send("http://localhost:8090/api/terrain/seed", "42").unwrap();
+    send("/api/camera/position/x", "3.5").unwrap();
+    assert!(matches!(
+        rx.try_recv(),
+        Ok(ConfigurationMessage::TerrainSeed(42))
+    ));
+    assert!(matches!(
+        rx.try_recv(),
+        Ok(ConfigurationMessage::CameraPositionX(x)) if x == 3.5
+    ));
+    assert!(rx.try_recv().is_err());
+}
+
+#[wasm_bindgen_test]
+fn send_rejects_invalid_values() {
+    let rx = connect();
+    assert!(send("/api/terrain/cubesize", "-1").is_err());
+    assert!(send("/api/terrain/missing", "1").is_err());
+    assert!(rx.try_recv().is_err());
+}
+
+#[wasm_bindgen_test]
+fn status_and_stats_start_empty() {
+    assert_eq!(status(), r#"{"requested":0,"ready":0,"progress":null}"#);
+    assert_eq!(stats(), "null");
+}
+
+// No threads in the browser: every mode builds on the calling thread, rayon included
+#[wasm_bindgen_test]
+fn worker_builds_on_the_calling_thread() {
+    for mode in [MeshMode::Voxels, MeshMode::Heightfield, MeshMode::Volume] {
+        let terrain_configuration = [
+            ConfigurationMessage::TerrainMode(mode),
+            ConfigurationMessage::TerrainThreads(2),
+            ConfigurationMessage::TerrainWaterLevel(0.5),
+        ]
+        .into_iter()
+        .fold(
+            TerrainConfiguration::new(
+                2.0,
+                2.0,
+                40000345266,
+                0.1,
+                "304630".to_string(),
+                2.0,
+                200.0,
+                21.0,
+                8,
+                0.4,
+                1.0,
+            ),
+            |terrain_configuration, msg| update_configuration(terrain_configuration, Some(msg)),
+        );
+
+        let worker = TerrainWorker::new(Arc::new(TerrainStatus::new()));
+        worker.submit(terrain_configuration.clone());
+
+        assert!(worker.try_mesh().is_some(), "{:?}", mode);
+        let report = worker.status().report();
+        assert_eq!(
+            (report.requested, report.ready, report.progress),
+            (1, 1, None)
+        );
+        assert_eq!(
+            worker.status().stats(),
+            Some(terrain_stats(
+                &generate_heightmap(&terrain_configuration),
+                &terrain_configuration
+            ))
+        );
+    }
+}